@@ -0,0 +1,264 @@
+//! `did:key` and `did:pkh` forms of the keys registered on a DID.
+//!
+//! A registered key can be named on its own by the `did:key` identifier derived from its
+//! `public_key_multibase`, and a blockchain account by a `did:pkh` identifier. Listing them
+//! under `alsoKnownAs` links the registry DID to those identifiers; parsing them back gives
+//! the verification method they stand for, to compare with the registered ones.
+
+use libsecp256k1::{PublicKey, PublicKeyFormat};
+use multibase::Base;
+
+pub use verifiable_data_registry::utils::P256_KEY_TYPE;
+use verifiable_data_registry::utils::{
+    curve25519_key, p256_public_key, ED25519_MULTICODEC_PREFIX, P256_MULTICODEC_PREFIX,
+};
+
+use crate::document::VerificationMethod;
+use crate::encryption::{x25519_public_key, X25519_KEY_TYPE, X25519_MULTICODEC_PREFIX};
+use crate::error::ResolverError;
+
+pub const ED25519_KEY_TYPE: &str = "Ed25519VerificationKey2020";
+pub const SOLANA_KEY_TYPE: &str = "SolanaMethod2021";
+pub const SECP256K1_KEY_TYPE: &str = "EcdsaSecp256k1VerificationKey2019";
+/// Type of `did:pkh` Ethereum accounts, whose key is only known from a signature.
+pub const SECP256K1_RECOVERY_KEY_TYPE: &str = "EcdsaSecp256k1RecoveryMethod2020";
+
+const SECP256K1_MULTICODEC_PREFIX: [u8; 2] = [0xe7, 0x01];
+
+/// `did:key` identifier of a key registered with `r#type` and `public_key_multibase`.
+/// Secp256k1 keys are compressed, as `did:key` requires; P-256 keys are registered so.
+pub fn did_key(r#type: &str, public_key_multibase: &str) -> Result<String, ResolverError> {
+    let invalid = || ResolverError::InvalidPublicKey(public_key_multibase.to_string());
+    let (prefix, key) = match r#type {
+        ED25519_KEY_TYPE | SOLANA_KEY_TYPE => {
+            let key = curve25519_key(public_key_multibase, ED25519_MULTICODEC_PREFIX)
+                .ok_or_else(invalid)?;
+            (ED25519_MULTICODEC_PREFIX, key.to_vec())
+        }
+        X25519_KEY_TYPE => {
+            let key = x25519_public_key(public_key_multibase).ok_or_else(invalid)?;
+            (X25519_MULTICODEC_PREFIX, key.to_vec())
+        }
+        SECP256K1_KEY_TYPE => {
+            let (_, bytes) = multibase::decode(public_key_multibase).map_err(|_| invalid())?;
+            let key = PublicKey::parse_slice(&bytes, None).map_err(|_| invalid())?;
+            (
+                SECP256K1_MULTICODEC_PREFIX,
+                key.serialize_compressed().to_vec(),
+            )
+        }
+        P256_KEY_TYPE => {
+            let key = p256_public_key(public_key_multibase).ok_or_else(invalid)?;
+            (P256_MULTICODEC_PREFIX, key.to_vec())
+        }
+        _ => return Err(ResolverError::UnsupportedKeyType(r#type.to_string())),
+    };
+    Ok(format!(
+        "did:key:{}",
+        multibase::encode(Base::Base58Btc, [prefix.as_slice(), &key].concat())
+    ))
+}
+
+/// Verification method a `did:key` or `did:pkh` identifier stands for. Secp256k1 keys are
+/// given in the form the registry stores them, `f` and the hex of the uncompressed key.
+pub fn verification_method(did: &str) -> Result<VerificationMethod, ResolverError> {
+    if let Some(multibase_key) = did.strip_prefix("did:key:") {
+        did_key_method(did, multibase_key)
+    } else if let Some(account_id) = did.strip_prefix("did:pkh:") {
+        did_pkh_method(did, account_id)
+    } else if did.starts_with("did:") {
+        Err(ResolverError::MethodNotSupported(did.to_string()))
+    } else {
+        Err(ResolverError::InvalidDid(did.to_string()))
+    }
+}
+
+fn did_key_method(did: &str, multibase_key: &str) -> Result<VerificationMethod, ResolverError> {
+    let invalid = || ResolverError::InvalidDid(did.to_string());
+    let (base, bytes) = multibase::decode(multibase_key).map_err(|_| invalid())?;
+    if base != Base::Base58Btc || bytes.len() < 2 {
+        return Err(invalid());
+    }
+    let (prefix, key) = bytes.split_at(2);
+    let (r#type, public_key_multibase) = match [prefix[0], prefix[1]] {
+        ED25519_MULTICODEC_PREFIX if key.len() == 32 => (ED25519_KEY_TYPE, multibase_key.into()),
+        X25519_MULTICODEC_PREFIX if key.len() == 32 => (X25519_KEY_TYPE, multibase_key.into()),
+        P256_MULTICODEC_PREFIX if key.len() == 33 => (P256_KEY_TYPE, multibase_key.into()),
+        SECP256K1_MULTICODEC_PREFIX => {
+            let key = PublicKey::parse_slice(key, Some(PublicKeyFormat::Compressed))
+                .map_err(|_| invalid())?;
+            let public_key_multibase = multibase::encode(Base::Base16Lower, &key.serialize()[1..]);
+            (SECP256K1_KEY_TYPE, public_key_multibase)
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(VerificationMethod {
+        id: format!("{did}#{multibase_key}"),
+        r#type: r#type.to_string(),
+        controller: did.to_string(),
+        public_key_multibase: Some(public_key_multibase),
+        blockchain_account_id: None,
+        valid_from: None,
+        valid_until: None,
+    })
+}
+
+// `account_id` is a CAIP-10 account id: `<namespace>:<chain reference>:<address>`.
+fn did_pkh_method(did: &str, account_id: &str) -> Result<VerificationMethod, ResolverError> {
+    let invalid = || ResolverError::InvalidDid(did.to_string());
+    let mut parts = account_id.splitn(3, ':');
+    let (namespace, reference, address) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(namespace), Some(reference), Some(address)) => (namespace, reference, address),
+        _ => return Err(invalid()),
+    };
+    if reference.is_empty()
+        || reference.len() > 32
+        || !reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(invalid());
+    }
+
+    let (r#type, public_key_multibase) = match namespace {
+        // Solana addresses are the ed25519 public key itself.
+        "solana" => {
+            let public_key_multibase = format!("z{address}");
+            match multibase::decode(&public_key_multibase) {
+                Ok((_, key)) if key.len() == 32 => (SOLANA_KEY_TYPE, Some(public_key_multibase)),
+                _ => return Err(invalid()),
+            }
+        }
+        "eip155" => {
+            let hex = address.strip_prefix("0x").ok_or_else(invalid)?;
+            if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            (SECP256K1_RECOVERY_KEY_TYPE, None)
+        }
+        _ => return Err(ResolverError::MethodNotSupported(did.to_string())),
+    };
+    Ok(VerificationMethod {
+        id: format!("{did}#blockchainAccountId"),
+        r#type: r#type.to_string(),
+        controller: did.to_string(),
+        public_key_multibase,
+        blockchain_account_id: Some(account_id.to_string()),
+        valid_from: None,
+        valid_until: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use registry_sdk::Secp256k1Signer;
+
+    use super::*;
+    use crate::encryption::x25519_public_key_multibase;
+
+    const SOLANA_ADDRESS: &str = "CKg5d12Jhpej1JqtmxLJgaFqqeYjxgPqToJ4LBdvG9Ev";
+
+    #[test]
+    fn test_did_key_round_trip() {
+        let secp256k1 = Secp256k1Signer::from_bytes(&[1u8; 32])
+            .unwrap()
+            .public_key_multibase();
+        for (r#type, public_key_multibase, did_prefix) in [
+            (
+                SOLANA_KEY_TYPE,
+                format!("z{SOLANA_ADDRESS}"),
+                "did:key:z6Mk",
+            ),
+            (
+                X25519_KEY_TYPE,
+                x25519_public_key_multibase(&[7u8; 32]),
+                "did:key:z6LS",
+            ),
+            (SECP256K1_KEY_TYPE, secp256k1, "did:key:zQ3s"),
+            (
+                P256_KEY_TYPE,
+                format!("f02{}", "11".repeat(32)),
+                "did:key:zDn",
+            ),
+        ] {
+            let did = did_key(r#type, &public_key_multibase).unwrap();
+            assert!(did.starts_with(did_prefix), "{did}");
+
+            let method = verification_method(&did).unwrap();
+            assert_eq!(method.controller, did);
+            assert_eq!(method.id, format!("{did}#{}", &did["did:key:".len()..]));
+            let expected_type = if r#type == SOLANA_KEY_TYPE {
+                ED25519_KEY_TYPE
+            } else {
+                r#type
+            };
+            assert_eq!(method.r#type, expected_type);
+            // Parsed keys derive the same identifier back.
+            let parsed_key = method.public_key_multibase.unwrap();
+            assert_eq!(did_key(&method.r#type, &parsed_key).unwrap(), did);
+            if r#type == SECP256K1_KEY_TYPE {
+                assert_eq!(parsed_key, public_key_multibase);
+            }
+        }
+    }
+
+    #[test]
+    fn test_did_key_rejects_unknown_keys() {
+        assert!(matches!(
+            did_key("Bls12381G2Key2020", "fabcd"),
+            Err(ResolverError::UnsupportedKeyType(_))
+        ));
+        assert!(matches!(
+            did_key(SECP256K1_KEY_TYPE, "fabcd"),
+            Err(ResolverError::InvalidPublicKey(_))
+        ));
+        assert!(matches!(
+            verification_method("did:key:fabcd"),
+            Err(ResolverError::InvalidDid(_))
+        ));
+        assert!(matches!(
+            verification_method("did:zuni:solana:abc"),
+            Err(ResolverError::MethodNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_did_pkh_methods() {
+        let did = format!("did:pkh:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:{SOLANA_ADDRESS}");
+        let method = verification_method(&did).unwrap();
+        assert_eq!(method.id, format!("{did}#blockchainAccountId"));
+        assert_eq!(method.r#type, SOLANA_KEY_TYPE);
+        assert_eq!(
+            method.blockchain_account_id.as_deref(),
+            Some(&did["did:pkh:".len()..])
+        );
+        assert_eq!(
+            did_key(&method.r#type, &method.public_key_multibase.unwrap()).unwrap(),
+            did_key(SOLANA_KEY_TYPE, &format!("z{SOLANA_ADDRESS}")).unwrap()
+        );
+
+        let did = "did:pkh:eip155:1:0xb9c5714089478a327f09197987f16f9e5d936e8a";
+        let method = verification_method(did).unwrap();
+        assert_eq!(method.r#type, SECP256K1_RECOVERY_KEY_TYPE);
+        assert_eq!(method.public_key_multibase, None);
+        assert_eq!(
+            method.blockchain_account_id.as_deref(),
+            Some("eip155:1:0xb9c5714089478a327f09197987f16f9e5d936e8a")
+        );
+
+        for did in [
+            "did:pkh:eip155:1:b9c5714089478a327f09197987f16f9e5d936e8a",
+            "did:pkh:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:0xb9c5",
+            "did:pkh:eip155:0xb9c5714089478a327f09197987f16f9e5d936e8a",
+        ] {
+            assert!(
+                matches!(verification_method(did), Err(ResolverError::InvalidDid(_))),
+                "{did}"
+            );
+        }
+        assert!(matches!(
+            verification_method("did:pkh:bip122:000000000019d6689c085ae165831e93:1A1zP1"),
+            Err(ResolverError::MethodNotSupported(_))
+        ));
+    }
+}
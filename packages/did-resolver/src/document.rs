@@ -0,0 +1,193 @@
+use anchor_lang::prelude::Pubkey;
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use verifiable_data_registry::state;
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const DID_RESOLUTION_CONTENT_TYPE: &str = "application/did+ld+json";
+
+/// DID document in the W3C DID Core JSON-LD representation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verification_method: Vec<VerificationMethod>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_agreement: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub controller: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+    /// CAIP-10 account of `did:pkh` methods, whose key is not known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockchain_account_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMetadata {
+    pub deactivated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+}
+
+/// Point in the history of a DID to resolve it at, the `versionId` and `versionTime` of DID
+/// Resolution with slots standing in for time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocumentVersion {
+    /// Document right after the change numbered `versionId` in the DID change log.
+    VersionId(u64),
+    /// Document as it was at the end of the slot.
+    Slot(u64),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionResult {
+    pub did_document: Option<DidDocument>,
+    pub did_resolution_metadata: ResolutionMetadata,
+    pub did_document_metadata: DocumentMetadata,
+}
+
+/// On-chain accounts that together make up one DID.
+#[derive(Clone, Debug)]
+pub struct RegistryDid {
+    pub document: state::DidDocument,
+    pub methods: Vec<state::VerificationMethod>,
+    pub relationships: Vec<state::VerificationRelationship>,
+    /// `versionId` of the accounts, unknown for DIDs without a change log.
+    pub version_id: Option<u64>,
+}
+
+impl RegistryDid {
+    /// Drops what `change` added. The controller is not part of the resolved document, so
+    /// controller changes are left as they are; `alsoKnownAs` is not logged and stays current.
+    pub fn undo(&mut self, change: &state::DidChange) {
+        match &change.op {
+            state::DidChangeOp::VerificationMethodAdded => {
+                self.methods.retain(|method| method.key_id != change.key_id);
+            }
+            state::DidChangeOp::VerificationRelationshipAdded { relationship } => {
+                self.relationships
+                    .retain(|r| r.relationship != *relationship || r.key_id != change.key_id);
+            }
+            state::DidChangeOp::Created | state::DidChangeOp::ControllerTransferred => {}
+        }
+    }
+
+    /// The program has no deactivation of its own: a DID handed over to the default key, which
+    /// no one can sign for, can never be changed again.
+    pub fn is_deactivated(&self) -> bool {
+        self.document.controller == Pubkey::default()
+    }
+
+    pub fn to_did_document(&self) -> DidDocument {
+        let did = &self.document.did;
+        let mut methods: Vec<VerificationMethod> = self
+            .methods
+            .iter()
+            .filter(|method| method.did == *did)
+            .map(|method| VerificationMethod {
+                id: method_id(did, &method.key_id),
+                r#type: method.r#type.clone(),
+                controller: did.clone(),
+                public_key_multibase: Some(method.public_key_multibase.clone()),
+                blockchain_account_id: None,
+                valid_from: method.valid_from.and_then(format_timestamp),
+                valid_until: method.valid_until.and_then(format_timestamp),
+            })
+            .collect();
+        methods.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut context = vec![DID_CONTEXT.to_string()];
+        for method in &methods {
+            if let Some(type_context) = context_for_type(&method.r#type) {
+                if !context.iter().any(|c| c == type_context) {
+                    context.push(type_context.to_string());
+                }
+            }
+        }
+
+        let references = |relationship: state::Relationship| -> Vec<String> {
+            let mut ids: Vec<String> = self
+                .relationships
+                .iter()
+                .filter(|r| r.did == *did && r.relationship == relationship)
+                .map(|r| method_id(did, &r.key_id))
+                .collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        };
+
+        DidDocument {
+            context,
+            id: did.clone(),
+            also_known_as: self.document.also_known_as.clone(),
+            verification_method: methods,
+            authentication: references(state::Relationship::Authentication),
+            assertion_method: references(state::Relationship::Assertion),
+            key_agreement: references(state::Relationship::KeyAgreement),
+        }
+    }
+}
+
+/// Absolute DID URL of a verification method. Key ids are stored either as full DID URLs
+/// (`did:zuni:solana:abc#key-0`), as fragments (`#key-0`) or as bare names (`key-0`).
+pub fn method_id(did: &str, key_id: &str) -> String {
+    if key_id.starts_with(did) {
+        key_id.to_string()
+    } else if let Some(fragment) = key_id.strip_prefix('#') {
+        format!("{did}#{fragment}")
+    } else {
+        format!("{did}#{key_id}")
+    }
+}
+
+fn format_timestamp(millis: u64) -> Option<String> {
+    let millis = i64::try_from(millis).ok()?;
+    DateTime::from_timestamp_millis(millis)
+        .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn context_for_type(r#type: &str) -> Option<&'static str> {
+    match r#type {
+        "EcdsaSecp256k1VerificationKey2019" => {
+            Some("https://w3id.org/security/suites/secp256k1-2019/v1")
+        }
+        "Ed25519VerificationKey2018" => Some("https://w3id.org/security/suites/ed25519-2018/v1"),
+        "Ed25519VerificationKey2020" => Some("https://w3id.org/security/suites/ed25519-2020/v1"),
+        "X25519KeyAgreementKey2020" => Some("https://w3id.org/security/suites/x25519-2020/v1"),
+        "JsonWebKey2020" => Some("https://w3id.org/security/suites/jws-2020/v1"),
+        _ => None,
+    }
+}
@@ -0,0 +1,248 @@
+//! Encryption of messages, such as credentials, to the `keyAgreement` key of a DID.
+//!
+//! The sender agrees on a secret with the recipient's `X25519KeyAgreementKey2020` key from an
+//! ephemeral X25519 key (ECDH-ES), derives an AES-256 key from it with HKDF-SHA256, and seals
+//! the message with AES-256-GCM-SIV. The recipient key id is authenticated along with it.
+
+use aes_gcm_siv::aead::{Aead, NewAead, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Key, Nonce};
+use chrono::DateTime;
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+use verifiable_data_registry::state::is_within_validity_window;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+// Keys are read as the program checks them when they are bound to `keyAgreement`.
+pub use verifiable_data_registry::utils::{
+    x25519_public_key, X25519_KEY_TYPE, X25519_MULTICODEC_PREFIX,
+};
+
+use crate::document::{DidDocument, VerificationMethod};
+use crate::error::ResolverError;
+
+const KDF_INFO: &[u8] = b"zuni-did x25519 aes-256-gcm-siv";
+
+/// Message sealed for the `recipient` key agreement method of a DID.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedMessage {
+    pub recipient: String, // absolute id of the verification method
+    pub ephemeral_public_key: [u8; 32],
+    pub nonce: [u8; 12],
+    pub ciphertext: Vec<u8>,
+}
+
+/// Value to register as `public_key_multibase` for the X25519 key of `secret_key`.
+pub fn x25519_public_key_multibase(secret_key: &[u8; 32]) -> String {
+    let public_key = PublicKey::from(&StaticSecret::from(*secret_key));
+    multibase::encode(
+        multibase::Base::Base58Btc,
+        [X25519_MULTICODEC_PREFIX.as_slice(), public_key.as_bytes()].concat(),
+    )
+}
+
+/// First X25519 method of `document` listed under `keyAgreement` and valid at `now`, in
+/// milliseconds, with its public key.
+pub fn key_agreement_key(
+    document: &DidDocument,
+    now: u64,
+) -> Option<(&VerificationMethod, [u8; 32])> {
+    document.key_agreement.iter().find_map(|id| {
+        let method = document.verification_method.iter().find(|method| {
+            method.id == *id && method.r#type == X25519_KEY_TYPE && is_valid_at(method, now)
+        })?;
+        Some((
+            method,
+            x25519_public_key(method.public_key_multibase.as_deref()?)?,
+        ))
+    })
+}
+
+/// Seals `plaintext` for the key agreement key of `document`.
+pub fn encrypt(
+    document: &DidDocument,
+    plaintext: &[u8],
+) -> Result<EncryptedMessage, ResolverError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| ResolverError::Encryption)?
+        .as_millis() as u64;
+    let (method, recipient_key) = key_agreement_key(document, now)
+        .ok_or_else(|| ResolverError::NoKeyAgreementKey(document.id.clone()))?;
+    let recipient_key = PublicKey::from(recipient_key);
+
+    let ephemeral_secret = EphemeralSecret::new(OsRng);
+    let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+    let shared_secret = ephemeral_secret.diffie_hellman(&recipient_key);
+    let cipher = cipher(
+        shared_secret.as_bytes(),
+        ephemeral_public_key.as_bytes(),
+        recipient_key.as_bytes(),
+    );
+
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: plaintext,
+                aad: method.id.as_bytes(),
+            },
+        )
+        .map_err(|_| ResolverError::Encryption)?;
+
+    Ok(EncryptedMessage {
+        recipient: method.id.clone(),
+        ephemeral_public_key: ephemeral_public_key.to_bytes(),
+        nonce,
+        ciphertext,
+    })
+}
+
+/// Opens `message` with the X25519 `secret_key` of its recipient method.
+pub fn decrypt(
+    secret_key: &[u8; 32],
+    message: &EncryptedMessage,
+) -> Result<Vec<u8>, ResolverError> {
+    let secret_key = StaticSecret::from(*secret_key);
+    let recipient_key = PublicKey::from(&secret_key);
+    let shared_secret = secret_key.diffie_hellman(&PublicKey::from(message.ephemeral_public_key));
+    let cipher = cipher(
+        shared_secret.as_bytes(),
+        &message.ephemeral_public_key,
+        recipient_key.as_bytes(),
+    );
+    cipher
+        .decrypt(
+            &Nonce::from(message.nonce),
+            Payload {
+                msg: &message.ciphertext,
+                aad: message.recipient.as_bytes(),
+            },
+        )
+        .map_err(|_| ResolverError::Encryption)
+}
+
+// Same window the program checks keys against, read back from the resolved timestamps. A
+// timestamp that does not parse leaves the method out rather than treating it as unbounded.
+fn is_valid_at(method: &VerificationMethod, now: u64) -> bool {
+    let parse = |timestamp: &Option<String>| match timestamp {
+        Some(timestamp) => DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .and_then(|datetime| u64::try_from(datetime.timestamp_millis()).ok())
+            .map(Some),
+        None => Some(None),
+    };
+    match (parse(&method.valid_from), parse(&method.valid_until)) {
+        (Some(valid_from), Some(valid_until)) => {
+            is_within_validity_window(valid_from, valid_until, now)
+        }
+        _ => false,
+    }
+}
+
+// Both public keys are mixed in so a key only ever seals messages between the same pair.
+fn cipher(
+    shared_secret: &[u8; 32],
+    ephemeral_key: &[u8; 32],
+    recipient_key: &[u8; 32],
+) -> Aes256GcmSiv {
+    let salt = [ephemeral_key.as_slice(), recipient_key.as_slice()].concat();
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(KDF_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Aes256GcmSiv::new(&Key::from(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DID_CONTEXT;
+
+    const DID: &str = "did:zuni:solana:holder";
+
+    fn document(secret_key: &[u8; 32]) -> DidDocument {
+        DidDocument {
+            context: vec![DID_CONTEXT.to_string()],
+            id: DID.to_string(),
+            also_known_as: Vec::new(),
+            verification_method: vec![
+                VerificationMethod {
+                    id: format!("{DID}#key-0"),
+                    r#type: "Ed25519VerificationKey2018".to_string(),
+                    controller: DID.to_string(),
+                    public_key_multibase: Some(x25519_public_key_multibase(&[9u8; 32])),
+                    blockchain_account_id: None,
+                    valid_from: None,
+                    valid_until: None,
+                },
+                VerificationMethod {
+                    id: format!("{DID}#key-1"),
+                    r#type: X25519_KEY_TYPE.to_string(),
+                    controller: DID.to_string(),
+                    public_key_multibase: Some(x25519_public_key_multibase(secret_key)),
+                    blockchain_account_id: None,
+                    valid_from: None,
+                    valid_until: None,
+                },
+            ],
+            authentication: vec![format!("{DID}#key-0")],
+            assertion_method: Vec::new(),
+            key_agreement: vec![format!("{DID}#key-0"), format!("{DID}#key-1")],
+        }
+    }
+
+    #[test]
+    fn test_encrypt_to_key_agreement_key() {
+        let secret_key = [7u8; 32];
+        let message = encrypt(&document(&secret_key), b"credential").unwrap();
+
+        // Methods of other types listed under keyAgreement are skipped.
+        assert_eq!(message.recipient, format!("{DID}#key-1"));
+        assert_ne!(message.ciphertext, b"credential");
+        assert_eq!(decrypt(&secret_key, &message).unwrap(), b"credential");
+
+        assert!(matches!(
+            decrypt(&[8u8; 32], &message),
+            Err(ResolverError::Encryption)
+        ));
+        let mut tampered = message.clone();
+        tampered.recipient = format!("{DID}#key-2");
+        assert!(matches!(
+            decrypt(&secret_key, &tampered),
+            Err(ResolverError::Encryption)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_without_key_agreement_key() {
+        let mut document = document(&[7u8; 32]);
+        document.key_agreement.truncate(1);
+        assert!(matches!(
+            encrypt(&document, b"credential"),
+            Err(ResolverError::NoKeyAgreementKey(did)) if did == DID
+        ));
+    }
+
+    #[test]
+    fn test_skip_key_agreement_key_outside_validity_window() {
+        let mut document = document(&[7u8; 32]);
+        let key_id = format!("{DID}#key-1");
+        document.verification_method[1].valid_from = Some("2023-11-14T22:13:20.000Z".into());
+        document.verification_method[1].valid_until = Some("2023-11-14T22:13:21.000Z".into());
+
+        let key = |now| key_agreement_key(&document, now).map(|(method, _)| method.id.clone());
+        assert_eq!(key(1_699_999_999_999), None);
+        assert_eq!(key(1_700_000_000_000), Some(key_id.clone()));
+        assert_eq!(key(1_700_000_000_999), Some(key_id));
+        assert_eq!(key(1_700_000_001_000), None);
+        assert!(matches!(
+            encrypt(&document, b"credential"),
+            Err(ResolverError::NoKeyAgreementKey(did)) if did == DID
+        ));
+    }
+}
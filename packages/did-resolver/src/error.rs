@@ -0,0 +1,44 @@
+use thiserror::Error;
+
+use crate::document::DocumentVersion;
+
+#[derive(Error, Debug)]
+pub enum ResolverError {
+    #[error("invalid DID: {0}")]
+    InvalidDid(String),
+    #[error("DID not found: {0}")]
+    NotFound(String),
+    #[error("{version:?} of {did} not found")]
+    VersionNotFound {
+        did: String,
+        version: DocumentVersion,
+    },
+    #[error("failed to decode account {address}: {reason}")]
+    Decode { address: String, reason: String },
+    #[error("invalid snapshot: {0}")]
+    Snapshot(String),
+    #[error("account source error: {0}")]
+    Source(String),
+    #[error("{0} has no X25519 keyAgreement key")]
+    NoKeyAgreementKey(String),
+    #[error("encryption failed")]
+    Encryption,
+    #[error("DID method not supported: {0}")]
+    MethodNotSupported(String),
+    #[error("{0} keys have no did:key form")]
+    UnsupportedKeyType(String),
+    #[error("invalid public key: {0}")]
+    InvalidPublicKey(String),
+}
+
+impl ResolverError {
+    /// Error code used in `didResolutionMetadata.error`, as defined by DID Resolution.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ResolverError::InvalidDid(_) => "invalidDid",
+            ResolverError::NotFound(_) | ResolverError::VersionNotFound { .. } => "notFound",
+            ResolverError::MethodNotSupported(_) => "methodNotSupported",
+            _ => "internalError",
+        }
+    }
+}
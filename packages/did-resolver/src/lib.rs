@@ -0,0 +1,12 @@
+pub mod did_key;
+pub mod document;
+pub mod encryption;
+pub mod error;
+pub mod resolver;
+pub mod source;
+
+pub use document::*;
+pub use encryption::EncryptedMessage;
+pub use error::ResolverError;
+pub use resolver::DidResolver;
+pub use source::{AccountSource, MemcmpFilter, MemoryAccountSource};
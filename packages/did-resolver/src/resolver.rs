@@ -0,0 +1,588 @@
+use anchor_lang::prelude::Pubkey;
+use registry_sdk::pda::{find_did_change_log_address, find_did_document_address};
+use verifiable_data_registry::migration::{decode_versioned, Migratable};
+use verifiable_data_registry::state;
+
+use crate::document::*;
+use crate::encryption::{self, EncryptedMessage};
+use crate::error::ResolverError;
+use crate::source::{AccountSource, MemcmpFilter};
+
+// Offset of the `did` string in each account, after the 8-byte discriminator and the version
+// byte. Accounts written before the version byte have it one byte earlier.
+const VERIFICATION_METHOD_DID_OFFSET: usize = 8 + 1 + 32;
+const VERIFICATION_RELATIONSHIP_DID_OFFSET: usize = 8 + 1;
+const LEGACY_LAYOUT_SHIFT: usize = 1;
+
+pub struct DidResolver<S> {
+    source: S,
+    program_id: Pubkey,
+}
+
+impl<S: AccountSource> DidResolver<S> {
+    pub fn new(source: S) -> Self {
+        Self::with_program_id(source, verifiable_data_registry::ID)
+    }
+
+    pub fn with_program_id(source: S, program_id: Pubkey) -> Self {
+        Self { source, program_id }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn did_document_address(&self, did: &str) -> Pubkey {
+        find_did_document_address(did, &self.program_id).0
+    }
+
+    /// Resolves `did` following DID Resolution: failures are reported in
+    /// `didResolutionMetadata.error` rather than returned.
+    pub fn resolve(&self, did: &str) -> ResolutionResult {
+        resolution_result(self.fetch(did))
+    }
+
+    /// Resolves `did` as it was at `version`, see [`DidResolver::fetch_version`].
+    pub fn resolve_version(&self, did: &str, version: DocumentVersion) -> ResolutionResult {
+        resolution_result(self.fetch_version(did, version))
+    }
+
+    pub fn resolve_document(&self, did: &str) -> Result<DidDocument, ResolverError> {
+        Ok(self.fetch(did)?.to_did_document())
+    }
+
+    /// Seals `plaintext` for the key agreement key `did` currently lists, see [`encryption`].
+    pub fn encrypt_for(
+        &self,
+        did: &str,
+        plaintext: &[u8],
+    ) -> Result<EncryptedMessage, ResolverError> {
+        encryption::encrypt(&self.resolve_document(did)?, plaintext)
+    }
+
+    pub fn resolve_document_version(
+        &self,
+        did: &str,
+        version: DocumentVersion,
+    ) -> Result<DidDocument, ResolverError> {
+        Ok(self.fetch_version(did, version)?.to_did_document())
+    }
+
+    /// Collects the `DidDocument`, `VerificationMethod` and `VerificationRelationship`
+    /// accounts registered for `did`.
+    pub fn fetch(&self, did: &str) -> Result<RegistryDid, ResolverError> {
+        if !did.starts_with("did:") {
+            return Err(ResolverError::InvalidDid(did.to_string()));
+        }
+
+        let address = self.did_document_address(did);
+        let data = self
+            .source
+            .get_account_data(&address)?
+            .ok_or_else(|| ResolverError::NotFound(did.to_string()))?;
+        let document: state::DidDocument = self.decode(&address, &data)?;
+        if document.did != did {
+            return Err(ResolverError::NotFound(did.to_string()));
+        }
+
+        let methods =
+            self.fetch_by_did::<state::VerificationMethod>(did, VERIFICATION_METHOD_DID_OFFSET)?;
+        let relationships = self.fetch_by_did::<state::VerificationRelationship>(
+            did,
+            VERIFICATION_RELATIONSHIP_DID_OFFSET,
+        )?;
+        let version_id = self
+            .fetch_change_log(did)?
+            .map(|change_log| change_log.version_id);
+
+        Ok(RegistryDid {
+            document,
+            methods,
+            relationships,
+            version_id,
+        })
+    }
+
+    /// Rebuilds the accounts of `did` as they were at `version` by undoing the changes
+    /// recorded after it. Only versions from just before the oldest change still held in
+    /// the DID change log can be rebuilt.
+    pub fn fetch_version(
+        &self,
+        did: &str,
+        version: DocumentVersion,
+    ) -> Result<RegistryDid, ResolverError> {
+        let mut registry_did = self.fetch(did)?;
+        let version_not_found = || ResolverError::VersionNotFound {
+            did: did.to_string(),
+            version,
+        };
+        let change_log = self.fetch_change_log(did)?.ok_or_else(version_not_found)?;
+        let oldest = change_log
+            .changes()
+            .next()
+            .map_or(change_log.version_id + 1, |change| change.version_id);
+
+        let version_id = match version {
+            DocumentVersion::VersionId(version_id) => version_id,
+            DocumentVersion::Slot(slot) => {
+                match change_log
+                    .changes()
+                    .filter(|change| change.slot <= slot)
+                    .last()
+                {
+                    Some(change) => change.version_id,
+                    // Every change since its creation is held: the DID did not exist yet.
+                    None if oldest == 1 => return Err(ResolverError::NotFound(did.to_string())),
+                    None => return Err(version_not_found()),
+                }
+            }
+        };
+        if version_id == 0 || version_id + 1 < oldest || version_id > change_log.version_id {
+            return Err(version_not_found());
+        }
+
+        for change in change_log
+            .changes()
+            .filter(|change| change.version_id > version_id)
+        {
+            registry_did.undo(change);
+        }
+        registry_did.version_id = Some(version_id);
+        Ok(registry_did)
+    }
+
+    fn fetch_change_log(&self, did: &str) -> Result<Option<state::DidChangeLog>, ResolverError> {
+        let address = find_did_change_log_address(did, &self.program_id).0;
+        self.source
+            .get_account_data(&address)?
+            .map(|data| self.decode(&address, &data))
+            .transpose()
+    }
+
+    fn fetch_by_did<T: Migratable + OfDid>(
+        &self,
+        did: &str,
+        did_offset: usize,
+    ) -> Result<Vec<T>, ResolverError> {
+        let mut accounts = self
+            .find_by_did::<T>(did, did_offset)?
+            .iter()
+            .map(|(address, data)| Ok((*address, self.decode::<T>(address, data)?)))
+            .collect::<Result<Vec<_>, ResolverError>>()?;
+
+        // Accounts that were not migrated yet have the `did` one byte earlier. This filter may
+        // also match unrelated bytes of current accounts, which are skipped.
+        for (address, data) in self.find_by_did::<T>(did, did_offset - LEGACY_LAYOUT_SHIFT)? {
+            if accounts.iter().any(|(known, _)| *known == address) {
+                continue;
+            }
+            if let Ok(account) = self.decode::<T>(&address, &data) {
+                if account.did() == did {
+                    accounts.push((address, account));
+                }
+            }
+        }
+        accounts.sort_by_key(|(address, _)| *address);
+
+        Ok(accounts.into_iter().map(|(_, account)| account).collect())
+    }
+
+    fn find_by_did<T: Migratable>(
+        &self,
+        did: &str,
+        did_offset: usize,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, ResolverError> {
+        let filters = [
+            MemcmpFilter::new(0, T::discriminator().to_vec()),
+            MemcmpFilter::new(did_offset, borsh_string(did)),
+        ];
+        self.source.get_program_accounts(&self.program_id, &filters)
+    }
+
+    fn decode<T: Migratable>(&self, address: &Pubkey, data: &[u8]) -> Result<T, ResolverError> {
+        decode_versioned(&self.program_id, address, data).map_err(|err| ResolverError::Decode {
+            address: address.to_string(),
+            reason: err.to_string(),
+        })
+    }
+}
+
+fn resolution_result(registry_did: Result<RegistryDid, ResolverError>) -> ResolutionResult {
+    match registry_did {
+        Ok(registry_did) => ResolutionResult {
+            did_document: Some(registry_did.to_did_document()),
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: Some(DID_RESOLUTION_CONTENT_TYPE.to_string()),
+                error: None,
+            },
+            did_document_metadata: DocumentMetadata {
+                deactivated: registry_did.is_deactivated(),
+                version_id: registry_did
+                    .version_id
+                    .map(|version_id| version_id.to_string()),
+            },
+        },
+        Err(err) => ResolutionResult {
+            did_document: None,
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: None,
+                error: Some(err.code().to_string()),
+            },
+            did_document_metadata: DocumentMetadata::default(),
+        },
+    }
+}
+
+trait OfDid {
+    fn did(&self) -> &str;
+}
+
+impl OfDid for state::VerificationMethod {
+    fn did(&self) -> &str {
+        &self.did
+    }
+}
+
+impl OfDid for state::VerificationRelationship {
+    fn did(&self) -> &str {
+        &self.did
+    }
+}
+
+fn borsh_string(value: &str) -> Vec<u8> {
+    [
+        (value.len() as u32).to_le_bytes().as_slice(),
+        value.as_bytes(),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AccountSerialize;
+    use registry_sdk::pda::*;
+    use serde_json::json;
+    use verifiable_data_registry::state::Versioned;
+
+    use super::*;
+    use crate::source::MemoryAccountSource;
+
+    const DID: &str = "did:zuni:solana:resolver";
+    const ALIAS: &str = "did:pkh:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:CKg5d12Jhpej1JqtmxLJgaFqqeYjxgPqToJ4LBdvG9Ev";
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    // Same account as written before layouts carried a version byte or a validity window, which
+    // must therefore be empty.
+    fn serialize_legacy<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = serialize(account);
+        data.remove(8);
+        assert_eq!(data.split_off(data.len() - 2), [0, 0]);
+        data
+    }
+
+    fn change_log() -> state::DidChangeLog {
+        state::DidChangeLog {
+            version: state::DidChangeLog::VERSION,
+            did_document: find_did_document_address(DID, &registry_sdk::ID).0,
+            version_id: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn source() -> MemoryAccountSource {
+        let mut source = MemoryAccountSource::new();
+        source.insert(
+            find_did_document_address(DID, &registry_sdk::ID).0,
+            serialize(&state::DidDocument {
+                version: state::DidDocument::VERSION,
+                controller: Pubkey::new_unique(),
+                did: DID.into(),
+                guardians: Vec::new(),
+                guardian_threshold: 0,
+                recovery_delay: 0,
+                operation_delay: 0,
+                also_known_as: vec![ALIAS.into()],
+            }),
+        );
+        // `#key-1` and its relationship have not been migrated to the versioned layout yet.
+        for (key_id, valid_until) in [("#key-0", Some(1_700_000_000_000)), ("#key-1", None)] {
+            let write = if key_id == "#key-1" {
+                serialize_legacy
+            } else {
+                serialize
+            };
+            source.insert(
+                find_verification_method_address(DID, key_id, &registry_sdk::ID).0,
+                write(&state::VerificationMethod {
+                    version: state::VerificationMethod::VERSION,
+                    controller: Pubkey::new_unique(),
+                    did: DID.into(),
+                    key_id: key_id.into(),
+                    r#type: "EcdsaSecp256k1VerificationKey2019".into(),
+                    public_key_multibase: "fabcd".into(),
+                    valid_from: None,
+                    valid_until,
+                }),
+            );
+        }
+        for (relationship, key_id) in [
+            (state::Relationship::Authentication, "#key-0"),
+            (state::Relationship::Assertion, "#key-0"),
+            (state::Relationship::KeyAgreement, "#key-1"),
+        ] {
+            let write = if key_id == "#key-1" {
+                serialize_legacy
+            } else {
+                serialize
+            };
+            source.insert(
+                find_verification_relationship_address(
+                    DID,
+                    &relationship,
+                    key_id,
+                    &registry_sdk::ID,
+                )
+                .0,
+                write(&state::VerificationRelationship {
+                    version: state::VerificationRelationship::VERSION,
+                    did: DID.into(),
+                    relationship,
+                    key_id: key_id.into(),
+                    valid_from: None,
+                    valid_until: None,
+                }),
+            );
+        }
+        // A method of another DID sharing the prefix must not leak into the document.
+        source.insert(
+            find_verification_method_address(
+                "did:zuni:solana:resolver2",
+                "#key-0",
+                &registry_sdk::ID,
+            )
+            .0,
+            serialize(&state::VerificationMethod {
+                version: state::VerificationMethod::VERSION,
+                controller: Pubkey::new_unique(),
+                did: "did:zuni:solana:resolver2".into(),
+                key_id: "#key-0".into(),
+                r#type: "EcdsaSecp256k1VerificationKey2019".into(),
+                public_key_multibase: "fabcd".into(),
+                valid_from: None,
+                valid_until: None,
+            }),
+        );
+        let mut change_log = change_log();
+        for (slot, op, key_id) in [
+            (10, state::DidChangeOp::Created, ""),
+            (20, state::DidChangeOp::VerificationMethodAdded, "#key-0"),
+            (
+                20,
+                state::DidChangeOp::VerificationRelationshipAdded {
+                    relationship: state::Relationship::Authentication,
+                },
+                "#key-0",
+            ),
+            (
+                30,
+                state::DidChangeOp::VerificationRelationshipAdded {
+                    relationship: state::Relationship::Assertion,
+                },
+                "#key-0",
+            ),
+            (40, state::DidChangeOp::VerificationMethodAdded, "#key-1"),
+            (
+                40,
+                state::DidChangeOp::VerificationRelationshipAdded {
+                    relationship: state::Relationship::KeyAgreement,
+                },
+                "#key-1",
+            ),
+            (50, state::DidChangeOp::ControllerTransferred, ""),
+        ] {
+            change_log.record(op, key_id.into(), [0; 32], slot);
+        }
+        source.insert(
+            find_did_change_log_address(DID, &registry_sdk::ID).0,
+            serialize(&change_log),
+        );
+        source
+    }
+
+    #[test]
+    fn test_resolve_did_document() {
+        let resolver = DidResolver::new(source());
+        let result = resolver.resolve(DID);
+        let document = serde_json::to_value(result.did_document.unwrap()).unwrap();
+
+        assert_eq!(
+            document,
+            json!({
+                "@context": [
+                    "https://www.w3.org/ns/did/v1",
+                    "https://w3id.org/security/suites/secp256k1-2019/v1"
+                ],
+                "id": DID,
+                "alsoKnownAs": [ALIAS],
+                "verificationMethod": [
+                    {
+                        "id": "did:zuni:solana:resolver#key-0",
+                        "type": "EcdsaSecp256k1VerificationKey2019",
+                        "controller": DID,
+                        "publicKeyMultibase": "fabcd",
+                        "validUntil": "2023-11-14T22:13:20.000Z"
+                    },
+                    {
+                        "id": "did:zuni:solana:resolver#key-1",
+                        "type": "EcdsaSecp256k1VerificationKey2019",
+                        "controller": DID,
+                        "publicKeyMultibase": "fabcd"
+                    }
+                ],
+                "authentication": ["did:zuni:solana:resolver#key-0"],
+                "assertionMethod": ["did:zuni:solana:resolver#key-0"],
+                "keyAgreement": ["did:zuni:solana:resolver#key-1"]
+            })
+        );
+        assert_eq!(
+            result.did_resolution_metadata.content_type.as_deref(),
+            Some(DID_RESOLUTION_CONTENT_TYPE)
+        );
+        assert!(!result.did_document_metadata.deactivated);
+        assert_eq!(
+            result.did_document_metadata.version_id.as_deref(),
+            Some("7")
+        );
+    }
+
+    #[test]
+    fn test_resolve_version() {
+        let resolver = DidResolver::new(source());
+        let method_ids = |document: &DidDocument| -> Vec<String> {
+            document
+                .verification_method
+                .iter()
+                .map(|method| method.id.clone())
+                .collect()
+        };
+
+        let result = resolver.resolve_version(DID, DocumentVersion::Slot(35));
+        assert_eq!(
+            result.did_document_metadata.version_id.as_deref(),
+            Some("4")
+        );
+        let document = result.did_document.unwrap();
+        assert_eq!(method_ids(&document), ["did:zuni:solana:resolver#key-0"]);
+        assert_eq!(document.authentication, ["did:zuni:solana:resolver#key-0"]);
+        assert_eq!(
+            document.assertion_method,
+            ["did:zuni:solana:resolver#key-0"]
+        );
+        assert!(document.key_agreement.is_empty());
+
+        let document = resolver
+            .resolve_document_version(DID, DocumentVersion::VersionId(2))
+            .unwrap();
+        assert_eq!(method_ids(&document), ["did:zuni:solana:resolver#key-0"]);
+        assert!(document.authentication.is_empty());
+
+        let document = resolver
+            .resolve_document_version(DID, DocumentVersion::Slot(50))
+            .unwrap();
+        assert_eq!(document, resolver.resolve_document(DID).unwrap());
+
+        for version in [DocumentVersion::Slot(5), DocumentVersion::VersionId(8)] {
+            let result = resolver.resolve_version(DID, version);
+            assert!(result.did_document.is_none());
+            assert_eq!(
+                result.did_resolution_metadata.error.as_deref(),
+                Some("notFound")
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_pruned_version() {
+        let mut change_log = change_log();
+        for slot in 1..=20 {
+            change_log.record(
+                state::DidChangeOp::ControllerTransferred,
+                String::new(),
+                [0; 32],
+                slot,
+            );
+        }
+        let versions: Vec<u64> = change_log
+            .changes()
+            .map(|change| change.version_id)
+            .collect();
+        assert_eq!(versions, (5..=20).collect::<Vec<_>>());
+
+        let mut source = source();
+        source.insert(
+            find_did_change_log_address(DID, &registry_sdk::ID).0,
+            serialize(&change_log),
+        );
+        let resolver = DidResolver::new(source);
+        assert!(resolver
+            .fetch_version(DID, DocumentVersion::VersionId(4))
+            .is_ok());
+        for version in [DocumentVersion::VersionId(3), DocumentVersion::Slot(2)] {
+            assert!(matches!(
+                resolver.fetch_version(DID, version),
+                Err(ResolverError::VersionNotFound { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let resolver = DidResolver::new(source());
+        let result = resolver.resolve("did:zuni:solana:unknown");
+        assert!(result.did_document.is_none());
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("notFound")
+        );
+
+        let result = resolver.resolve("zuni:solana:resolver");
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("invalidDid")
+        );
+    }
+
+    #[test]
+    fn test_load_snapshot_json() {
+        let data = serialize(&state::DidDocument {
+            version: state::DidDocument::VERSION,
+            controller: Pubkey::default(),
+            did: DID.into(),
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            recovery_delay: 0,
+            operation_delay: 0,
+            also_known_as: Vec::new(),
+        });
+        let snapshot = json!({
+            "jsonrpc": "2.0",
+            "result": [{
+                "pubkey": find_did_document_address(DID, &registry_sdk::ID).0.to_string(),
+                "account": { "data": [base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data), "base64"] }
+            }],
+            "id": 1
+        });
+        let source = MemoryAccountSource::from_json(&snapshot.to_string()).unwrap();
+        let result = DidResolver::new(source).resolve(DID);
+        let document = result.did_document.unwrap();
+        assert_eq!(document.id, DID);
+        assert!(document.verification_method.is_empty());
+        // No one can sign for the default key the DID was handed over to.
+        assert!(result.did_document_metadata.deactivated);
+    }
+}
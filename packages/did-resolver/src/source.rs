@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+
+use crate::error::ResolverError;
+
+/// Byte comparison against account data, mirroring the RPC `memcmp` filter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemcmpFilter {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl MemcmpFilter {
+    pub fn new(offset: usize, bytes: Vec<u8>) -> Self {
+        Self { offset, bytes }
+    }
+
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.get(self.offset..self.offset + self.bytes.len()) == Some(self.bytes.as_slice())
+    }
+}
+
+/// Read access to the registry program accounts.
+pub trait AccountSource {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ResolverError>;
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[MemcmpFilter],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, ResolverError>;
+}
+
+/// Account source backed by an in-memory snapshot of the registry program accounts.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryAccountSource {
+    accounts: BTreeMap<Pubkey, Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnapshotJson {
+    Accounts(Vec<KeyedAccountJson>),
+    RpcResponse { result: Vec<KeyedAccountJson> },
+}
+
+#[derive(Deserialize)]
+struct KeyedAccountJson {
+    pubkey: String,
+    account: AccountJson,
+}
+
+#[derive(Deserialize)]
+struct AccountJson {
+    data: (String, String),
+}
+
+impl MemoryAccountSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, address: Pubkey, data: Vec<u8>) {
+        self.accounts.insert(address, data);
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = (&Pubkey, &Vec<u8>)> {
+        self.accounts.iter()
+    }
+
+    /// Loads the JSON returned by `getProgramAccounts` with `base64` encoding, either the
+    /// bare `result` array or the whole RPC response.
+    pub fn from_json(json: &str) -> Result<Self, ResolverError> {
+        let snapshot: SnapshotJson =
+            serde_json::from_str(json).map_err(|err| ResolverError::Snapshot(err.to_string()))?;
+        let keyed_accounts = match snapshot {
+            SnapshotJson::Accounts(accounts) => accounts,
+            SnapshotJson::RpcResponse { result } => result,
+        };
+
+        let mut source = Self::new();
+        for keyed_account in keyed_accounts {
+            let address = Pubkey::from_str(&keyed_account.pubkey)
+                .map_err(|err| ResolverError::Snapshot(err.to_string()))?;
+            let (data, encoding) = keyed_account.account.data;
+            if encoding != "base64" {
+                return Err(ResolverError::Snapshot(format!(
+                    "unsupported encoding {encoding}"
+                )));
+            }
+            let data = STANDARD
+                .decode(data)
+                .map_err(|err| ResolverError::Snapshot(err.to_string()))?;
+            source.insert(address, data);
+        }
+        Ok(source)
+    }
+}
+
+impl AccountSource for MemoryAccountSource {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ResolverError> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    fn get_program_accounts(
+        &self,
+        _program_id: &Pubkey,
+        filters: &[MemcmpFilter],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, ResolverError> {
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, data)| filters.iter().all(|filter| filter.matches(data)))
+            .map(|(address, data)| (*address, data.clone()))
+            .collect())
+    }
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use solana_account_decoder::UiAccountEncoding;
+    use solana_client::rpc_client::RpcClient;
+    use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+    use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+    use super::*;
+
+    impl AccountSource for RpcClient {
+        fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ResolverError> {
+            let response = self
+                .get_account_with_commitment(address, self.commitment())
+                .map_err(|err| ResolverError::Source(err.to_string()))?;
+            Ok(response.value.map(|account| account.data))
+        }
+
+        fn get_program_accounts(
+            &self,
+            program_id: &Pubkey,
+            filters: &[MemcmpFilter],
+        ) -> Result<Vec<(Pubkey, Vec<u8>)>, ResolverError> {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(
+                    filters
+                        .iter()
+                        .map(|filter| {
+                            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                                filter.offset,
+                                filter.bytes.clone(),
+                            ))
+                        })
+                        .collect(),
+                ),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+            let accounts = self
+                .get_program_accounts_with_config(program_id, config)
+                .map_err(|err| ResolverError::Source(err.to_string()))?;
+            Ok(accounts
+                .into_iter()
+                .map(|(address, account)| (address, account.data))
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum IndexerError {
+    #[error("invalid snapshot: {0}")]
+    Snapshot(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
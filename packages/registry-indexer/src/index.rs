@@ -0,0 +1,614 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::Pubkey;
+use registry_sdk::{RegistryAccount, SdkError};
+use verifiable_data_registry::state::*;
+
+use crate::snapshot::Snapshot;
+
+#[derive(Clone, Debug)]
+pub struct Indexed<T> {
+    pub address: Pubkey,
+    pub account: T,
+}
+
+/// Queryable view over a set of registry accounts.
+#[derive(Debug, Default)]
+pub struct RegistryIndex {
+    dids: BTreeMap<String, Indexed<DidDocument>>,
+    dids_by_controller: BTreeMap<Pubkey, Vec<String>>,
+    dids_by_address: BTreeMap<Pubkey, String>,
+    methods_by_did: BTreeMap<String, Vec<Indexed<VerificationMethod>>>,
+    relationships_by_did: BTreeMap<String, Vec<Indexed<VerificationRelationship>>>,
+    credentials: BTreeMap<String, Indexed<CredentialState>>,
+    credentials_by_issuer: BTreeMap<String, Vec<String>>,
+    schemas: BTreeMap<Pubkey, Indexed<CredentialSchema>>,
+    verification_schemas_by_verifier: BTreeMap<Pubkey, Vec<Indexed<VerificationSchema>>>,
+    accreditations_by_issuer: BTreeMap<Pubkey, Vec<Indexed<Accreditation>>>,
+    delegations_by_did: BTreeMap<String, Vec<Indexed<Delegation>>>,
+    recoveries: BTreeMap<Pubkey, Indexed<Recovery>>,
+    operations_by_did_document: BTreeMap<Pubkey, Vec<Indexed<PendingOperation>>>,
+    change_logs: BTreeMap<Pubkey, Indexed<DidChangeLog>>,
+    config: Option<Indexed<RegistryConfig>>,
+    undecoded: Vec<(Pubkey, String)>,
+}
+
+impl RegistryIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes every account of the snapshot. Accounts that fail to decode are kept in
+    /// [`RegistryIndex::undecoded`] instead of aborting the whole load.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let mut index = Self::new();
+        for account in &snapshot.accounts {
+            if let Err(err) = index.insert(account.address, &account.data) {
+                index.undecoded.push((account.address, err.to_string()));
+            }
+        }
+        index
+    }
+
+    pub fn insert(&mut self, address: Pubkey, data: &[u8]) -> Result<(), SdkError> {
+        match RegistryAccount::decode_at(&address, data)? {
+            RegistryAccount::DidDocument(account) => {
+                self.dids_by_controller
+                    .entry(account.controller)
+                    .or_default()
+                    .push(account.did.clone());
+                self.dids_by_address.insert(address, account.did.clone());
+                self.dids
+                    .insert(account.did.clone(), Indexed { address, account });
+            }
+            RegistryAccount::VerificationMethod(account) => {
+                self.methods_by_did
+                    .entry(account.did.clone())
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::VerificationRelationship(account) => {
+                self.relationships_by_did
+                    .entry(account.did.clone())
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::CredentialState(account) => {
+                self.credentials_by_issuer
+                    .entry(account.issuer_did.clone())
+                    .or_default()
+                    .push(account.credential_id.clone());
+                self.credentials
+                    .insert(account.credential_id.clone(), Indexed { address, account });
+            }
+            RegistryAccount::CredentialSchema(account) => {
+                self.schemas.insert(address, Indexed { address, account });
+            }
+            RegistryAccount::Accreditation(account) => {
+                self.accreditations_by_issuer
+                    .entry(account.issuer)
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::VerificationSchema(account) => {
+                self.verification_schemas_by_verifier
+                    .entry(account.verifier)
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::Delegation(account) => {
+                self.delegations_by_did
+                    .entry(account.did.clone())
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::Recovery(account) => {
+                self.recoveries
+                    .insert(account.did_document, Indexed { address, account });
+            }
+            RegistryAccount::DidChangeLog(account) => {
+                self.change_logs
+                    .insert(account.did_document, Indexed { address, account });
+            }
+            RegistryAccount::PendingOperation(account) => {
+                self.operations_by_did_document
+                    .entry(account.did_document)
+                    .or_default()
+                    .push(Indexed { address, account });
+            }
+            RegistryAccount::RegistryConfig(account) => {
+                self.config = Some(Indexed { address, account });
+            }
+            // The on-chain reverse indexes hold nothing that is not rebuilt here.
+            RegistryAccount::ControllerDidIndex(_) | RegistryAccount::IssuerCredentialIndex(_) => {}
+            // Logins are short-lived and checked on-chain, there is nothing to look up later.
+            RegistryAccount::AuthChallenge(_) | RegistryAccount::AuthSession(_) => {}
+            // Tombstones only keep closed credential ids from being issued again.
+            RegistryAccount::ClosedCredential(_) => {}
+        }
+        Ok(())
+    }
+
+    pub fn config(&self) -> Option<&Indexed<RegistryConfig>> {
+        self.config.as_ref()
+    }
+
+    pub fn did(&self, did: &str) -> Option<&Indexed<DidDocument>> {
+        self.dids.get(did)
+    }
+
+    pub fn dids(&self) -> impl Iterator<Item = &Indexed<DidDocument>> {
+        self.dids.values()
+    }
+
+    pub fn dids_by_controller(&self, controller: &Pubkey) -> &[String] {
+        self.dids_by_controller
+            .get(controller)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn methods(&self, did: &str) -> &[Indexed<VerificationMethod>] {
+        self.methods_by_did.get(did).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn relationships(&self, did: &str) -> &[Indexed<VerificationRelationship>] {
+        self.relationships_by_did
+            .get(did)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Verification methods of `did` referenced by `relationship`.
+    pub fn methods_for_relationship(
+        &self,
+        did: &str,
+        relationship: Relationship,
+    ) -> impl Iterator<Item = &Indexed<VerificationMethod>> {
+        let key_ids: Vec<&str> = self
+            .relationships(did)
+            .iter()
+            .filter(|r| r.account.relationship == relationship)
+            .map(|r| r.account.key_id.as_str())
+            .collect();
+        self.methods(did)
+            .iter()
+            .filter(move |m| key_ids.contains(&m.account.key_id.as_str()))
+    }
+
+    pub fn credential(&self, credential_id: &str) -> Option<&Indexed<CredentialState>> {
+        self.credentials.get(credential_id)
+    }
+
+    pub fn schema(&self, address: &Pubkey) -> Option<&Indexed<CredentialSchema>> {
+        self.schemas.get(address)
+    }
+
+    /// Schema declared by `credential_id` at issuance, if it referenced one.
+    pub fn credential_schema(&self, credential_id: &str) -> Option<&Indexed<CredentialSchema>> {
+        let schema = self.credentials.get(credential_id)?.account.schema?;
+        self.schemas.get(&schema)
+    }
+
+    /// Presentation requests published by the verifier `did`, including deprecated ones.
+    pub fn verification_schemas(&self, did: &str) -> &[Indexed<VerificationSchema>] {
+        self.dids
+            .get(did)
+            .and_then(|did_document| {
+                self.verification_schemas_by_verifier
+                    .get(&did_document.address)
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Accreditations granted to `did`, for any scope and including expired ones.
+    pub fn accreditations(&self, did: &str) -> &[Indexed<Accreditation>] {
+        self.dids
+            .get(did)
+            .and_then(|did_document| self.accreditations_by_issuer.get(&did_document.address))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Authorities linking `issuer_did` to `root_did` for `scope` at `timestamp`, as the `via`
+    /// of [`registry_sdk::instruction::check_accreditation`]; empty when the issuer is the
+    /// root itself, `None` when it is not accredited.
+    pub fn accreditation_path(
+        &self,
+        root_did: &str,
+        issuer_did: &str,
+        scope: &AccreditationScope,
+        timestamp: u64,
+    ) -> Option<Vec<&str>> {
+        let root = self.dids.get(root_did)?.address;
+        let issuer = self.dids.get(issuer_did)?.address;
+        let mut chain = Vec::new();
+        if !self.find_accreditation_chain(&root, &issuer, scope, timestamp, &mut chain) {
+            return None;
+        }
+        chain
+            .iter()
+            .take(chain.len().saturating_sub(1))
+            .map(|authority| self.dids_by_address.get(authority).map(String::as_str))
+            .collect()
+    }
+
+    // Depth-first, following the same rules as `check_accreditation`. `chain` collects the
+    // authorities of the accreditations walked so far.
+    fn find_accreditation_chain(
+        &self,
+        root: &Pubkey,
+        subject: &Pubkey,
+        scope: &AccreditationScope,
+        timestamp: u64,
+        chain: &mut Vec<Pubkey>,
+    ) -> bool {
+        if subject == root {
+            return true;
+        }
+        if chain.len() == MAX_ACCREDITATION_DEPTH as usize {
+            return false;
+        }
+        for accreditation in self
+            .accreditations_by_issuer
+            .get(subject)
+            .into_iter()
+            .flatten()
+        {
+            let accreditation = &accreditation.account;
+            if accreditation.scope != *scope
+                || (accreditation.max_depth as usize) < chain.len()
+                || accreditation.is_expired_at(timestamp)
+                || chain.contains(&accreditation.authority)
+            {
+                continue;
+            }
+            chain.push(accreditation.authority);
+            if self.find_accreditation_chain(
+                root,
+                &accreditation.authority,
+                scope,
+                timestamp,
+                chain,
+            ) {
+                return true;
+            }
+            chain.pop();
+        }
+        false
+    }
+
+    /// Delegations granted by `did`, including expired or exhausted ones.
+    pub fn delegations(&self, did: &str) -> &[Indexed<Delegation>] {
+        self.delegations_by_did.get(did).map_or(&[], Vec::as_slice)
+    }
+
+    /// Guardian recovery pending for `did`, which its controller may still cancel.
+    pub fn pending_recovery(&self, did: &str) -> Option<&Indexed<Recovery>> {
+        let did_document = self.dids.get(did)?.address;
+        self.recoveries.get(&did_document)
+    }
+
+    /// Time-locked operations proposed on `did` and not yet executed or cancelled.
+    pub fn pending_operations(&self, did: &str) -> &[Indexed<PendingOperation>] {
+        self.dids
+            .get(did)
+            .and_then(|did_document| self.operations_by_did_document.get(&did_document.address))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Latest changes made to `did`, see [`DidChangeLog::changes`].
+    pub fn change_log(&self, did: &str) -> Option<&Indexed<DidChangeLog>> {
+        let did_document = self.dids.get(did)?.address;
+        self.change_logs.get(&did_document)
+    }
+
+    /// Every pending guardian recovery, worth watching for unexpected hand-overs.
+    pub fn pending_recoveries(&self) -> impl Iterator<Item = &Indexed<Recovery>> {
+        self.recoveries.values()
+    }
+
+    pub fn credentials_by_issuer(
+        &self,
+        issuer_did: &str,
+    ) -> impl Iterator<Item = &Indexed<CredentialState>> {
+        self.credentials_by_issuer
+            .get(issuer_did)
+            .into_iter()
+            .flatten()
+            .filter_map(|credential_id| self.credentials.get(credential_id))
+    }
+
+    pub fn revoked_credentials(&self) -> impl Iterator<Item = &Indexed<CredentialState>> {
+        self.credentials
+            .values()
+            .filter(|c| c.account.status == CredentialStatus::Revoked)
+    }
+
+    /// Active credentials whose `expire_at` is at or before `timestamp` (Unix milliseconds).
+    pub fn expired_credentials(
+        &self,
+        timestamp: u64,
+    ) -> impl Iterator<Item = &Indexed<CredentialState>> {
+        self.credentials.values().filter(move |c| {
+            c.account.status == CredentialStatus::Active
+                && matches!(c.account.expire_at, Some(expire_at) if expire_at <= timestamp)
+        })
+    }
+
+    pub fn undecoded(&self) -> &[(Pubkey, String)] {
+        &self.undecoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use verifiable_data_registry::migration::Migratable;
+
+    use super::*;
+    use crate::snapshot::SnapshotAccount;
+
+    fn account<T: Migratable>(account: T) -> SnapshotAccount {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        SnapshotAccount {
+            address: account.expected_address(&registry_sdk::ID),
+            data,
+        }
+    }
+
+    fn credential(
+        issuer_did: &str,
+        credential_id: &str,
+        status: CredentialStatus,
+    ) -> SnapshotAccount {
+        account(CredentialState {
+            version: CredentialState::VERSION,
+            issuer_did: issuer_did.to_string(),
+            credential_id: credential_id.to_string(),
+            status,
+            expire_at: Some(1_000),
+            payer: Pubkey::new_unique(),
+            delegation: None,
+            holder: None,
+            acknowledged_at: None,
+            schema: None,
+            field_merkle_root: None,
+        })
+    }
+
+    fn snapshot(controller: Pubkey) -> Snapshot {
+        let did = "did:zuni:solana:university";
+        let mut accounts = vec![
+            account(DidDocument {
+                version: DidDocument::VERSION,
+                controller,
+                did: did.to_string(),
+                guardians: Vec::new(),
+                guardian_threshold: 0,
+                recovery_delay: 0,
+                operation_delay: 0,
+                also_known_as: Vec::new(),
+            }),
+            account(DidDocument {
+                version: DidDocument::VERSION,
+                controller,
+                did: "did:zuni:solana:faculty".to_string(),
+                guardians: Vec::new(),
+                guardian_threshold: 0,
+                recovery_delay: 0,
+                operation_delay: 0,
+                also_known_as: Vec::new(),
+            }),
+            account(VerificationMethod {
+                version: VerificationMethod::VERSION,
+                controller,
+                did: did.to_string(),
+                key_id: format!("{did}#key-0"),
+                r#type: "EcdsaSecp256k1VerificationKey2019".to_string(),
+                public_key_multibase: "fabcd".to_string(),
+                valid_from: None,
+                valid_until: None,
+            }),
+            account(VerificationRelationship {
+                version: VerificationRelationship::VERSION,
+                did: did.to_string(),
+                relationship: Relationship::Authentication,
+                key_id: format!("{did}#key-0"),
+                valid_from: None,
+                valid_until: None,
+            }),
+            account(Recovery {
+                version: Recovery::VERSION,
+                did_document: registry_sdk::pda::find_did_document_address(did, &registry_sdk::ID)
+                    .0,
+                new_controller: Pubkey::new_unique(),
+                approvals: vec![Guardian::Key(Pubkey::new_unique())],
+                executable_at: None,
+                expire_at: 1_000,
+                payer: controller,
+            }),
+            account(PendingOperation {
+                version: PendingOperation::VERSION,
+                did_document: registry_sdk::pda::find_did_document_address(did, &registry_sdk::ID)
+                    .0,
+                id: 0,
+                operation: DidOperation::SetOperationDelay { operation_delay: 0 },
+                executable_at: 1_000,
+            }),
+            account(CredentialSchema {
+                version: CredentialSchema::VERSION,
+                author: registry_sdk::pda::find_did_document_address(did, &registry_sdk::ID).0,
+                schema_id: "degree".to_string(),
+                schema_version: 1,
+                name: "Degree".to_string(),
+                fields: vec![SchemaField {
+                    name: "degree".to_string(),
+                    field_type: SchemaFieldType::String,
+                }],
+                content_hash: [0; 32],
+                deprecated: false,
+            }),
+            account(VerificationSchema {
+                version: VerificationSchema::VERSION,
+                verifier: registry_sdk::pda::find_did_document_address(did, &registry_sdk::ID).0,
+                schema_id: "alumni-discount".to_string(),
+                schema_version: 1,
+                name: "Alumni discount".to_string(),
+                check_roots: vec![[1; 32]],
+                requested_fields: vec!["0:degree".to_string()],
+                deprecated: false,
+            }),
+            credential(did, "diploma-1", CredentialStatus::Active),
+            credential(did, "diploma-2", CredentialStatus::Revoked),
+            credential(
+                "did:zuni:solana:faculty",
+                "transcript-1",
+                CredentialStatus::Active,
+            ),
+        ];
+        accounts.push(SnapshotAccount {
+            address: Pubkey::new_unique(),
+            data: vec![1; 16],
+        });
+        Snapshot { accounts }
+    }
+
+    #[test]
+    fn test_index_snapshot() {
+        let controller = Pubkey::new_unique();
+        let index = RegistryIndex::from_snapshot(&snapshot(controller));
+        let did = "did:zuni:solana:university";
+
+        assert_eq!(index.dids_by_controller(&controller).len(), 2);
+        assert!(index.dids_by_controller(&Pubkey::new_unique()).is_empty());
+        assert_eq!(index.methods(did).len(), 1);
+        assert_eq!(
+            index
+                .methods_for_relationship(did, Relationship::Authentication)
+                .count(),
+            1
+        );
+        assert_eq!(
+            index
+                .methods_for_relationship(did, Relationship::Assertion)
+                .count(),
+            0
+        );
+
+        let issued: Vec<&str> = index
+            .credentials_by_issuer(did)
+            .map(|c| c.account.credential_id.as_str())
+            .collect();
+        assert_eq!(issued, ["diploma-1", "diploma-2"]);
+        let revoked: Vec<&str> = index
+            .revoked_credentials()
+            .map(|c| c.account.credential_id.as_str())
+            .collect();
+        assert_eq!(revoked, ["diploma-2"]);
+        assert_eq!(index.expired_credentials(999).count(), 0);
+        assert_eq!(index.expired_credentials(1_000).count(), 2);
+        assert_eq!(index.undecoded().len(), 1);
+        assert!(index.pending_recovery(did).is_some());
+        assert!(index.pending_recovery("did:zuni:solana:faculty").is_none());
+        assert_eq!(index.pending_operations(did).len(), 1);
+        assert!(index
+            .pending_operations("did:zuni:solana:faculty")
+            .is_empty());
+        let schema =
+            registry_sdk::pda::find_credential_schema_address(did, "degree", 1, &registry_sdk::ID)
+                .0;
+        assert_eq!(index.schema(&schema).unwrap().account.name, "Degree");
+        assert!(index.credential_schema("diploma-1").is_none());
+        assert!(index.verification_schemas(did)[0]
+            .account
+            .has_check_root(&[1; 32]));
+        assert!(index
+            .verification_schemas("did:zuni:solana:faculty")
+            .is_empty());
+    }
+
+    fn did_document(did: &str) -> SnapshotAccount {
+        account(DidDocument {
+            version: DidDocument::VERSION,
+            controller: Pubkey::new_unique(),
+            did: did.to_string(),
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            recovery_delay: 0,
+            operation_delay: 0,
+            also_known_as: Vec::new(),
+        })
+    }
+
+    fn accreditation(
+        authority_did: &str,
+        issuer_did: &str,
+        max_depth: u8,
+        expire_at: Option<u64>,
+    ) -> SnapshotAccount {
+        let address = |did| registry_sdk::pda::find_did_document_address(did, &registry_sdk::ID).0;
+        account(Accreditation {
+            version: Accreditation::VERSION,
+            authority: address(authority_did),
+            issuer: address(issuer_did),
+            scope: AccreditationScope::CredentialType("MedicalLicense".to_string()),
+            max_depth,
+            expire_at,
+        })
+    }
+
+    #[test]
+    fn test_accreditation_path() {
+        let scope = AccreditationScope::CredentialType("MedicalLicense".to_string());
+        let ministry = "did:zuni:solana:ministry";
+        let board = "did:zuni:solana:board";
+        let clinic = "did:zuni:solana:clinic";
+        let lab = "did:zuni:solana:lab";
+        let index = RegistryIndex::from_snapshot(&Snapshot {
+            accounts: vec![
+                did_document(ministry),
+                did_document(board),
+                did_document(clinic),
+                did_document(lab),
+                accreditation(ministry, board, 1, None),
+                accreditation(board, clinic, 0, Some(1_000)),
+                // The clinic may not accredit further.
+                accreditation(clinic, lab, 0, None),
+            ],
+        });
+
+        assert_eq!(index.accreditations(clinic).len(), 1);
+        assert_eq!(
+            index.accreditation_path(ministry, board, &scope, 0),
+            Some(vec![])
+        );
+        assert_eq!(
+            index.accreditation_path(ministry, clinic, &scope, 0),
+            Some(vec![board])
+        );
+        assert_eq!(
+            index.accreditation_path(ministry, ministry, &scope, 0),
+            Some(vec![])
+        );
+        assert_eq!(
+            index.accreditation_path(ministry, clinic, &scope, 1_000),
+            None
+        );
+        assert_eq!(index.accreditation_path(ministry, lab, &scope, 0), None);
+        assert_eq!(
+            index.accreditation_path(
+                ministry,
+                board,
+                &AccreditationScope::CredentialType("Diploma".to_string()),
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_binary_snapshot_roundtrip() {
+        let snapshot = snapshot(Pubkey::new_unique());
+        let bytes = snapshot.to_bytes();
+        assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+        assert!(Snapshot::from_bytes(&bytes[8..]).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod error;
+pub mod index;
+pub mod snapshot;
+
+pub use error::IndexerError;
+pub use index::{Indexed, RegistryIndex};
+pub use registry_sdk::RegistryAccount;
+pub use snapshot::{Snapshot, SnapshotAccount};
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::Path;
+
+use anchor_lang::prelude::{AnchorDeserialize, AnchorSerialize, Pubkey};
+use did_resolver::MemoryAccountSource;
+
+use crate::error::IndexerError;
+
+// Prefix of binary snapshots, followed by the borsh-serialized `Snapshot`.
+const BINARY_MAGIC: &[u8; 8] = b"ZUNIVDR1";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct SnapshotAccount {
+    pub address: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// Raw accounts of the registry program, as returned by `getProgramAccounts`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+impl Snapshot {
+    /// Loads the JSON output of `getProgramAccounts` with `base64` encoding.
+    pub fn from_json(json: &str) -> Result<Self, IndexerError> {
+        let source = MemoryAccountSource::from_json(json)
+            .map_err(|err| IndexerError::Snapshot(err.to_string()))?;
+        Ok(Self {
+            accounts: source
+                .accounts()
+                .map(|(address, data)| SnapshotAccount {
+                    address: *address,
+                    data: data.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IndexerError> {
+        let payload = bytes
+            .strip_prefix(BINARY_MAGIC.as_slice())
+            .ok_or_else(|| IndexerError::Snapshot("missing binary snapshot header".to_string()))?;
+        Self::try_from_slice(payload).map_err(|err| IndexerError::Snapshot(err.to_string()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        self.serialize(&mut bytes)
+            .expect("writing to a Vec cannot fail");
+        bytes
+    }
+
+    /// Loads a binary snapshot, or a JSON one if the file does not start with the binary header.
+    pub fn load(path: &Path) -> Result<Self, IndexerError> {
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(BINARY_MAGIC) {
+            Self::from_bytes(&bytes)
+        } else {
+            let json = String::from_utf8(bytes).map_err(|_| {
+                IndexerError::Snapshot("snapshot is neither binary nor JSON".to_string())
+            })?;
+            Self::from_json(&json)
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), IndexerError> {
+        Ok(fs::write(path, self.to_bytes())?)
+    }
+
+    /// Account source for resolving DID documents from the same snapshot.
+    pub fn to_account_source(&self) -> MemoryAccountSource {
+        let mut source = MemoryAccountSource::new();
+        for account in &self.accounts {
+            source.insert(account.address, account.data.clone());
+        }
+        source
+    }
+}
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use verifiable_data_registry::migration::upgrade_account_data;
+use verifiable_data_registry::state::*;
+use verifiable_data_registry::ID;
+
+use crate::error::SdkError;
+
+/// Any account owned by the registry program, identified by its Anchor discriminator.
+#[derive(Clone, Debug)]
+pub enum RegistryAccount {
+    DidDocument(DidDocument),
+    VerificationMethod(VerificationMethod),
+    VerificationRelationship(VerificationRelationship),
+    CredentialState(CredentialState),
+    ControllerDidIndex(ControllerDidIndex),
+    IssuerCredentialIndex(IssuerCredentialIndex),
+    RegistryConfig(RegistryConfig),
+    Delegation(Delegation),
+    Recovery(Recovery),
+    PendingOperation(PendingOperation),
+    DidChangeLog(DidChangeLog),
+    AuthChallenge(AuthChallenge),
+    AuthSession(AuthSession),
+    CredentialSchema(CredentialSchema),
+    VerificationSchema(VerificationSchema),
+    Accreditation(Accreditation),
+    ClosedCredential(ClosedCredential),
+}
+
+impl RegistryAccount {
+    /// Decodes an account written with the current layout of its type.
+    pub fn decode(data: &[u8]) -> Result<Self, SdkError> {
+        let discriminator: [u8; 8] = data
+            .get(..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(SdkError::AccountTooSmall)?;
+
+        if discriminator == DidDocument::discriminator() {
+            decode(data).map(RegistryAccount::DidDocument)
+        } else if discriminator == VerificationMethod::discriminator() {
+            decode(data).map(RegistryAccount::VerificationMethod)
+        } else if discriminator == VerificationRelationship::discriminator() {
+            decode(data).map(RegistryAccount::VerificationRelationship)
+        } else if discriminator == CredentialState::discriminator() {
+            decode(data).map(RegistryAccount::CredentialState)
+        } else if discriminator == ControllerDidIndex::discriminator() {
+            decode(data).map(RegistryAccount::ControllerDidIndex)
+        } else if discriminator == IssuerCredentialIndex::discriminator() {
+            decode(data).map(RegistryAccount::IssuerCredentialIndex)
+        } else if discriminator == RegistryConfig::discriminator() {
+            decode(data).map(RegistryAccount::RegistryConfig)
+        } else if discriminator == Delegation::discriminator() {
+            decode(data).map(RegistryAccount::Delegation)
+        } else if discriminator == Recovery::discriminator() {
+            decode(data).map(RegistryAccount::Recovery)
+        } else if discriminator == PendingOperation::discriminator() {
+            decode(data).map(RegistryAccount::PendingOperation)
+        } else if discriminator == DidChangeLog::discriminator() {
+            decode(data).map(RegistryAccount::DidChangeLog)
+        } else if discriminator == AuthChallenge::discriminator() {
+            decode(data).map(RegistryAccount::AuthChallenge)
+        } else if discriminator == AuthSession::discriminator() {
+            decode(data).map(RegistryAccount::AuthSession)
+        } else if discriminator == CredentialSchema::discriminator() {
+            decode(data).map(RegistryAccount::CredentialSchema)
+        } else if discriminator == VerificationSchema::discriminator() {
+            decode(data).map(RegistryAccount::VerificationSchema)
+        } else if discriminator == Accreditation::discriminator() {
+            decode(data).map(RegistryAccount::Accreditation)
+        } else if discriminator == ClosedCredential::discriminator() {
+            decode(data).map(RegistryAccount::ClosedCredential)
+        } else {
+            Err(SdkError::UnknownAccount(discriminator))
+        }
+    }
+
+    /// Like [`RegistryAccount::decode`], but also accepts accounts written with an older
+    /// layout, which are told apart from current ones by their `address`.
+    pub fn decode_at(address: &Pubkey, data: &[u8]) -> Result<Self, SdkError> {
+        match upgrade_account_data(&ID, address, data) {
+            Ok(Some(upgrade)) => Self::decode(&upgrade.data),
+            Ok(None) => Self::decode(data),
+            Err(err) => Self::decode(data).and(Err(SdkError::AccountDecode(err.to_string()))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RegistryAccount::DidDocument(_) => "DidDocument",
+            RegistryAccount::VerificationMethod(_) => "VerificationMethod",
+            RegistryAccount::VerificationRelationship(_) => "VerificationRelationship",
+            RegistryAccount::CredentialState(_) => "CredentialState",
+            RegistryAccount::ControllerDidIndex(_) => "ControllerDidIndex",
+            RegistryAccount::IssuerCredentialIndex(_) => "IssuerCredentialIndex",
+            RegistryAccount::RegistryConfig(_) => "RegistryConfig",
+            RegistryAccount::Delegation(_) => "Delegation",
+            RegistryAccount::Recovery(_) => "Recovery",
+            RegistryAccount::PendingOperation(_) => "PendingOperation",
+            RegistryAccount::DidChangeLog(_) => "DidChangeLog",
+            RegistryAccount::AuthChallenge(_) => "AuthChallenge",
+            RegistryAccount::AuthSession(_) => "AuthSession",
+            RegistryAccount::CredentialSchema(_) => "CredentialSchema",
+            RegistryAccount::VerificationSchema(_) => "VerificationSchema",
+            RegistryAccount::Accreditation(_) => "Accreditation",
+            RegistryAccount::ClosedCredential(_) => "ClosedCredential",
+        }
+    }
+}
+
+fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T, SdkError> {
+    T::try_deserialize(&mut &data[..]).map_err(|err| SdkError::AccountDecode(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::borsh;
+    use anchor_lang::{AccountSerialize, AnchorSerialize};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_registry_account() {
+        let mut data = Vec::new();
+        CredentialState {
+            version: CredentialState::VERSION,
+            issuer_did: "did:zuni:solana:sdk".to_string(),
+            credential_id: "credential-1".to_string(),
+            status: CredentialStatus::Revoked,
+            expire_at: Some(1),
+            payer: Pubkey::new_unique(),
+            delegation: None,
+            holder: None,
+            acknowledged_at: None,
+            schema: None,
+            field_merkle_root: None,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+
+        match RegistryAccount::decode(&data).unwrap() {
+            RegistryAccount::CredentialState(credential) => {
+                assert_eq!(credential.credential_id, "credential-1");
+                assert_eq!(credential.status, CredentialStatus::Revoked);
+            }
+            other => panic!("decoded {}", other.name()),
+        }
+        assert!(matches!(
+            RegistryAccount::decode(&[0u8; 4]),
+            Err(SdkError::AccountTooSmall)
+        ));
+        assert!(matches!(
+            RegistryAccount::decode(&[0u8; 16]),
+            Err(SdkError::UnknownAccount(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_legacy_account() {
+        let controller = Pubkey::new_unique();
+        let did = "did:zuni:solana:legacy";
+        let address = crate::pda::find_did_document_address(did, &ID).0;
+
+        // Version 0 document: no version byte, padded to its allocated size.
+        let mut data = DidDocument::discriminator().to_vec();
+        (controller, did.to_string()).serialize(&mut data).unwrap();
+        data.resize(data.len() + 32, 0);
+
+        match RegistryAccount::decode_at(&address, &data).unwrap() {
+            RegistryAccount::DidDocument(document) => {
+                assert_eq!(document.version, DidDocument::VERSION);
+                assert_eq!(document.controller, controller);
+                assert_eq!(document.did, did);
+            }
+            other => panic!("decoded {}", other.name()),
+        }
+        let upgrade = upgrade_account_data(&ID, &address, &data).unwrap().unwrap();
+        assert!(upgrade_account_data(&ID, &address, &upgrade.data)
+            .unwrap()
+            .is_none());
+
+        // A legacy layout at an address that does not match its contents is rejected.
+        assert!(RegistryAccount::decode_at(&Pubkey::new_unique(), &data).is_err());
+    }
+
+    // Layouts deployed before accounts carried a version byte.
+    #[derive(AnchorSerialize)]
+    struct BaselineVerificationMethod {
+        controller: Pubkey,
+        did: String,
+        key_id: String,
+        r#type: String,
+        public_key_multibase: String,
+    }
+
+    #[derive(AnchorSerialize)]
+    struct BaselineVerificationRelationship {
+        did: String,
+        relationship: Relationship,
+        key_id: String,
+    }
+
+    #[derive(AnchorSerialize)]
+    struct BaselineCredentialState {
+        issuer_did: String,
+        credential_id: String,
+        status: CredentialStatus,
+        expire_at: Option<u64>,
+    }
+
+    fn baseline_data<T: Discriminator>(account: impl AnchorSerialize) -> Vec<u8> {
+        let mut data = T::discriminator().to_vec();
+        account.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_migrate_baseline_accounts() {
+        let did = "did:zuni:solana:baseline";
+        let key_id = "did:zuni:solana:baseline#key-0";
+        let controller = Pubkey::new_unique();
+
+        let address = crate::pda::find_verification_method_address(did, key_id, &ID).0;
+        let data = baseline_data::<VerificationMethod>(BaselineVerificationMethod {
+            controller,
+            did: did.to_string(),
+            key_id: key_id.to_string(),
+            r#type: "EcdsaSecp256k1VerificationKey2019".to_string(),
+            public_key_multibase: "f04".to_string(),
+        });
+        let upgrade = upgrade_account_data(&ID, &address, &data).unwrap().unwrap();
+        assert_eq!(upgrade.from_version, 0);
+        let method = VerificationMethod::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(method.version, VerificationMethod::VERSION);
+        assert_eq!(method.controller, controller);
+        assert_eq!(method.public_key_multibase, "f04");
+        assert_eq!((method.valid_from, method.valid_until), (None, None));
+
+        let relationship = Relationship::Authentication;
+        let address =
+            crate::pda::find_verification_relationship_address(did, &relationship, key_id, &ID).0;
+        let data = baseline_data::<VerificationRelationship>(BaselineVerificationRelationship {
+            did: did.to_string(),
+            relationship: relationship.clone(),
+            key_id: key_id.to_string(),
+        });
+        let upgrade = upgrade_account_data(&ID, &address, &data).unwrap().unwrap();
+        let verification_relationship =
+            VerificationRelationship::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(verification_relationship.relationship, relationship);
+        assert_eq!(verification_relationship.key_id, key_id);
+        assert_eq!(verification_relationship.valid_until, None);
+
+        let address = crate::pda::find_credential_state_address("credential-1", &ID).0;
+        let data = baseline_data::<CredentialState>(BaselineCredentialState {
+            issuer_did: did.to_string(),
+            credential_id: "credential-1".to_string(),
+            status: CredentialStatus::Revoked,
+            expire_at: Some(1),
+        });
+        let upgrade = upgrade_account_data(&ID, &address, &data).unwrap().unwrap();
+        let credential = CredentialState::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(credential.version, CredentialState::VERSION);
+        assert_eq!(credential.issuer_did, did);
+        assert_eq!(credential.status, CredentialStatus::Revoked);
+        assert_eq!(credential.expire_at, Some(1));
+        // Closing refunds the issuer's controller rather than whoever migrated it.
+        assert_eq!(credential.payer, Pubkey::default());
+        assert_eq!(credential.rent_recipient(&controller), controller);
+        assert_eq!(credential.holder, None);
+        assert!(upgrade_account_data(&ID, &address, &upgrade.data)
+            .unwrap()
+            .is_none());
+    }
+}
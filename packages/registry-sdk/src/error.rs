@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SdkError {
+    #[error("invalid secp256k1 secret key")]
+    InvalidSecretKey,
+    #[error("invalid hex: {0}")]
+    InvalidHex(String),
+    #[error("account data is smaller than a discriminator")]
+    AccountTooSmall,
+    #[error("unknown account discriminator {0:?}")]
+    UnknownAccount([u8; 8]),
+    #[error("failed to decode account: {0}")]
+    AccountDecode(String),
+    #[error("invalid DER signature")]
+    InvalidDerSignature,
+    #[error("invalid field element: {0}")]
+    InvalidFieldElement(String),
+}
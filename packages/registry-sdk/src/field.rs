@@ -0,0 +1,91 @@
+//! Poseidon field elements of the TypeScript library, such as the `fieldMerkleRoot` of a
+//! credential or of a `SchemaCredentialCheck`.
+//!
+//! The library prints them in decimal while accounts store them as 32 big-endian bytes.
+
+use crate::error::SdkError;
+
+// Order of the BN254 scalar field the circuits work in.
+const BN254_SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Parses a decimal field element, e.g. `checkSMT.F.toString(checkSMT.root, 10)`.
+pub fn parse_field_element(decimal: &str) -> Result<[u8; 32], SdkError> {
+    let invalid = || SdkError::InvalidFieldElement(decimal.to_string());
+    if decimal.is_empty() {
+        return Err(invalid());
+    }
+    let mut element = [0u8; 32];
+    for digit in decimal.bytes() {
+        if !digit.is_ascii_digit() {
+            return Err(invalid());
+        }
+        let mut carry = u16::from(digit - b'0');
+        for byte in element.iter_mut().rev() {
+            let value = u16::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(invalid());
+        }
+    }
+    if element >= BN254_SCALAR_MODULUS {
+        return Err(invalid());
+    }
+    Ok(element)
+}
+
+/// Decimal form of `element`, as the TypeScript library prints it.
+pub fn format_field_element(element: &[u8; 32]) -> String {
+    let mut element = *element;
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u16;
+        for byte in element.iter_mut() {
+            let value = (remainder << 8) | u16::from(*byte);
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        if element.iter().all(|byte| *byte == 0) {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("decimal digits are ASCII")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_element_roundtrip() {
+        let root = "7353183431244536536573612469101768404624432487262452437981389880213428164815";
+        let element = parse_field_element(root).unwrap();
+        assert_eq!(format_field_element(&element), root);
+        assert_eq!(parse_field_element("0").unwrap(), [0u8; 32]);
+        assert_eq!(format_field_element(&[0u8; 32]), "0");
+        assert_eq!(parse_field_element("258").unwrap()[30..], [1, 2]);
+    }
+
+    #[test]
+    fn test_parse_field_element_rejects_out_of_field() {
+        let modulus =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        assert_eq!(
+            parse_field_element(modulus).unwrap_err().to_string(),
+            format!("invalid field element: {modulus}")
+        );
+        assert!(parse_field_element(
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+        )
+        .is_ok());
+        assert!(parse_field_element(&"9".repeat(80)).is_err());
+        assert!(parse_field_element("0x01").is_err());
+        assert!(parse_field_element("").is_err());
+    }
+}
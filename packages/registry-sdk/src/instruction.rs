@@ -0,0 +1,1597 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use verifiable_data_registry::state::{
+    AccreditationScope, ConfigUpdate, CredentialHolder, DidOperation, Guardian, Relationship,
+    SchemaField, Secp256k1Signature, WebAuthnAssertion,
+};
+use verifiable_data_registry::{accounts, instruction, ID};
+
+use crate::pda::*;
+
+pub fn initialize_did(controller: &Pubkey, did: &str, controller_index_page: u32) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::InitializeDID {
+            did_document: find_did_document_address(did, &ID).0,
+            change_log: find_did_change_log_address(did, &ID).0,
+            controller_index: find_controller_did_index_address(
+                controller,
+                controller_index_page,
+                &ID,
+            )
+            .0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeDid {
+            did: did.to_string(),
+            controller_index_page,
+        }
+        .data(),
+    }
+}
+
+pub fn transfer_did_controller(
+    controller: &Pubkey,
+    did: &str,
+    new_controller: &Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: transfer_did_controller_accounts(
+            controller,
+            did,
+            new_controller,
+            from_index_page,
+            to_index_page,
+        )
+        .to_account_metas(None),
+        data: instruction::TransferDidController {
+            did: did.to_string(),
+            new_controller: *new_controller,
+            from_index_page,
+            to_index_page,
+        }
+        .data(),
+    }
+}
+
+fn transfer_did_controller_accounts(
+    controller: &Pubkey,
+    did: &str,
+    new_controller: &Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> accounts::TransferDidController {
+    accounts::TransferDidController {
+        did_document: find_did_document_address(did, &ID).0,
+        change_log: find_did_change_log_address(did, &ID).0,
+        from_controller_index: find_controller_did_index_address(controller, from_index_page, &ID)
+            .0,
+        to_controller_index: find_controller_did_index_address(new_controller, to_index_page, &ID)
+            .0,
+        config: find_registry_config_address(&ID).0,
+        controller: *controller,
+        system_program: system_program::ID,
+    }
+}
+
+pub struct AddVerificationMethodArgs<'a> {
+    pub did: &'a str,
+    pub key_id: &'a str,
+    pub r#type: &'a str,
+    pub public_key_multibase: &'a str,
+    /// Controller of the key itself, not of the DID.
+    pub key_controller: Pubkey,
+    pub valid_from: Option<u64>,
+    pub valid_until: Option<u64>,
+}
+
+pub fn add_verification_method(
+    controller: &Pubkey,
+    args: AddVerificationMethodArgs,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: add_verification_method_accounts(controller, &args).to_account_metas(None),
+        data: instruction::AddVerificationMethod {
+            did: args.did.to_string(),
+            key_id: args.key_id.to_string(),
+            r#type: args.r#type.to_string(),
+            public_key_multibase: args.public_key_multibase.to_string(),
+            controller: args.key_controller,
+            valid_from: args.valid_from,
+            valid_until: args.valid_until,
+        }
+        .data(),
+    }
+}
+
+fn add_verification_method_accounts(
+    controller: &Pubkey,
+    args: &AddVerificationMethodArgs,
+) -> accounts::AddVerificationMethod {
+    accounts::AddVerificationMethod {
+        verification_method: find_verification_method_address(args.did, args.key_id, &ID).0,
+        did_document: find_did_document_address(args.did, &ID).0,
+        change_log: find_did_change_log_address(args.did, &ID).0,
+        config: find_registry_config_address(&ID).0,
+        controller: *controller,
+        system_program: system_program::ID,
+    }
+}
+
+pub fn add_verification_relationship(
+    controller: &Pubkey,
+    did: &str,
+    relationship: Relationship,
+    key_id: &str,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: add_verification_relationship_accounts(controller, did, &relationship, key_id)
+            .to_account_metas(None),
+        data: instruction::AddVerificationRelationship {
+            did: did.to_string(),
+            relationship,
+            key_id: key_id.to_string(),
+            valid_from,
+            valid_until,
+        }
+        .data(),
+    }
+}
+
+fn add_verification_relationship_accounts(
+    controller: &Pubkey,
+    did: &str,
+    relationship: &Relationship,
+    key_id: &str,
+) -> accounts::AddVerificationRelationship {
+    accounts::AddVerificationRelationship {
+        verification_relationship: find_verification_relationship_address(
+            did,
+            relationship,
+            key_id,
+            &ID,
+        )
+        .0,
+        did_document: find_did_document_address(did, &ID).0,
+        change_log: find_did_change_log_address(did, &ID).0,
+        verification_method: find_verification_method_address(did, key_id, &ID).0,
+        config: find_registry_config_address(&ID).0,
+        controller: *controller,
+        system_program: system_program::ID,
+    }
+}
+
+/// How an issuer, or a DID logging in, proves control of its authentication key.
+#[derive(Clone, Copy, Debug)]
+pub enum IssuerAuthentication<'a> {
+    /// Signature of an `EcdsaSecp256k1VerificationKey2019` key over the instruction message.
+    Secp256k1(&'a Secp256k1Signature),
+    /// Solana key, of a `SolanaMethod2021` or `Ed25519VerificationKey2020` method, signing
+    /// the transaction.
+    Signer(Pubkey),
+    /// Passkey assertion for a `JsonWebKey2020` method. The transaction must also carry the
+    /// [`secp256r1_instruction`](crate::webauthn::secp256r1_instruction) of its signature.
+    WebAuthn(&'a WebAuthnAssertion),
+}
+
+impl IssuerAuthentication<'_> {
+    fn signature(&self) -> (Option<u8>, Option<[u8; 64]>) {
+        match self {
+            IssuerAuthentication::Secp256k1(signature) => {
+                (Some(signature.recovery_id), Some(signature.signature))
+            }
+            IssuerAuthentication::Signer(_) | IssuerAuthentication::WebAuthn(_) => (None, None),
+        }
+    }
+
+    fn signer(&self) -> Option<Pubkey> {
+        match self {
+            IssuerAuthentication::Signer(signer) => Some(*signer),
+            IssuerAuthentication::Secp256k1(_) | IssuerAuthentication::WebAuthn(_) => None,
+        }
+    }
+
+    fn webauthn_assertion(&self) -> Option<WebAuthnAssertion> {
+        match self {
+            IssuerAuthentication::WebAuthn(assertion) => Some((*assertion).clone()),
+            IssuerAuthentication::Secp256k1(_) | IssuerAuthentication::Signer(_) => None,
+        }
+    }
+
+    fn instructions_sysvar(&self) -> Option<Pubkey> {
+        self.webauthn_assertion().map(|_| sysvar_instructions::ID)
+    }
+}
+
+/// Issues `credential_id` signed by an authentication key of `did`. A `holder` binds the
+/// credential to its subject, a `credential_schema` address declares its claims and a
+/// `field_merkle_root` anchors the Poseidon root field proofs are checked against; all of
+/// them must be covered by the signed message, see
+/// [`add_credential_message`](crate::signer::add_credential_message).
+#[allow(clippy::too_many_arguments)]
+pub fn add_credential(
+    payer: &Pubkey,
+    did: &str,
+    authentication_id: &str,
+    credential_id: &str,
+    expire_at: Option<u64>,
+    holder: Option<&CredentialHolder>,
+    credential_schema: Option<&Pubkey>,
+    field_merkle_root: Option<&[u8; 32]>,
+    issuer_authentication: IssuerAuthentication,
+    issuer_index_page: u32,
+) -> Instruction {
+    let (recovery_id, signature) = issuer_authentication.signature();
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AddCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            closed_credential: find_closed_credential_address(credential_id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            verification_method: find_verification_method_address(did, authentication_id, &ID).0,
+            authentication: find_verification_relationship_address(
+                did,
+                &Relationship::Authentication,
+                authentication_id,
+                &ID,
+            )
+            .0,
+            authentication_signer: issuer_authentication.signer(),
+            instructions_sysvar: issuer_authentication.instructions_sysvar(),
+            credential_schema: credential_schema.copied(),
+            issuer_index: find_issuer_credential_index_address(did, issuer_index_page, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::AddCredential {
+            did: did.to_string(),
+            authentication_id: authentication_id.to_string(),
+            credential_id: credential_id.to_string(),
+            expire_at,
+            holder: holder.cloned(),
+            field_merkle_root: field_merkle_root.copied(),
+            recovery_id,
+            signature,
+            webauthn_assertion: issuer_authentication.webauthn_assertion(),
+            issuer_index_page,
+        }
+        .data(),
+    }
+}
+
+pub fn revoke_credential(
+    payer: &Pubkey,
+    did: &str,
+    authentication_id: &str,
+    credential_id: &str,
+    issuer_authentication: IssuerAuthentication,
+) -> Instruction {
+    let (recovery_id, signature) = issuer_authentication.signature();
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RevokeCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            verification_method: find_verification_method_address(did, authentication_id, &ID).0,
+            authentication: find_verification_relationship_address(
+                did,
+                &Relationship::Authentication,
+                authentication_id,
+                &ID,
+            )
+            .0,
+            authentication_signer: issuer_authentication.signer(),
+            instructions_sysvar: issuer_authentication.instructions_sysvar(),
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeCredential {
+            did: did.to_string(),
+            authentication_id: authentication_id.to_string(),
+            credential_id: credential_id.to_string(),
+            recovery_id,
+            signature,
+            webauthn_assertion: issuer_authentication.webauthn_assertion(),
+        }
+        .data(),
+    }
+}
+
+/// Closes a credential and returns its rent to `payer`, the account that paid for it. The
+/// `closer` pays for the `ClosedCredential` tombstone that keeps its id from being issued again.
+/// `issuer_authentication` holds the issuer's authentication key id and how the key signs
+/// the close message; a Solana key must be the `closer`. Without it the credential must be
+/// past its expiry grace period.
+pub fn close_credential(
+    closer: &Pubkey,
+    payer: &Pubkey,
+    issuer_did: &str,
+    credential_id: &str,
+    issuer_index_page: u32,
+    issuer_authentication: Option<(&str, IssuerAuthentication)>,
+) -> Instruction {
+    let (verification_method, authentication) = match issuer_authentication {
+        Some((authentication_id, _)) => (
+            Some(find_verification_method_address(issuer_did, authentication_id, &ID).0),
+            Some(
+                find_verification_relationship_address(
+                    issuer_did,
+                    &Relationship::Authentication,
+                    authentication_id,
+                    &ID,
+                )
+                .0,
+            ),
+        ),
+        None => (None, None),
+    };
+    let key_authentication = issuer_authentication.map(|(_, authentication)| authentication);
+    let (recovery_id, signature) = key_authentication.map_or((None, None), |a| a.signature());
+
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CloseCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            closed_credential: find_closed_credential_address(credential_id, &ID).0,
+            did_document: find_did_document_address(issuer_did, &ID).0,
+            issuer_index: find_issuer_credential_index_address(issuer_did, issuer_index_page, &ID)
+                .0,
+            verification_method,
+            authentication,
+            instructions_sysvar: key_authentication.and_then(|a| a.instructions_sysvar()),
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            closer: *closer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CloseCredential {
+            credential_id: credential_id.to_string(),
+            issuer_index_page,
+            recovery_id,
+            signature,
+            webauthn_assertion: key_authentication.and_then(|a| a.webauthn_assertion()),
+        }
+        .data(),
+    }
+}
+
+/// Acknowledges `credential_id` as its holder. A holder DID passes `(did, authentication_id)`
+/// of the key signing the acknowledgement message, while a key committed to with
+/// [`CredentialHolder::KeccakCommitment`] passes `None` and signs with `Secp256k1`.
+pub fn acknowledge_credential(
+    credential_id: &str,
+    holder_did: Option<(&str, &str)>,
+    holder_authentication: IssuerAuthentication,
+) -> Instruction {
+    let (verification_method, authentication) = match holder_did {
+        Some((did, authentication_id)) => (
+            Some(find_verification_method_address(did, authentication_id, &ID).0),
+            Some(
+                find_verification_relationship_address(
+                    did,
+                    &Relationship::Authentication,
+                    authentication_id,
+                    &ID,
+                )
+                .0,
+            ),
+        ),
+        None => (None, None),
+    };
+    let (recovery_id, signature) = holder_authentication.signature();
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AcknowledgeCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            verification_method,
+            authentication,
+            authentication_signer: holder_authentication.signer(),
+            instructions_sysvar: holder_authentication.instructions_sysvar(),
+            config: find_registry_config_address(&ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::AcknowledgeCredential {
+            credential_id: credential_id.to_string(),
+            recovery_id,
+            signature,
+            webauthn_assertion: holder_authentication.webauthn_assertion(),
+        }
+        .data(),
+    }
+}
+
+/// Fails unless `credential_id` is active and was issued with `field_merkle_root`, the
+/// Poseidon root a field-check proof is verified against. Meant to be invoked by other
+/// programs.
+pub fn check_credential(credential_id: &str, field_merkle_root: &[u8; 32]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CheckCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::CheckCredential {
+            credential_id: credential_id.to_string(),
+            field_merkle_root: *field_merkle_root,
+        }
+        .data(),
+    }
+}
+
+pub struct CreateDelegationArgs<'a> {
+    pub did: &'a str,
+    pub delegate: Pubkey,
+    /// Never reused for the same delegate, so that credentials issued under a revoked
+    /// delegation do not fall under a later one.
+    pub id: u64,
+    /// Ids of the `CredentialSchema`s the delegate may issue against must start with this
+    /// prefix.
+    pub schema_id_prefix: &'a str,
+    pub max_credentials: Option<u32>,
+    pub can_revoke: bool,
+    pub expire_at: Option<u64>,
+}
+
+pub fn create_delegation(controller: &Pubkey, args: CreateDelegationArgs) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CreateDelegation {
+            delegation: find_delegation_address(args.did, &args.delegate, args.id, &ID).0,
+            did_document: find_did_document_address(args.did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateDelegation {
+            did: args.did.to_string(),
+            delegate: args.delegate,
+            id: args.id,
+            schema_id_prefix: args.schema_id_prefix.to_string(),
+            max_credentials: args.max_credentials,
+            can_revoke: args.can_revoke,
+            expire_at: args.expire_at,
+        }
+        .data(),
+    }
+}
+
+/// Closes the delegation `id` of `did` to `delegate` and returns its rent to `controller`.
+pub fn revoke_delegation(
+    controller: &Pubkey,
+    did: &str,
+    delegate: &Pubkey,
+    id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RevokeDelegation {
+            delegation: find_delegation_address(did, delegate, id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeDelegation {
+            did: did.to_string(),
+            delegate: *delegate,
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Issues a credential for `did` signed by `delegate` under its delegation `delegation_id`.
+/// The `credential_schema` must be in the scope of the delegation, and the credential must
+/// not expire after it.
+#[allow(clippy::too_many_arguments)]
+pub fn add_delegated_credential(
+    payer: &Pubkey,
+    delegate: &Pubkey,
+    delegation_id: u64,
+    did: &str,
+    credential_id: &str,
+    expire_at: Option<u64>,
+    holder: Option<&CredentialHolder>,
+    credential_schema: &Pubkey,
+    field_merkle_root: Option<&[u8; 32]>,
+    issuer_index_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AddDelegatedCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            closed_credential: find_closed_credential_address(credential_id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            delegation: find_delegation_address(did, delegate, delegation_id, &ID).0,
+            credential_schema: *credential_schema,
+            issuer_index: find_issuer_credential_index_address(did, issuer_index_page, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            delegate: *delegate,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::AddDelegatedCredential {
+            did: did.to_string(),
+            credential_id: credential_id.to_string(),
+            expire_at,
+            holder: holder.cloned(),
+            field_merkle_root: field_merkle_root.copied(),
+            issuer_index_page,
+        }
+        .data(),
+    }
+}
+
+pub fn revoke_delegated_credential(
+    delegate: &Pubkey,
+    delegation_id: u64,
+    did: &str,
+    credential_id: &str,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RevokeDelegatedCredential {
+            credential_state: find_credential_state_address(credential_id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            delegation: find_delegation_address(did, delegate, delegation_id, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            delegate: *delegate,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeDelegatedCredential {
+            did: did.to_string(),
+            credential_id: credential_id.to_string(),
+        }
+        .data(),
+    }
+}
+
+pub struct CreateCredentialSchemaArgs<'a> {
+    pub did: &'a str,
+    pub schema_id: &'a str,
+    pub schema_version: u32,
+    pub name: &'a str,
+    pub fields: Vec<SchemaField>,
+    /// sha256 of the JSON Schema document.
+    pub content_hash: [u8; 32],
+}
+
+/// Publishes a credential schema authored by `args.did`, paid by its `controller`.
+pub fn create_credential_schema(
+    controller: &Pubkey,
+    args: CreateCredentialSchemaArgs,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CreateCredentialSchema {
+            credential_schema: find_credential_schema_address(
+                args.did,
+                args.schema_id,
+                args.schema_version,
+                &ID,
+            )
+            .0,
+            did_document: find_did_document_address(args.did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateCredentialSchema {
+            did: args.did.to_string(),
+            schema_id: args.schema_id.to_string(),
+            schema_version: args.schema_version,
+            name: args.name.to_string(),
+            fields: args.fields,
+            content_hash: args.content_hash,
+        }
+        .data(),
+    }
+}
+
+/// Stops issuance against a schema of `did`; credentials already issued keep referring to it.
+pub fn deprecate_credential_schema(
+    controller: &Pubkey,
+    did: &str,
+    schema_id: &str,
+    schema_version: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::DeprecateCredentialSchema {
+            credential_schema: find_credential_schema_address(did, schema_id, schema_version, &ID)
+                .0,
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+        }
+        .to_account_metas(None),
+        data: instruction::DeprecateCredentialSchema {
+            did: did.to_string(),
+            schema_id: schema_id.to_string(),
+            schema_version,
+        }
+        .data(),
+    }
+}
+
+pub struct CreateVerificationSchemaArgs<'a> {
+    pub did: &'a str,
+    pub schema_id: &'a str,
+    pub schema_version: u32,
+    pub name: &'a str,
+    /// `fieldMerkleRoot` of each credential check, see [`crate::field::parse_field_element`].
+    pub check_roots: Vec<[u8; 32]>,
+    pub requested_fields: Vec<String>,
+}
+
+/// Publishes a presentation request of the verifier `args.did`, paid by its `controller`.
+pub fn create_verification_schema(
+    controller: &Pubkey,
+    args: CreateVerificationSchemaArgs,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CreateVerificationSchema {
+            verification_schema: find_verification_schema_address(
+                args.did,
+                args.schema_id,
+                args.schema_version,
+                &ID,
+            )
+            .0,
+            did_document: find_did_document_address(args.did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateVerificationSchema {
+            did: args.did.to_string(),
+            schema_id: args.schema_id.to_string(),
+            schema_version: args.schema_version,
+            name: args.name.to_string(),
+            check_roots: args.check_roots,
+            requested_fields: args.requested_fields,
+        }
+        .data(),
+    }
+}
+
+pub fn deprecate_verification_schema(
+    controller: &Pubkey,
+    did: &str,
+    schema_id: &str,
+    schema_version: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::DeprecateVerificationSchema {
+            verification_schema: find_verification_schema_address(
+                did,
+                schema_id,
+                schema_version,
+                &ID,
+            )
+            .0,
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+        }
+        .to_account_metas(None),
+        data: instruction::DeprecateVerificationSchema {
+            did: did.to_string(),
+            schema_id: schema_id.to_string(),
+            schema_version,
+        }
+        .data(),
+    }
+}
+
+pub struct AccreditIssuerArgs<'a> {
+    pub did: &'a str,
+    pub issuer_did: &'a str,
+    pub scope: AccreditationScope,
+    /// Levels of accreditations `issuer_did` may grant below it for `scope`.
+    pub max_depth: u8,
+    pub expire_at: Option<u64>,
+}
+
+/// Accredits `args.issuer_did` on behalf of the authority `args.did`, paid by its `controller`.
+pub fn accredit_issuer(controller: &Pubkey, args: AccreditIssuerArgs) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AccreditIssuer {
+            accreditation: find_accreditation_address(args.did, args.issuer_did, &args.scope, &ID)
+                .0,
+            did_document: find_did_document_address(args.did, &ID).0,
+            issuer_did_document: find_did_document_address(args.issuer_did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::AccreditIssuer {
+            did: args.did.to_string(),
+            issuer_did: args.issuer_did.to_string(),
+            scope: args.scope,
+            max_depth: args.max_depth,
+            expire_at: args.expire_at,
+        }
+        .data(),
+    }
+}
+
+pub fn revoke_accreditation(
+    controller: &Pubkey,
+    did: &str,
+    issuer_did: &str,
+    scope: AccreditationScope,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RevokeAccreditation {
+            accreditation: find_accreditation_address(did, issuer_did, &scope, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            issuer_did_document: find_did_document_address(issuer_did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeAccreditation {
+            did: did.to_string(),
+            issuer_did: issuer_did.to_string(),
+            scope,
+        }
+        .data(),
+    }
+}
+
+/// Succeeds only when `issuer_did` is accredited for `scope` by `root_did` through the
+/// authorities in `via`, ordered from the one that accredited the issuer up to the one the
+/// root accredited. With a `credential_id`, the credential must also be active and issued by
+/// `issuer_did`.
+pub fn check_accreditation(
+    root_did: &str,
+    via: &[&str],
+    issuer_did: &str,
+    scope: AccreditationScope,
+    credential_id: Option<&str>,
+) -> Instruction {
+    let mut accounts = accounts::CheckAccreditation {
+        root_did_document: find_did_document_address(root_did, &ID).0,
+        issuer_did_document: find_did_document_address(issuer_did, &ID).0,
+        credential_state: credential_id
+            .map(|credential_id| find_credential_state_address(credential_id, &ID).0),
+    }
+    .to_account_metas(None);
+    let chain = std::iter::once(issuer_did)
+        .chain(via.iter().copied())
+        .chain(std::iter::once(root_did))
+        .collect::<Vec<_>>();
+    accounts.extend(chain.windows(2).map(|link| {
+        AccountMeta::new_readonly(
+            find_accreditation_address(link[1], link[0], &scope, &ID).0,
+            false,
+        )
+    }));
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction::CheckAccreditation {
+            root_did: root_did.to_string(),
+            issuer_did: issuer_did.to_string(),
+            scope,
+        }
+        .data(),
+    }
+}
+
+/// Replaces the guardians of `did`. An empty set with a zero threshold turns recovery off.
+pub fn set_guardians(
+    controller: &Pubkey,
+    did: &str,
+    guardians: Vec<Guardian>,
+    guardian_threshold: u8,
+    recovery_delay: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::SetGuardians {
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::SetGuardians {
+            did: did.to_string(),
+            guardians,
+            guardian_threshold,
+            recovery_delay,
+        }
+        .data(),
+    }
+}
+
+/// Replaces the `alsoKnownAs` identifiers of `did`. An empty list removes them all.
+pub fn set_also_known_as(
+    controller: &Pubkey,
+    did: &str,
+    also_known_as: Vec<String>,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::SetAlsoKnownAs {
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::SetAlsoKnownAs {
+            did: did.to_string(),
+            also_known_as,
+        }
+        .data(),
+    }
+}
+
+/// Starts the recovery of `did` to `new_controller` with the approval of `guardian`.
+/// `guardian_did` is the DID `guardian` controls when the guardian is a DID rather than a key.
+pub fn initiate_recovery(
+    payer: &Pubkey,
+    guardian: &Pubkey,
+    guardian_did: Option<&str>,
+    did: &str,
+    new_controller: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::InitiateRecovery {
+            recovery: find_recovery_address(did, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            guardian_did_document: guardian_did.map(|did| find_did_document_address(did, &ID).0),
+            config: find_registry_config_address(&ID).0,
+            guardian: *guardian,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitiateRecovery {
+            did: did.to_string(),
+            new_controller: *new_controller,
+        }
+        .data(),
+    }
+}
+
+pub fn approve_recovery(guardian: &Pubkey, guardian_did: Option<&str>, did: &str) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ApproveRecovery {
+            recovery: find_recovery_address(did, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            guardian_did_document: guardian_did.map(|did| find_did_document_address(did, &ID).0),
+            config: find_registry_config_address(&ID).0,
+            guardian: *guardian,
+        }
+        .to_account_metas(None),
+        data: instruction::ApproveRecovery {
+            did: did.to_string(),
+        }
+        .data(),
+    }
+}
+
+/// Cancels the pending recovery of `did` and returns its rent to `payer`, who started it.
+/// Without the `controller`, the recovery must have expired short of guardian approvals;
+/// guardians may then close it and initiate another one in the same transaction.
+pub fn cancel_recovery(controller: Option<&Pubkey>, payer: &Pubkey, did: &str) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CancelRecovery {
+            recovery: find_recovery_address(did, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            payer: *payer,
+            controller: controller.copied(),
+        }
+        .to_account_metas(None),
+        data: instruction::CancelRecovery {
+            did: did.to_string(),
+        }
+        .data(),
+    }
+}
+
+/// Hands `did` over from `controller` to the `new_controller` of its approved recovery.
+pub fn execute_recovery(
+    executor: &Pubkey,
+    payer: &Pubkey,
+    did: &str,
+    controller: &Pubkey,
+    new_controller: &Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ExecuteRecovery {
+            recovery: find_recovery_address(did, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            change_log: find_did_change_log_address(did, &ID).0,
+            from_controller_index: find_controller_did_index_address(
+                controller,
+                from_index_page,
+                &ID,
+            )
+            .0,
+            to_controller_index: find_controller_did_index_address(
+                new_controller,
+                to_index_page,
+                &ID,
+            )
+            .0,
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            executor: *executor,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ExecuteRecovery {
+            did: did.to_string(),
+            from_index_page,
+            to_index_page,
+        }
+        .data(),
+    }
+}
+
+/// Raises the operation delay of `did`; lowering it must be proposed like other operations.
+pub fn set_operation_delay(controller: &Pubkey, did: &str, operation_delay: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: set_operation_delay_accounts(controller, did).to_account_metas(None),
+        data: instruction::SetOperationDelay {
+            did: did.to_string(),
+            operation_delay,
+        }
+        .data(),
+    }
+}
+
+fn set_operation_delay_accounts(controller: &Pubkey, did: &str) -> accounts::SetOperationDelay {
+    accounts::SetOperationDelay {
+        did_document: find_did_document_address(did, &ID).0,
+        config: find_registry_config_address(&ID).0,
+        controller: *controller,
+    }
+}
+
+/// Announces `operation` on a time-locked `did`. `id` tells apart operations pending at once.
+pub fn propose_operation(
+    controller: &Pubkey,
+    did: &str,
+    id: u64,
+    operation: DidOperation,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ProposeOperation {
+            pending_operation: find_pending_operation_address(did, id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            controller: *controller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ProposeOperation {
+            did: did.to_string(),
+            id,
+            operation,
+        }
+        .data(),
+    }
+}
+
+pub fn cancel_operation(controller: &Pubkey, did: &str, id: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CancelOperation {
+            pending_operation: find_pending_operation_address(did, id, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            controller: *controller,
+        }
+        .to_account_metas(None),
+        data: instruction::CancelOperation {
+            did: did.to_string(),
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Executes the pending operation `id`, a [`DidOperation::TransferController`].
+pub fn execute_transfer_did_controller(
+    controller: &Pubkey,
+    did: &str,
+    new_controller: &Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+    id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ExecuteTransferDidController {
+            operation: transfer_did_controller_accounts(
+                controller,
+                did,
+                new_controller,
+                from_index_page,
+                to_index_page,
+            ),
+            pending_operation: find_pending_operation_address(did, id, &ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::ExecuteTransferDidController {
+            did: did.to_string(),
+            new_controller: *new_controller,
+            from_index_page,
+            to_index_page,
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Executes the pending operation `id`, a [`DidOperation::AddVerificationMethod`].
+pub fn execute_add_verification_method(
+    controller: &Pubkey,
+    args: AddVerificationMethodArgs,
+    id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ExecuteAddVerificationMethod {
+            operation: add_verification_method_accounts(controller, &args),
+            pending_operation: find_pending_operation_address(args.did, id, &ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::ExecuteAddVerificationMethod {
+            did: args.did.to_string(),
+            key_id: args.key_id.to_string(),
+            r#type: args.r#type.to_string(),
+            public_key_multibase: args.public_key_multibase.to_string(),
+            controller: args.key_controller,
+            valid_from: args.valid_from,
+            valid_until: args.valid_until,
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Executes the pending operation `id`, a [`DidOperation::AddVerificationRelationship`].
+pub fn execute_add_verification_relationship(
+    controller: &Pubkey,
+    did: &str,
+    relationship: Relationship,
+    key_id: &str,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ExecuteAddVerificationRelationship {
+            operation: add_verification_relationship_accounts(
+                controller,
+                did,
+                &relationship,
+                key_id,
+            ),
+            pending_operation: find_pending_operation_address(did, id, &ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::ExecuteAddVerificationRelationship {
+            did: did.to_string(),
+            relationship,
+            key_id: key_id.to_string(),
+            valid_from,
+            valid_until,
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Executes the pending operation `id`, a [`DidOperation::SetOperationDelay`].
+pub fn execute_set_operation_delay(
+    controller: &Pubkey,
+    did: &str,
+    operation_delay: u64,
+    id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ExecuteSetOperationDelay {
+            operation: set_operation_delay_accounts(controller, did),
+            pending_operation: find_pending_operation_address(did, id, &ID).0,
+        }
+        .to_account_metas(None),
+        data: instruction::ExecuteSetOperationDelay {
+            did: did.to_string(),
+            operation_delay,
+            id,
+        }
+        .data(),
+    }
+}
+
+/// Issues a login challenge to `session_key`, which signs the transaction, for `did` and
+/// `audience`. The nonce to answer is read back from the challenge account.
+pub fn request_auth_challenge(
+    payer: &Pubkey,
+    session_key: &Pubkey,
+    did: &str,
+    audience: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RequestAuthChallenge {
+            challenge: find_auth_challenge_address(did, session_key, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            config: find_registry_config_address(&ID).0,
+            session_key: *session_key,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RequestAuthChallenge {
+            did: did.to_string(),
+            audience: *audience,
+        }
+        .data(),
+    }
+}
+
+/// Answers the challenge of `session_key` with the `authentication_id` key of `did`, opening
+/// a session of `duration` milliseconds. The challenge rent goes back to `challenge_payer`.
+pub fn create_auth_session(
+    payer: &Pubkey,
+    challenge_payer: &Pubkey,
+    did: &str,
+    session_key: &Pubkey,
+    authentication_id: &str,
+    duration: u64,
+    authentication: IssuerAuthentication,
+) -> Instruction {
+    let (recovery_id, signature) = authentication.signature();
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CreateAuthSession {
+            auth_session: find_auth_session_address(did, session_key, &ID).0,
+            challenge: find_auth_challenge_address(did, session_key, &ID).0,
+            challenge_payer: *challenge_payer,
+            did_document: find_did_document_address(did, &ID).0,
+            verification_method: find_verification_method_address(did, authentication_id, &ID).0,
+            authentication: find_verification_relationship_address(
+                did,
+                &Relationship::Authentication,
+                authentication_id,
+                &ID,
+            )
+            .0,
+            authentication_signer: authentication.signer(),
+            instructions_sysvar: authentication.instructions_sysvar(),
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateAuthSession {
+            did: did.to_string(),
+            authentication_id: authentication_id.to_string(),
+            duration,
+            recovery_id,
+            signature,
+            webauthn_assertion: authentication.webauthn_assertion(),
+        }
+        .data(),
+    }
+}
+
+/// Fails unless `session_key`, which signs the transaction, is logged in to `did` for
+/// `audience`. Meant to be invoked by other programs.
+pub fn check_auth_session(session_key: &Pubkey, did: &str, audience: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CheckAuthSession {
+            auth_session: find_auth_session_address(did, session_key, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            session_key: *session_key,
+        }
+        .to_account_metas(None),
+        data: instruction::CheckAuthSession {
+            did: did.to_string(),
+            audience: *audience,
+        }
+        .data(),
+    }
+}
+
+/// Ends the session of `session_key` on `did` and returns its rent to `payer`. `closer` is
+/// the session key, the DID controller, or anyone once the session expired.
+pub fn close_auth_session(
+    closer: &Pubkey,
+    payer: &Pubkey,
+    did: &str,
+    session_key: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CloseAuthSession {
+            auth_session: find_auth_session_address(did, session_key, &ID).0,
+            did_document: find_did_document_address(did, &ID).0,
+            payer: *payer,
+            closer: *closer,
+        }
+        .to_account_metas(None),
+        data: instruction::CloseAuthSession {
+            did: did.to_string(),
+        }
+        .data(),
+    }
+}
+
+/// Creates the registry config. `authority` must be the program upgrade authority.
+pub fn initialize_config(
+    authority: &Pubkey,
+    admin: &Pubkey,
+    allowed_key_types: Vec<String>,
+    issuance_fee: Option<u64>,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::InitializeConfig {
+            config: find_registry_config_address(&ID).0,
+            program: ID,
+            program_data: find_program_data_address(&ID).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeConfig {
+            admin: *admin,
+            allowed_key_types,
+            issuance_fee,
+        }
+        .data(),
+    }
+}
+
+pub fn update_config(admin: &Pubkey, update: ConfigUpdate) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::UpdateConfig {
+            config: find_registry_config_address(&ID).0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: instruction::UpdateConfig { update }.data(),
+    }
+}
+
+/// Moves `amount` lamports of collected issuance fees to `recipient`.
+pub fn withdraw_fees(admin: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::WithdrawFees {
+            config: find_registry_config_address(&ID).0,
+            admin: *admin,
+            recipient: *recipient,
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawFees { amount }.data(),
+    }
+}
+
+/// Upgrades `account` in place to the current layout of its type. `payer` tops up the rent
+/// of the grown account.
+pub fn migrate_account(payer: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::MigrateAccount {
+            account: *account,
+            config: find_registry_config_address(&ID).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::MigrateAccount {}.data(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+
+    use super::*;
+    use crate::signer::Secp256k1Signer;
+
+    #[test]
+    fn test_add_credential_instruction() {
+        let payer = Pubkey::new_unique();
+        let did = "did:zuni:solana:sdk";
+        let authentication_id = "did:zuni:solana:sdk#key-0";
+        let field_merkle_root = [5u8; 32];
+        let signature = Secp256k1Signer::from_bytes(&[7u8; 32])
+            .unwrap()
+            .sign_add_credential(
+                "credential-1",
+                Some(1),
+                None,
+                None,
+                Some(&field_merkle_root),
+            );
+        let ix = add_credential(
+            &payer,
+            did,
+            authentication_id,
+            "credential-1",
+            Some(1),
+            None,
+            None,
+            Some(&field_merkle_root),
+            IssuerAuthentication::Secp256k1(&signature),
+            0,
+        );
+
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            find_credential_state_address("credential-1", &ID).0
+        );
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            find_closed_credential_address("credential-1", &ID).0
+        );
+        assert!(!ix.accounts[1].is_writable);
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            find_verification_relationship_address(
+                did,
+                &Relationship::Authentication,
+                authentication_id,
+                &ID
+            )
+            .0
+        );
+        // Absent optional accounts are passed as the program id.
+        assert_eq!(ix.accounts[5].pubkey, ID);
+        assert_eq!(ix.accounts[6].pubkey, ID);
+        assert_eq!(ix.accounts[7].pubkey, ID);
+        assert_eq!(
+            ix.accounts[8].pubkey,
+            find_issuer_credential_index_address(did, 0, &ID).0
+        );
+        assert!(ix.accounts[10].is_signer && ix.accounts[10].is_writable);
+
+        assert_eq!(ix.data[..8], instruction::AddCredential::discriminator());
+        let args = instruction::AddCredential::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.did, did);
+        assert_eq!(args.expire_at, Some(1));
+        assert_eq!(args.field_merkle_root, Some(field_merkle_root));
+        assert_eq!(args.recovery_id, Some(signature.recovery_id));
+        assert_eq!(args.signature, Some(signature.signature));
+    }
+
+    #[test]
+    fn test_add_credential_instruction_with_solana_key() {
+        let issuer = Pubkey::new_unique();
+        let ix = add_credential(
+            &issuer,
+            "did:zuni:solana:sdk",
+            "did:zuni:solana:sdk#key-0",
+            "credential-1",
+            None,
+            None,
+            None,
+            None,
+            IssuerAuthentication::Signer(issuer),
+            0,
+        );
+
+        assert_eq!(ix.accounts[5].pubkey, issuer);
+        assert!(ix.accounts[5].is_signer);
+        let args = instruction::AddCredential::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.recovery_id, None);
+        assert_eq!(args.signature, None);
+    }
+
+    #[test]
+    fn test_add_credential_instruction_with_schema() {
+        let issuer = Pubkey::new_unique();
+        let schema = find_credential_schema_address("did:zuni:solana:sdk", "degree", 1, &ID).0;
+        let ix = add_credential(
+            &issuer,
+            "did:zuni:solana:sdk",
+            "did:zuni:solana:sdk#key-0",
+            "credential-1",
+            None,
+            None,
+            Some(&schema),
+            None,
+            IssuerAuthentication::Signer(issuer),
+            0,
+        );
+
+        assert_eq!(ix.accounts[7].pubkey, schema);
+        assert!(!ix.accounts[7].is_writable);
+    }
+
+    #[test]
+    fn test_add_credential_instruction_with_passkey() {
+        let assertion = WebAuthnAssertion {
+            authenticator_data: vec![1u8; 37],
+            client_data_json: b"{}".to_vec(),
+        };
+        let ix = add_credential(
+            &Pubkey::new_unique(),
+            "did:zuni:solana:sdk",
+            "did:zuni:solana:sdk#key-0",
+            "credential-1",
+            None,
+            None,
+            None,
+            None,
+            IssuerAuthentication::WebAuthn(&assertion),
+            0,
+        );
+
+        assert_eq!(ix.accounts[5].pubkey, ID);
+        assert_eq!(ix.accounts[6].pubkey, sysvar_instructions::ID);
+        let args = instruction::AddCredential::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.signature, None);
+        assert_eq!(args.webauthn_assertion, Some(assertion));
+    }
+
+    #[test]
+    fn test_check_credential_instruction() {
+        let ix = check_credential("credential-1", &[5u8; 32]);
+
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            find_credential_state_address("credential-1", &ID).0
+        );
+        assert!(!ix.accounts[0].is_writable);
+        let args = instruction::CheckCredential::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.field_merkle_root, [5u8; 32]);
+    }
+
+    #[test]
+    fn test_check_accreditation_instruction() {
+        let scope = AccreditationScope::CredentialType("MedicalLicense".to_string());
+        let ix = check_accreditation(
+            "did:zuni:solana:ministry",
+            &["did:zuni:solana:board"],
+            "did:zuni:solana:clinic",
+            scope.clone(),
+            Some("license-1"),
+        );
+
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            find_credential_state_address("license-1", &ID).0
+        );
+        let chain: Vec<Pubkey> = ix.accounts[3..].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            chain,
+            [
+                find_accreditation_address(
+                    "did:zuni:solana:board",
+                    "did:zuni:solana:clinic",
+                    &scope,
+                    &ID
+                )
+                .0,
+                find_accreditation_address(
+                    "did:zuni:solana:ministry",
+                    "did:zuni:solana:board",
+                    &scope,
+                    &ID
+                )
+                .0,
+            ]
+        );
+        assert!(ix.accounts.iter().all(|meta| !meta.is_writable));
+    }
+
+    #[test]
+    fn test_acknowledge_credential_instruction() {
+        let holder = Secp256k1Signer::from_bytes(&[9u8; 32]).unwrap();
+        let signature = holder.sign_acknowledge_credential("credential-1");
+        let ix = acknowledge_credential(
+            "credential-1",
+            None,
+            IssuerAuthentication::Secp256k1(&signature),
+        );
+
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            find_credential_state_address("credential-1", &ID).0
+        );
+        assert!(ix.accounts[0].is_writable);
+        for account in &ix.accounts[1..5] {
+            assert_eq!(account.pubkey, ID);
+        }
+        let args = instruction::AcknowledgeCredential::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.signature, Some(signature.signature));
+
+        let did = "did:zuni:solana:holder";
+        let ix = acknowledge_credential(
+            "credential-1",
+            Some((did, "did:zuni:solana:holder#key-0")),
+            IssuerAuthentication::Signer(Pubkey::new_unique()),
+        );
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            find_verification_method_address(did, "did:zuni:solana:holder#key-0", &ID).0
+        );
+        assert!(ix.accounts[3].is_signer);
+    }
+
+    #[test]
+    fn test_create_auth_session_instruction() {
+        let payer = Pubkey::new_unique();
+        let session_key = Pubkey::new_unique();
+        let did = "did:zuni:solana:sdk";
+        let signature = Secp256k1Signer::from_bytes(&[7u8; 32])
+            .unwrap()
+            .sign(&[1u8; 32]);
+        let ix = create_auth_session(
+            &payer,
+            &payer,
+            did,
+            &session_key,
+            "did:zuni:solana:sdk#key-0",
+            60_000,
+            IssuerAuthentication::Secp256k1(&signature),
+        );
+
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            find_auth_session_address(did, &session_key, &ID).0
+        );
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            find_auth_challenge_address(did, &session_key, &ID).0
+        );
+        assert!(ix.accounts[1].is_writable && ix.accounts[2].is_writable);
+        assert_eq!(ix.accounts[6].pubkey, ID);
+
+        let args = instruction::CreateAuthSession::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.duration, 60_000);
+        assert_eq!(args.signature, Some(signature.signature));
+    }
+}
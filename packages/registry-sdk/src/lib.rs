@@ -0,0 +1,18 @@
+pub mod account;
+pub mod error;
+pub mod field;
+pub mod instruction;
+pub mod pda;
+pub mod signer;
+pub mod transaction;
+pub mod webauthn;
+
+pub use account::RegistryAccount;
+pub use error::SdkError;
+pub use instruction::IssuerAuthentication;
+pub use signer::Secp256k1Signer;
+pub use verifiable_data_registry::state::{
+    AccreditationScope, ConfigUpdate, CredentialHolder, DidOperation, Guardian, Relationship,
+    SchemaField, SchemaFieldType, Secp256k1Signature, WebAuthnAssertion,
+};
+pub use verifiable_data_registry::ID;
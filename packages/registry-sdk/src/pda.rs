@@ -0,0 +1,251 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{bpf_loader_upgradeable, keccak};
+use verifiable_data_registry::state::{
+    AccreditationScope, Relationship, ACCREDITATION_SEED, AUTH_CHALLENGE_SEED, AUTH_SESSION_SEED,
+    CLOSED_CREDENTIAL_SEED, CONTROLLER_DID_INDEX_SEED, CREDENTIAL_SCHEMA_SEED, DELEGATION_SEED,
+    DID_CHANGE_LOG_SEED, ISSUER_CREDENTIAL_INDEX_SEED, PENDING_OPERATION_SEED, RECOVERY_SEED,
+    REGISTRY_CONFIG_SEED, VERIFICATION_SCHEMA_SEED,
+};
+
+pub fn did_document_seed(did: &str) -> keccak::Hash {
+    keccak::hash(did.as_bytes())
+}
+
+pub fn verification_method_seed(did: &str, key_id: &str) -> keccak::Hash {
+    keccak::hash([did.as_bytes(), key_id.as_bytes()].concat().as_slice())
+}
+
+pub fn verification_relationship_seed(
+    did: &str,
+    relationship: &Relationship,
+    key_id: &str,
+) -> keccak::Hash {
+    keccak::hash(
+        [did.as_bytes(), relationship.as_bytes(), key_id.as_bytes()]
+            .concat()
+            .as_slice(),
+    )
+}
+
+pub fn credential_state_seed(credential_id: &str) -> keccak::Hash {
+    keccak::hash(credential_id.as_bytes())
+}
+
+pub fn find_did_document_address(did: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[did_document_seed(did).as_ref()], program_id)
+}
+
+pub fn find_verification_method_address(
+    did: &str,
+    key_id: &str,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[verification_method_seed(did, key_id).as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_verification_relationship_address(
+    did: &str,
+    relationship: &Relationship,
+    key_id: &str,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[verification_relationship_seed(did, relationship, key_id).as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_credential_state_address(credential_id: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[credential_state_seed(credential_id).as_ref()], program_id)
+}
+
+pub fn find_closed_credential_address(credential_id: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CLOSED_CREDENTIAL_SEED,
+            credential_state_seed(credential_id).as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_controller_did_index_address(
+    controller: &Pubkey,
+    page: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CONTROLLER_DID_INDEX_SEED,
+            controller.as_ref(),
+            page.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Index page of the credentials issued by `did`, keyed by its `DidDocument` address.
+pub fn find_issuer_credential_index_address(
+    did: &str,
+    page: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let issuer = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            ISSUER_CREDENTIAL_INDEX_SEED,
+            issuer.as_ref(),
+            page.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Delegation `id` granted by `did` to `delegate`, keyed by the `DidDocument` address.
+pub fn find_delegation_address(
+    did: &str,
+    delegate: &Pubkey,
+    id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            DELEGATION_SEED,
+            did_document.as_ref(),
+            delegate.as_ref(),
+            id.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Pending guardian recovery of `did`, keyed by the `DidDocument` address.
+pub fn find_recovery_address(did: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(&[RECOVERY_SEED, did_document.as_ref()], program_id)
+}
+
+/// Change log of `did`, keyed by the `DidDocument` address.
+pub fn find_did_change_log_address(did: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(&[DID_CHANGE_LOG_SEED, did_document.as_ref()], program_id)
+}
+
+/// Operation `id` pending on `did`, keyed by the `DidDocument` address.
+pub fn find_pending_operation_address(did: &str, id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            PENDING_OPERATION_SEED,
+            did_document.as_ref(),
+            id.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Login challenge issued to `session_key` for `did`, keyed by the `DidDocument` address.
+pub fn find_auth_challenge_address(
+    did: &str,
+    session_key: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            AUTH_CHALLENGE_SEED,
+            did_document.as_ref(),
+            session_key.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Session of `session_key` logged in to `did`, keyed by the `DidDocument` address.
+pub fn find_auth_session_address(
+    did: &str,
+    session_key: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            AUTH_SESSION_SEED,
+            did_document.as_ref(),
+            session_key.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Version `schema_version` of the schema `schema_id` authored by `did`, keyed by the
+/// `DidDocument` address.
+pub fn find_credential_schema_address(
+    did: &str,
+    schema_id: &str,
+    schema_version: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            CREDENTIAL_SCHEMA_SEED,
+            did_document.as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Version `schema_version` of the presentation request `schema_id` of the verifier `did`.
+pub fn find_verification_schema_address(
+    did: &str,
+    schema_id: &str,
+    schema_version: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let did_document = find_did_document_address(did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            VERIFICATION_SCHEMA_SEED,
+            did_document.as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Accreditation granted by `authority_did` to `issuer_did` for `scope`.
+pub fn find_accreditation_address(
+    authority_did: &str,
+    issuer_did: &str,
+    scope: &AccreditationScope,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let authority = find_did_document_address(authority_did, program_id).0;
+    let issuer = find_did_document_address(issuer_did, program_id).0;
+    Pubkey::find_program_address(
+        &[
+            ACCREDITATION_SEED,
+            authority.as_ref(),
+            issuer.as_ref(),
+            scope.seed().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_registry_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_CONFIG_SEED], program_id)
+}
+
+/// `ProgramData` account of the upgradeable program, holding its upgrade authority.
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID)
+}
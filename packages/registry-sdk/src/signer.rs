@@ -0,0 +1,318 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::keccak;
+use libsecp256k1::{Message, PublicKey, SecretKey};
+use verifiable_data_registry::state::{
+    AuthChallenge, CredentialHolder, CredentialState, Secp256k1Signature,
+};
+
+use crate::error::SdkError;
+
+/// Hash the issuer signs to register `credential_id` with `add_credential`, bound to its
+/// expiry, `holder`, `CredentialSchema` address and field Merkle root, or to their absence.
+pub fn add_credential_message(
+    credential_id: &str,
+    expire_at: Option<u64>,
+    holder: Option<&CredentialHolder>,
+    schema: Option<&Pubkey>,
+    field_merkle_root: Option<&[u8; 32]>,
+) -> [u8; 32] {
+    CredentialState::issuance_message_hash(
+        credential_id,
+        expire_at,
+        holder,
+        schema,
+        field_merkle_root,
+    )
+}
+
+/// Hash the issuer `did` signs to revoke `credential_id` with `revoke_credential`.
+pub fn revoke_credential_message(did: &str, credential_id: &str) -> [u8; 32] {
+    CredentialState::revocation_message_hash(did, credential_id)
+}
+
+/// Hash the issuer signs to close `credential_id` with `close_credential`.
+pub fn close_credential_message(credential_id: &str) -> [u8; 32] {
+    CredentialState::close_message_hash(credential_id)
+}
+
+/// Hash the holder signs to acknowledge `credential_id` with `acknowledge_credential`.
+pub fn acknowledge_credential_message(credential_id: &str) -> [u8; 32] {
+    CredentialState::acknowledgement_message_hash(credential_id)
+}
+
+/// Commitment recording a secp256k1 holder key as the holder of a credential, from the hex
+/// `holderPublicKey` of the TypeScript library: the keccak hash of the uncompressed key
+/// without its `04` prefix, as recovered from the holder's signatures.
+pub fn holder_key_commitment(holder_public_key: &str) -> Result<CredentialHolder, SdkError> {
+    let hex = holder_public_key
+        .strip_prefix("0x")
+        .unwrap_or(holder_public_key);
+    let (_, bytes) = multibase::decode(format!("f{}", hex.to_ascii_lowercase()))
+        .map_err(|err| SdkError::InvalidHex(err.to_string()))?;
+    let public_key = match bytes.len() {
+        64 => &bytes[..],
+        65 if bytes[0] == 0x04 => &bytes[1..],
+        _ => {
+            return Err(SdkError::InvalidHex(
+                "expected an uncompressed secp256k1 public key".to_string(),
+            ))
+        }
+    };
+    Ok(CredentialHolder::KeccakCommitment(
+        keccak::hash(public_key).to_bytes(),
+    ))
+}
+
+/// Local secp256k1 key used to sign credential operations for an
+/// `EcdsaSecp256k1VerificationKey2019` verification method.
+#[derive(Clone, Debug)]
+pub struct Secp256k1Signer {
+    secret_key: SecretKey,
+}
+
+impl Secp256k1Signer {
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self { secret_key }
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, SdkError> {
+        SecretKey::parse(bytes)
+            .map(Self::new)
+            .map_err(|_| SdkError::InvalidSecretKey)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, SdkError> {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        let (_, bytes) = multibase::decode(format!("f{}", hex.to_ascii_lowercase()))
+            .map_err(|err| SdkError::InvalidHex(err.to_string()))?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| SdkError::InvalidHex("expected 32 bytes".to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Uncompressed public key without the `0x04` prefix, as returned by `secp256k1_recover`.
+    pub fn public_key(&self) -> [u8; 64] {
+        let serialized = PublicKey::from_secret_key(&self.secret_key).serialize();
+        let mut public_key = [0u8; 64];
+        public_key.copy_from_slice(&serialized[1..]);
+        public_key
+    }
+
+    /// Value to register as `public_key_multibase` for this key.
+    pub fn public_key_multibase(&self) -> String {
+        multibase::encode(multibase::Base::Base16Lower, self.public_key())
+    }
+
+    pub fn sign(&self, message_hash: &[u8; 32]) -> Secp256k1Signature {
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&Message::parse(message_hash), &self.secret_key);
+        Secp256k1Signature {
+            recovery_id: recovery_id.serialize(),
+            signature: signature.serialize(),
+        }
+    }
+
+    pub fn sign_add_credential(
+        &self,
+        credential_id: &str,
+        expire_at: Option<u64>,
+        holder: Option<&CredentialHolder>,
+        schema: Option<&Pubkey>,
+        field_merkle_root: Option<&[u8; 32]>,
+    ) -> Secp256k1Signature {
+        self.sign(&add_credential_message(
+            credential_id,
+            expire_at,
+            holder,
+            schema,
+            field_merkle_root,
+        ))
+    }
+
+    pub fn sign_revoke_credential(&self, did: &str, credential_id: &str) -> Secp256k1Signature {
+        self.sign(&revoke_credential_message(did, credential_id))
+    }
+
+    pub fn sign_close_credential(&self, credential_id: &str) -> Secp256k1Signature {
+        self.sign(&close_credential_message(credential_id))
+    }
+
+    pub fn sign_acknowledge_credential(&self, credential_id: &str) -> Secp256k1Signature {
+        self.sign(&acknowledge_credential_message(credential_id))
+    }
+
+    /// Commitment recording this key as the holder of a credential, the keccak hash of
+    /// [`public_key`](Self::public_key).
+    pub fn holder_commitment(&self) -> CredentialHolder {
+        CredentialHolder::KeccakCommitment(keccak::hash(&self.public_key()).to_bytes())
+    }
+
+    /// Answers `challenge`, logging its session key in with `create_auth_session`.
+    pub fn sign_auth_challenge(&self, challenge: &AuthChallenge) -> Secp256k1Signature {
+        self.sign(&challenge.message_hash())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::secp256k1_recover::secp256k1_recover;
+
+    use super::*;
+
+    #[test]
+    fn test_signature_recovers_registered_key() {
+        let signer = Secp256k1Signer::from_hex(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let (_, registered_key) = multibase::decode(signer.public_key_multibase()).unwrap();
+        let challenge = AuthChallenge {
+            version: 1,
+            did_document: Pubkey::new_unique(),
+            session_key: Pubkey::new_unique(),
+            audience: Pubkey::new_unique(),
+            nonce: [3u8; 32],
+            expire_at: 0,
+            payer: Pubkey::new_unique(),
+        };
+        let holder = CredentialHolder::Did("did:zuni:solana:holder".to_string());
+        let schema = Pubkey::new_unique();
+        let root = [5u8; 32];
+
+        for (message, signature) in [
+            (
+                add_credential_message("credential-1", None, None, None, None),
+                signer.sign_add_credential("credential-1", None, None, None, None),
+            ),
+            (
+                add_credential_message(
+                    "credential-1",
+                    Some(7),
+                    Some(&holder),
+                    Some(&schema),
+                    Some(&root),
+                ),
+                signer.sign_add_credential(
+                    "credential-1",
+                    Some(7),
+                    Some(&holder),
+                    Some(&schema),
+                    Some(&root),
+                ),
+            ),
+            (
+                revoke_credential_message("did:zuni:solana:issuer", "credential-1"),
+                signer.sign_revoke_credential("did:zuni:solana:issuer", "credential-1"),
+            ),
+            (
+                close_credential_message("credential-1"),
+                signer.sign_close_credential("credential-1"),
+            ),
+            (
+                acknowledge_credential_message("credential-1"),
+                signer.sign_acknowledge_credential("credential-1"),
+            ),
+            (
+                challenge.message_hash(),
+                signer.sign_auth_challenge(&challenge),
+            ),
+        ] {
+            let recovered =
+                secp256k1_recover(&message, signature.recovery_id, &signature.signature).unwrap();
+            assert_eq!(recovered.0.to_vec(), registered_key);
+        }
+        assert_ne!(
+            add_credential_message("credential-1", None, None, None, None),
+            revoke_credential_message("did:zuni:solana:issuer", "credential-1")
+        );
+        assert_ne!(
+            revoke_credential_message("did:zuni:solana:issuer", "credential-1"),
+            revoke_credential_message("did:zuni:solana:other", "credential-1")
+        );
+        assert_ne!(
+            add_credential_message("credential-1", None, None, None, None),
+            add_credential_message("credential-1", None, Some(&holder), None, None)
+        );
+        assert_ne!(
+            add_credential_message("credential-1", None, None, None, None),
+            add_credential_message("credential-1", None, None, Some(&schema), None)
+        );
+        assert_ne!(
+            add_credential_message("credential-1", None, None, None, None),
+            add_credential_message("credential-1", None, None, None, Some(&root))
+        );
+        assert_ne!(
+            add_credential_message("credential-1", None, None, None, None),
+            add_credential_message("credential-1", Some(1), None, None, None)
+        );
+    }
+
+    #[test]
+    fn test_add_credential_message_fields_cannot_be_moved_into_the_id() {
+        let holder = CredentialHolder::Did("did:ex:bob".to_string());
+        assert_ne!(
+            add_credential_message("c1", None, Some(&holder), None, None),
+            add_credential_message("c1HOLDER_DIDdid:ex:bob", None, None, None, None)
+        );
+        let mut expire_at = b"c1EXPIRE_AT".to_vec();
+        expire_at.extend_from_slice(&7u64.to_le_bytes());
+        assert_ne!(
+            add_credential_message("c1", Some(7), None, None, None),
+            add_credential_message(
+                std::str::from_utf8(&expire_at).unwrap(),
+                None,
+                None,
+                None,
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_operation_messages_cannot_be_confused() {
+        assert_ne!(
+            add_credential_message("xCLOSE", None, None, None, None),
+            close_credential_message("x")
+        );
+        assert_ne!(
+            add_credential_message("xACKNOWLEDGE", None, None, None, None),
+            acknowledge_credential_message("x")
+        );
+        assert_ne!(
+            close_credential_message("credential-1"),
+            acknowledge_credential_message("credential-1")
+        );
+        assert_ne!(
+            revoke_credential_message("did:zuni:solana:issuer", "credential-1"),
+            revoke_credential_message("did:zuni:solana:issuercredential-1", "")
+        );
+    }
+
+    #[test]
+    fn test_holder_commitment_matches_acknowledging_key() {
+        let holder = Secp256k1Signer::from_bytes(&[9u8; 32]).unwrap();
+        let signature = holder.sign_acknowledge_credential("credential-1");
+        let recovered = secp256k1_recover(
+            &acknowledge_credential_message("credential-1"),
+            signature.recovery_id,
+            &signature.signature,
+        )
+        .unwrap();
+
+        assert_eq!(
+            holder.holder_commitment(),
+            CredentialHolder::KeccakCommitment(keccak::hash(&recovered.0).to_bytes())
+        );
+        let (_, public_key) = multibase::decode(holder.public_key_multibase()).unwrap();
+        let hex = multibase::encode(
+            multibase::Base::Base16Lower,
+            [&[0x04u8][..], &public_key].concat(),
+        );
+        assert_eq!(
+            holder_key_commitment(&hex[1..]).unwrap(),
+            holder.holder_commitment()
+        );
+        assert!(holder_key_commitment(&hex[5..]).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signers::Signers;
+use solana_sdk::transaction::Transaction;
+
+/// Builds a transaction without signing it, for signers that are not available locally.
+pub fn build_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+    transaction.message.recent_blockhash = recent_blockhash;
+    transaction
+}
+
+/// Builds and signs a transaction. The first signer pays the fees.
+pub fn build_signed_transaction<T: Signers + ?Sized>(
+    instructions: &[Instruction],
+    signers: &T,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let payer = signers.pubkeys()[0];
+    Transaction::new_signed_with_payer(instructions, Some(&payer), signers, recent_blockhash)
+}
+
+/// Adds the signatures of `signers` to a transaction built with [`build_transaction`].
+pub fn partial_sign<T: Signers + ?Sized>(
+    transaction: &mut Transaction,
+    signers: &T,
+    recent_blockhash: Hash,
+) {
+    transaction.partial_sign(signers, recent_blockhash);
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    use super::*;
+    use crate::instruction;
+    use crate::signer::Secp256k1Signer;
+
+    #[test]
+    fn test_build_and_sign_add_credential() {
+        let payer = Keypair::new();
+        let issuer = Secp256k1Signer::from_bytes(&[7u8; 32]).unwrap();
+        let did = "did:zuni:solana:sdk";
+        let authentication_id = "did:zuni:solana:sdk#key-0";
+        let ix = instruction::add_credential(
+            &payer.pubkey(),
+            did,
+            authentication_id,
+            "credential-1",
+            None,
+            None,
+            None,
+            None,
+            instruction::IssuerAuthentication::Secp256k1(&issuer.sign_add_credential(
+                "credential-1",
+                None,
+                None,
+                None,
+                None,
+            )),
+            0,
+        );
+
+        let blockhash = Hash::new_unique();
+        let mut transaction =
+            build_transaction(std::slice::from_ref(&ix), &payer.pubkey(), blockhash);
+        assert!(!transaction.is_signed());
+        partial_sign(&mut transaction, &[&payer], blockhash);
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+
+        let signed = build_signed_transaction(&[ix], &[&payer], blockhash);
+        assert_eq!(signed.signatures, transaction.signatures);
+    }
+}
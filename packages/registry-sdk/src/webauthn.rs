@@ -0,0 +1,200 @@
+//! Passkeys: `JsonWebKey2020` P-256 verification methods used through WebAuthn assertions.
+//!
+//! The passkey signs `authenticatorData || sha256(clientDataJSON)`, the client data holding
+//! the [`webauthn_challenge`] of the operation message. The transaction carries the
+//! [`secp256r1_instruction`] verifying that signature ahead of the registry instruction, which
+//! gets the assertion through [`IssuerAuthentication::WebAuthn`](crate::IssuerAuthentication).
+
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use verifiable_data_registry::state::WebAuthnAssertion;
+pub use verifiable_data_registry::webauthn::{
+    P256_PUBLIC_KEY_LENGTH, P256_SIGNATURE_LENGTH, SECP256R1_PROGRAM_ID,
+};
+
+use crate::error::SdkError;
+
+// A signature count, a padding byte and one set of offsets.
+const DATA_START: usize = 2 + 14;
+// Order of the P-256 group. The secp256r1 program only accepts signatures with `s` at most
+// half of it.
+const P256_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63, 0x25, 0x51,
+];
+
+/// Challenge to request the passkey assertion with for an operation signing `message_hash`,
+/// e.g. [`add_credential_message`](crate::signer::add_credential_message).
+pub fn webauthn_challenge(message_hash: &[u8; 32]) -> String {
+    URL_SAFE_NO_PAD.encode(message_hash)
+}
+
+/// Message the passkey signed for `assertion`.
+pub fn signed_message(assertion: &WebAuthnAssertion) -> Vec<u8> {
+    [
+        assertion.authenticator_data.as_slice(),
+        hash(&assertion.client_data_json).as_ref(),
+    ]
+    .concat()
+}
+
+/// Instruction of the secp256r1 program verifying the `signature` of `assertion` by the
+/// compressed `public_key`, to place before the registry instruction.
+pub fn secp256r1_instruction(
+    public_key: &[u8; P256_PUBLIC_KEY_LENGTH],
+    signature: &[u8; P256_SIGNATURE_LENGTH],
+    assertion: &WebAuthnAssertion,
+) -> Instruction {
+    let message = signed_message(assertion);
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + P256_PUBLIC_KEY_LENGTH;
+    let message_offset = signature_offset + P256_SIGNATURE_LENGTH;
+
+    let mut data = vec![1, 0];
+    // The instruction index `u16::MAX` points into this instruction's own data.
+    for value in [
+        signature_offset,
+        u16::MAX.into(),
+        public_key_offset,
+        u16::MAX.into(),
+        message_offset,
+        message.len(),
+        u16::MAX.into(),
+    ] {
+        data.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+    data.extend_from_slice(public_key);
+    data.extend_from_slice(signature);
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: SECP256R1_PROGRAM_ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+/// Raw `r || s` signature, with the low `s` the secp256r1 program requires, from the DER
+/// signature an authenticator returns.
+pub fn p256_signature_from_der(der: &[u8]) -> Result<[u8; P256_SIGNATURE_LENGTH], SdkError> {
+    let body = match der {
+        [0x30, length, body @ ..] if *length as usize == body.len() => body,
+        _ => return Err(SdkError::InvalidDerSignature),
+    };
+    let (r, rest) = der_integer(body)?;
+    let (s, rest) = der_integer(rest)?;
+    if !rest.is_empty() {
+        return Err(SdkError::InvalidDerSignature);
+    }
+
+    let negated_s = subtract(&P256_ORDER, &s);
+    let s = if negated_s < s { negated_s } else { s };
+    let mut signature = [0u8; P256_SIGNATURE_LENGTH];
+    signature[..32].copy_from_slice(&r);
+    signature[32..].copy_from_slice(&s);
+    Ok(signature)
+}
+
+// Reads a DER INTEGER of at most 32 bytes, left-padded to 32 bytes.
+fn der_integer(der: &[u8]) -> Result<([u8; 32], &[u8]), SdkError> {
+    let (value, rest) = match der {
+        [0x02, length, rest @ ..] if (*length as usize) <= rest.len() => {
+            rest.split_at(*length as usize)
+        }
+        _ => return Err(SdkError::InvalidDerSignature),
+    };
+    let value = match value {
+        [0x00, unsigned @ ..] => unsigned,
+        _ => value,
+    };
+    if value.len() > 32 {
+        return Err(SdkError::InvalidDerSignature);
+    }
+    let mut integer = [0u8; 32];
+    integer[32 - value.len()..].copy_from_slice(value);
+    Ok((integer, rest))
+}
+
+// `a - b` of big-endian integers with `a >= b`.
+fn subtract(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut difference = [0u8; 32];
+    let mut borrow = false;
+    for i in (0..32).rev() {
+        let (value, borrow_a) = a[i].overflowing_sub(b[i]);
+        let (value, borrow_b) = value.overflowing_sub(borrow as u8);
+        difference[i] = value;
+        borrow = borrow_a || borrow_b;
+    }
+    difference
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn der(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x02, r.len() as u8];
+        body.extend_from_slice(r);
+        body.extend_from_slice(&[0x02, s.len() as u8]);
+        body.extend_from_slice(s);
+        [vec![0x30, body.len() as u8], body].concat()
+    }
+
+    #[test]
+    fn test_p256_signature_from_der_lowers_s() {
+        let r = [0x80u8; 32];
+        let mut high_s = P256_ORDER;
+        high_s[31] -= 1; // order - 1
+
+        let signature = p256_signature_from_der(&der(&[&[0u8][..], &r].concat(), &high_s)).unwrap();
+        assert_eq!(signature[..32], r);
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(signature[32..], one);
+
+        let signature = p256_signature_from_der(&der(&r, &[1])).unwrap();
+        assert_eq!(signature[32..], one);
+
+        assert!(p256_signature_from_der(&der(&[1u8; 33], &[1])).is_err());
+        assert!(p256_signature_from_der(&der(&r, &[1])[..10]).is_err());
+    }
+
+    #[test]
+    fn test_secp256r1_instruction_layout() {
+        let public_key = [2u8; P256_PUBLIC_KEY_LENGTH];
+        let signature = [5u8; P256_SIGNATURE_LENGTH];
+        let challenge = webauthn_challenge(&[7u8; 32]);
+        let assertion = WebAuthnAssertion {
+            authenticator_data: [[9u8; 32].as_slice(), &[0x05, 0, 0, 0, 1]].concat(),
+            client_data_json: format!(
+                r#"{{"type":"webauthn.get","challenge":"{challenge}","origin":"https://zuni.app"}}"#
+            )
+            .into_bytes(),
+        };
+        let instruction = secp256r1_instruction(&public_key, &signature, &assertion);
+        let data = &instruction.data;
+        let offset = |n: usize| u16::from_le_bytes([data[2 + 2 * n], data[3 + 2 * n]]) as usize;
+
+        assert_eq!(instruction.program_id, SECP256R1_PROGRAM_ID);
+        assert_eq!(data[0], 1);
+        assert_eq!(
+            &data[offset(0)..offset(0) + P256_SIGNATURE_LENGTH],
+            signature
+        );
+        assert_eq!(
+            &data[offset(2)..offset(2) + P256_PUBLIC_KEY_LENGTH],
+            public_key
+        );
+        assert_eq!(
+            &data[offset(4)..offset(4) + offset(5)],
+            signed_message(&assertion)
+        );
+        assert_eq!(offset(4) + offset(5), data.len());
+        for n in [1, 3, 6] {
+            assert_eq!(offset(n), u16::MAX as usize);
+        }
+        assert_eq!(challenge, "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc");
+    }
+}
@@ -0,0 +1,25 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+
+/// Reads account data dumped either as raw bytes (`solana account <ADDRESS> --output-file`)
+/// or as JSON (`solana account <ADDRESS> --output json`).
+pub fn read_account_data(path: &Path) -> Result<Vec<u8>> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let json = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(json) if json.is_object() => json,
+        _ => return Ok(bytes),
+    };
+
+    let data = json.pointer("/account/data").or_else(|| json.get("data"));
+    match data.and_then(Value::as_array).map(Vec::as_slice) {
+        Some([Value::String(data), Value::String(encoding)]) if encoding == "base64" => {
+            STANDARD.decode(data).context("invalid base64 account data")
+        }
+        Some([_, Value::String(encoding)]) => bail!("unsupported account encoding {encoding}"),
+        _ => bail!("{} has no account data", path.display()),
+    }
+}
@@ -0,0 +1,922 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use verifiable_data_registry::state::Relationship;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "zuni-did",
+    version,
+    about = "Build and sign verifiable data registry transactions offline"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create a DID controlled by the `--controller` keypair
+    CreateDid {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        controller: PathBuf,
+        /// Page of the controller's DID index that still has room
+        #[arg(long, default_value_t = 0)]
+        index_page: u32,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Hand a DID over to a new controller
+    TransferController {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        new_controller: Pubkey,
+        /// Page of the current controller's DID index holding the DID
+        #[arg(long, default_value_t = 0)]
+        from_index_page: u32,
+        /// Page of the new controller's DID index that still has room
+        #[arg(long, default_value_t = 0)]
+        to_index_page: u32,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        lock: TimeLockArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Add a verification method to a DID
+    AddMethod {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        key_id: String,
+        #[arg(long, default_value = "EcdsaSecp256k1VerificationKey2019")]
+        r#type: String,
+        /// Public key to register, e.g. `f<hex>` for secp256k1 keys
+        #[arg(long, required_unless_present = "secp256k1_key")]
+        public_key_multibase: Option<String>,
+        /// Derive the public key from a hex-encoded secp256k1 secret key file
+        #[arg(long, conflicts_with = "public_key_multibase")]
+        secp256k1_key: Option<PathBuf>,
+        /// Controller of the key, defaults to the DID controller
+        #[arg(long)]
+        key_controller: Option<Pubkey>,
+        #[command(flatten)]
+        validity: ValidityArgs,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        lock: TimeLockArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Add a verification relationship for an existing verification method
+    AddRelationship {
+        #[arg(long)]
+        did: String,
+        #[arg(long, value_enum)]
+        relationship: RelationshipArg,
+        #[arg(long)]
+        key_id: String,
+        #[command(flatten)]
+        validity: ValidityArgs,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        lock: TimeLockArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Replace the `alsoKnownAs` identifiers of a DID, e.g. its did:key or did:pkh forms
+    SetAlsoKnownAs {
+        #[arg(long)]
+        did: String,
+        /// Other identifier of the DID subject, repeat for several; none clears them
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Issue a credential signed by an authentication key of the issuer DID
+    IssueCredential {
+        #[command(flatten)]
+        credential: CredentialArgs,
+        /// Expiry as a Unix timestamp in milliseconds
+        #[arg(long)]
+        expire_at: Option<u64>,
+        #[command(flatten)]
+        holder: HolderArgs,
+        /// Address of the credential schema the credential conforms to
+        #[arg(long)]
+        schema: Option<Pubkey>,
+        /// Decimal `fieldMerkleRoot` of the credential, as computed by `issueVC`
+        #[arg(long)]
+        field_merkle_root: Option<String>,
+        /// Page of the issuer's credential index that still has room
+        #[arg(long, default_value_t = 0)]
+        index_page: u32,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Revoke a credential signed by an authentication key of the issuer DID
+    RevokeCredential {
+        #[command(flatten)]
+        credential: CredentialArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Close a credential and return its rent to the account that paid for it
+    CloseCredential {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        credential_id: String,
+        /// Account refunded the rent: the payer of the credential, see `inspect`, or the
+        /// controller of the issuer DID when no payer is recorded
+        #[arg(long)]
+        payer: Pubkey,
+        /// Close as the issuer with this authentication key, signing with `--secp256k1-key`
+        /// or, for a Solana key, as `--fee-payer`; without it the credential must be past its
+        /// expiry grace period
+        #[arg(long)]
+        authentication_id: Option<String>,
+        #[arg(long, requires = "authentication_id")]
+        secp256k1_key: Option<PathBuf>,
+        /// Page of the issuer's credential index holding the credential
+        #[arg(long, default_value_t = 0)]
+        index_page: u32,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Acknowledge a credential as the holder it was issued to
+    AcknowledgeCredential {
+        #[arg(long)]
+        credential_id: String,
+        /// Holder DID acknowledging with its `--authentication-id` key; without it the holder
+        /// is the committed key in `--secp256k1-key`
+        #[arg(long, requires = "authentication_id")]
+        holder_did: Option<String>,
+        #[arg(long, requires = "holder_did")]
+        authentication_id: Option<String>,
+        /// Hex-encoded secp256k1 secret key file of the holder key
+        #[arg(
+            long,
+            required_unless_present = "solana_key",
+            conflicts_with = "solana_key"
+        )]
+        secp256k1_key: Option<PathBuf>,
+        /// Keypair of the holder's authentication key when it is a Solana key
+        #[arg(long, requires = "holder_did")]
+        solana_key: Option<PathBuf>,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Upgrade a registry account written with an older layout to the current one
+    MigrateAccount {
+        #[arg(long)]
+        account: Pubkey,
+        /// Pays the rent of the grown account
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Check the status of a credential from a `CredentialState` account dump
+    CheckCredential {
+        #[arg(long)]
+        account: PathBuf,
+        /// Unix timestamp in milliseconds to check expiry against, defaults to now
+        #[arg(long)]
+        at: Option<u64>,
+    },
+    /// Decode a registry account dump
+    Inspect {
+        #[arg(long)]
+        account: PathBuf,
+    },
+    /// Derive the address of a registry account
+    Address {
+        #[command(subcommand)]
+        account: AddressCommand,
+    },
+    /// Manage the program-wide registry config
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Let another signer issue credentials on behalf of a DID
+    Delegation {
+        #[command(subcommand)]
+        action: DelegationCommand,
+    },
+    /// Publish the credential schemas a DID issues against
+    Schema {
+        #[command(subcommand)]
+        action: SchemaCommand,
+    },
+    /// Publish the presentation requests a verifier DID asks holders to answer
+    VerificationSchema {
+        #[command(subcommand)]
+        action: VerificationSchemaCommand,
+    },
+    /// Accredit issuer DIDs for a credential type or schema, as a trust registry
+    Accreditation {
+        #[command(subcommand)]
+        action: AccreditationCommand,
+    },
+    /// Recover a DID whose controller key is lost with the approval of its guardians
+    Recovery {
+        #[command(subcommand)]
+        action: RecoveryCommand,
+    },
+    /// Time-lock the controller-gated changes of a DID
+    Operation {
+        #[command(subcommand)]
+        action: OperationCommand,
+    },
+    /// Prove control of a DID to a dApp with a short-lived session key
+    Auth {
+        #[command(subcommand)]
+        action: AuthCommand,
+    },
+    /// Print the `public_key_multibase` of a secp256k1 secret key file
+    PublicKey {
+        #[arg(long)]
+        secp256k1_key: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Create the config, signed by the program upgrade authority
+    Init {
+        #[arg(long)]
+        admin: Pubkey,
+        /// Verification method type to accept, repeat for several
+        #[arg(long = "allowed-key-type", required = true)]
+        allowed_key_types: Vec<String>,
+        /// Lamports charged per issued credential
+        #[arg(long)]
+        issuance_fee: Option<u64>,
+        #[arg(long)]
+        authority: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Stop every non-admin instruction
+    Pause {
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Resume a paused registry
+    Unpause {
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Hand the config over to a new admin
+    SetAdmin {
+        #[arg(long)]
+        new_admin: Pubkey,
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Replace the accepted verification method types
+    SetKeyTypes {
+        #[arg(long = "allowed-key-type", required = true)]
+        allowed_key_types: Vec<String>,
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Set the issuance fee in lamports, or remove it when omitted
+    SetFee {
+        #[arg(long)]
+        issuance_fee: Option<u64>,
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Withdraw collected issuance fees
+    WithdrawFees {
+        #[arg(long)]
+        recipient: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        admin: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DelegationCommand {
+    /// Grant `--delegate` the right to issue credentials for the DID
+    Create {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        delegate: Pubkey,
+        /// Id of the delegation, never reused for the same delegate
+        #[arg(long)]
+        id: u64,
+        /// Ids of the credential schemas the delegate issues against must start with this
+        /// prefix
+        #[arg(long, default_value = "")]
+        schema_id_prefix: String,
+        /// Maximum number of credentials the delegate may issue
+        #[arg(long)]
+        max_credentials: Option<u32>,
+        /// Also let the delegate revoke the credentials it issued
+        #[arg(long)]
+        can_revoke: bool,
+        /// Expiry as a Unix timestamp in milliseconds
+        #[arg(long)]
+        expire_at: Option<u64>,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Withdraw a delegation, credentials already issued stay valid
+    Revoke {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        delegate: Pubkey,
+        #[arg(long)]
+        id: u64,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Issue a credential as the delegate, which also pays for it
+    IssueCredential {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        credential_id: String,
+        /// Expiry as a Unix timestamp in milliseconds
+        #[arg(long)]
+        expire_at: Option<u64>,
+        #[command(flatten)]
+        holder: HolderArgs,
+        /// Address of the credential schema the credential conforms to, in the scope of the
+        /// delegation
+        #[arg(long)]
+        schema: Pubkey,
+        /// Decimal `fieldMerkleRoot` of the credential, as computed by `issueVC`
+        #[arg(long)]
+        field_merkle_root: Option<String>,
+        /// Page of the issuer's credential index that still has room
+        #[arg(long, default_value_t = 0)]
+        index_page: u32,
+        #[arg(long)]
+        delegate: PathBuf,
+        /// Id of the delegation the credential is issued under
+        #[arg(long)]
+        delegation_id: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Revoke a credential issued under the delegate's delegation
+    RevokeCredential {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        credential_id: String,
+        #[arg(long)]
+        delegate: PathBuf,
+        /// Id of the delegation the credential was issued under
+        #[arg(long)]
+        delegation_id: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SchemaCommand {
+    /// Publish a schema authored by the DID, identified by `--schema-id` and `--schema-version`
+    Create {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+        #[arg(long)]
+        name: String,
+        /// Claim as `name:type`, type being one of string, integer, number, boolean, date or
+        /// bytes; repeat for several
+        #[arg(long = "field", required = true)]
+        fields: Vec<String>,
+        /// JSON Schema document, whose sha256 is recorded on-chain
+        #[arg(long)]
+        json_schema: PathBuf,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Refuse new credentials against a schema, issued ones keep referring to it
+    Deprecate {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum VerificationSchemaCommand {
+    /// Publish the `Schema` of the TypeScript library for the verifier DID
+    Create {
+        #[arg(long)]
+        did: String,
+        /// `id` of the schema
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+        #[arg(long)]
+        name: String,
+        /// Decimal `fieldMerkleRoot` of a credential check; repeat in `credentialChecks` order
+        #[arg(long = "check-root", required = true)]
+        check_roots: Vec<String>,
+        /// Entry of `requestedFields`, e.g. `0:degree`; repeat for several
+        #[arg(long = "requested-field")]
+        requested_fields: Vec<String>,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Tell holders to stop answering a presentation request
+    Deprecate {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AccreditationCommand {
+    /// Accredit `--issuer-did` on behalf of the authority `--did`
+    Grant {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        issuer_did: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+        /// Levels of accreditations the issuer may grant below it, 0 for none
+        #[arg(long, default_value_t = 0)]
+        max_depth: u8,
+        /// Expiry as a Unix timestamp in milliseconds
+        #[arg(long)]
+        expire_at: Option<u64>,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Withdraw an accreditation, and with it every one granted below it
+    Revoke {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        issuer_did: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Build a transaction that fails unless `--issuer-did` is accredited by `--root-did`
+    Check {
+        #[arg(long)]
+        root_did: String,
+        /// Authority between the issuer and the root, starting from the issuer's; repeat for
+        /// several
+        #[arg(long)]
+        via: Vec<String>,
+        #[arg(long)]
+        issuer_did: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+        /// Also require this credential of the issuer to be active
+        #[arg(long)]
+        credential_id: Option<String>,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RecoveryCommand {
+    /// Replace the guardians of a DID, none turns recovery off
+    SetGuardians {
+        #[arg(long)]
+        did: String,
+        /// Guardian key, repeat for several
+        #[arg(long = "guardian-key")]
+        guardian_keys: Vec<Pubkey>,
+        /// Guardian DID, approving with its controller key, repeat for several
+        #[arg(long = "guardian-did")]
+        guardian_dids: Vec<String>,
+        /// Approvals needed to start a recovery
+        #[arg(long, default_value_t = 0)]
+        threshold: u8,
+        /// Milliseconds the controller has to cancel an approved recovery
+        #[arg(long, default_value_t = 0)]
+        delay: u64,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Propose a new controller for the DID as one of its guardians
+    Initiate {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        new_controller: Pubkey,
+        #[command(flatten)]
+        guardian: GuardianArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Approve the pending recovery of the DID as one of its guardians
+    Approve {
+        #[arg(long)]
+        did: String,
+        #[command(flatten)]
+        guardian: GuardianArgs,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Cancel the pending recovery of the DID as its current controller, or close it once it
+    /// expired short of approvals
+    Cancel {
+        #[arg(long)]
+        did: String,
+        /// Account that paid for the recovery, see `inspect`
+        #[arg(long)]
+        payer: Pubkey,
+        /// Cancel as the controller; without it the recovery must have expired
+        #[arg(long)]
+        controller: Option<PathBuf>,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Hand the DID over once the recovery is approved and its delay is over
+    Execute {
+        #[arg(long)]
+        did: String,
+        /// Current controller of the DID
+        #[arg(long)]
+        controller: Pubkey,
+        #[arg(long)]
+        new_controller: Pubkey,
+        /// Account that paid for the recovery, see `inspect`
+        #[arg(long)]
+        payer: Pubkey,
+        /// Page of the current controller's DID index holding the DID
+        #[arg(long, default_value_t = 0)]
+        from_index_page: u32,
+        /// Page of the new controller's DID index that still has room
+        #[arg(long, default_value_t = 0)]
+        to_index_page: u32,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OperationCommand {
+    /// Set the milliseconds every controller-gated change must wait once proposed
+    SetDelay {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        delay: u64,
+        #[command(flatten)]
+        lock: TimeLockArgs,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Withdraw a proposed operation before it is executed
+    Cancel {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        id: u64,
+        #[arg(long)]
+        controller: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+    /// Request a login challenge for the `--session-key` keypair, which co-signs
+    Challenge {
+        #[arg(long)]
+        did: String,
+        /// Program or dApp the session is meant for
+        #[arg(long)]
+        audience: Pubkey,
+        #[arg(long)]
+        session_key: PathBuf,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// Answer a challenge with an authentication key of the DID, opening a session
+    Login {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        authentication_id: String,
+        /// Dump of the `AuthChallenge` account, as accepted by `inspect`
+        #[arg(long)]
+        challenge: PathBuf,
+        /// Milliseconds the session lasts
+        #[arg(long, default_value_t = 60 * 60 * 1000)]
+        duration: u64,
+        /// Hex-encoded secp256k1 secret key file of the authentication key
+        #[arg(
+            long,
+            required_unless_present = "solana_key",
+            conflicts_with = "solana_key"
+        )]
+        secp256k1_key: Option<PathBuf>,
+        /// Keypair of the authentication key when it is a Solana key, co-signing the transaction
+        #[arg(long)]
+        solana_key: Option<PathBuf>,
+        #[arg(long)]
+        fee_payer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// End a session, signed by its session key, the DID controller, or anyone once expired
+    Logout {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        session_key: Pubkey,
+        /// Account that paid for the session, receives its rent
+        #[arg(long)]
+        payer: Pubkey,
+        #[arg(long)]
+        closer: PathBuf,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AddressCommand {
+    Config,
+    Did {
+        #[arg(long)]
+        did: String,
+    },
+    Method {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        key_id: String,
+    },
+    Relationship {
+        #[arg(long)]
+        did: String,
+        #[arg(long, value_enum)]
+        relationship: RelationshipArg,
+        #[arg(long)]
+        key_id: String,
+    },
+    Credential {
+        #[arg(long)]
+        credential_id: String,
+    },
+    Schema {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+    },
+    VerificationSchema {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value_t = 1)]
+        schema_version: u32,
+    },
+    Accreditation {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        issuer_did: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+    ControllerIndex {
+        #[arg(long)]
+        controller: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        page: u32,
+    },
+    IssuerIndex {
+        #[arg(long)]
+        did: String,
+        #[arg(long, default_value_t = 0)]
+        page: u32,
+    },
+    Delegation {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        delegate: Pubkey,
+        #[arg(long)]
+        id: u64,
+    },
+    Recovery {
+        #[arg(long)]
+        did: String,
+    },
+    ChangeLog {
+        #[arg(long)]
+        did: String,
+    },
+    Operation {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        id: u64,
+    },
+    AuthChallenge {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        session_key: Pubkey,
+    },
+    AuthSession {
+        #[arg(long)]
+        did: String,
+        #[arg(long)]
+        session_key: Pubkey,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct TxArgs {
+    /// Recent blockhash, obtained on a connected machine with `getLatestBlockhash`
+    #[arg(long)]
+    pub blockhash: Hash,
+    /// File the base64-encoded signed transaction is written to
+    #[arg(long)]
+    pub out: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidityArgs {
+    /// Unix timestamp in milliseconds from which the key is valid
+    #[arg(long)]
+    pub valid_from: Option<u64>,
+    /// Unix timestamp in milliseconds until which the key is valid
+    #[arg(long)]
+    pub valid_until: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct CredentialArgs {
+    #[arg(long)]
+    pub did: String,
+    #[arg(long)]
+    pub authentication_id: String,
+    #[arg(long)]
+    pub credential_id: String,
+    /// Hex-encoded secp256k1 secret key file of the authentication key
+    #[arg(
+        long,
+        required_unless_present = "solana_key",
+        conflicts_with = "solana_key"
+    )]
+    pub secp256k1_key: Option<PathBuf>,
+    /// Keypair of the authentication key when it is a Solana key, co-signing the transaction
+    #[arg(long)]
+    pub solana_key: Option<PathBuf>,
+    #[arg(long)]
+    pub fee_payer: PathBuf,
+}
+
+/// Subject the credential is issued to, recorded on-chain so it can acknowledge it.
+#[derive(Args, Debug)]
+pub struct HolderArgs {
+    #[arg(long, conflicts_with = "holder_public_key")]
+    pub holder_did: Option<String>,
+    /// Hex-encoded uncompressed secp256k1 public key of the holder, recorded as its keccak
+    /// commitment
+    #[arg(long)]
+    pub holder_public_key: Option<String>,
+}
+
+/// Credentials an accreditation covers.
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct ScopeArgs {
+    /// `type` of the credentials, e.g. `MedicalLicense`
+    #[arg(long)]
+    pub credential_type: Option<String>,
+    /// Address of the credential schema the credentials are issued against
+    #[arg(long)]
+    pub schema: Option<Pubkey>,
+}
+
+/// Without either flag the change is applied at once, which a DID with an
+/// operation delay refuses.
+#[derive(Args, Debug)]
+pub struct TimeLockArgs {
+    /// Propose the change as pending operation `ID` instead of applying it
+    #[arg(long, value_name = "ID", conflicts_with = "execute")]
+    pub propose: Option<u64>,
+    /// Apply the change proposed as pending operation `ID` once its delay is over
+    #[arg(long, value_name = "ID")]
+    pub execute: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct GuardianArgs {
+    /// Keypair of the guardian key, or of the controller of the guardian DID
+    #[arg(long)]
+    pub guardian: PathBuf,
+    /// Guardian DID `--guardian` approves for, when the guardian is a DID
+    #[arg(long)]
+    pub guardian_did: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum RelationshipArg {
+    Authentication,
+    Assertion,
+    KeyAgreement,
+}
+
+impl From<RelationshipArg> for Relationship {
+    fn from(relationship: RelationshipArg) -> Self {
+        match relationship {
+            RelationshipArg::Authentication => Relationship::Authentication,
+            RelationshipArg::Assertion => Relationship::Assertion,
+            RelationshipArg::KeyAgreement => Relationship::KeyAgreement,
+        }
+    }
+}
@@ -0,0 +1,1055 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use registry_sdk::field::{format_field_element, parse_field_element};
+use registry_sdk::instruction::{
+    self, AccreditIssuerArgs, AddVerificationMethodArgs, CreateCredentialSchemaArgs,
+    CreateDelegationArgs, CreateVerificationSchemaArgs,
+};
+use registry_sdk::pda::*;
+use registry_sdk::signer::holder_key_commitment;
+use registry_sdk::transaction::build_signed_transaction;
+use registry_sdk::{
+    AccreditationScope, ConfigUpdate, CredentialHolder, DidOperation, Guardian,
+    IssuerAuthentication, RegistryAccount, SchemaField, SchemaFieldType, Secp256k1Signer, ID,
+};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::Signer;
+use verifiable_data_registry::state::CredentialStatus;
+use verifiable_data_registry::validation;
+
+use crate::account_file::read_account_data;
+use crate::cli::*;
+
+pub fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::CreateDid {
+            did,
+            controller,
+            index_page,
+            tx,
+        } => {
+            validation::validate_did(&did)?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::initialize_did(&controller.pubkey(), &did, index_page);
+            write_transaction(&[ix], &controller, &tx)
+        }
+        Command::TransferController {
+            did,
+            new_controller,
+            from_index_page,
+            to_index_page,
+            controller,
+            lock,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = match (lock.propose, lock.execute) {
+                (Some(id), _) => instruction::propose_operation(
+                    &controller.pubkey(),
+                    &did,
+                    id,
+                    DidOperation::TransferController { new_controller },
+                ),
+                (None, Some(id)) => instruction::execute_transfer_did_controller(
+                    &controller.pubkey(),
+                    &did,
+                    &new_controller,
+                    from_index_page,
+                    to_index_page,
+                    id,
+                ),
+                (None, None) => instruction::transfer_did_controller(
+                    &controller.pubkey(),
+                    &did,
+                    &new_controller,
+                    from_index_page,
+                    to_index_page,
+                ),
+            };
+            write_transaction(&[ix], &controller, &tx)
+        }
+        Command::AddMethod {
+            did,
+            key_id,
+            r#type,
+            public_key_multibase,
+            secp256k1_key,
+            key_controller,
+            validity,
+            controller,
+            lock,
+            tx,
+        } => {
+            validation::validate_key_id(&did, &key_id)?;
+            validation::validate_key_type(&r#type)?;
+            let controller = read_keypair(&controller)?;
+            let public_key_multibase = match (public_key_multibase, secp256k1_key) {
+                (Some(public_key_multibase), _) => public_key_multibase,
+                (None, Some(path)) => read_secp256k1_key(&path)?.public_key_multibase(),
+                (None, None) => {
+                    bail!("either --public-key-multibase or --secp256k1-key is required")
+                }
+            };
+            let key_controller = key_controller.unwrap_or_else(|| controller.pubkey());
+            let args = AddVerificationMethodArgs {
+                did: &did,
+                key_id: &key_id,
+                r#type: &r#type,
+                public_key_multibase: &public_key_multibase,
+                key_controller,
+                valid_from: validity.valid_from,
+                valid_until: validity.valid_until,
+            };
+            let ix = match (lock.propose, lock.execute) {
+                (Some(id), _) => instruction::propose_operation(
+                    &controller.pubkey(),
+                    &did,
+                    id,
+                    DidOperation::AddVerificationMethod {
+                        key_id: key_id.clone(),
+                        r#type: r#type.clone(),
+                        public_key_multibase: public_key_multibase.clone(),
+                        controller: key_controller,
+                        valid_from: validity.valid_from,
+                        valid_until: validity.valid_until,
+                    },
+                ),
+                (None, Some(id)) => {
+                    instruction::execute_add_verification_method(&controller.pubkey(), args, id)
+                }
+                (None, None) => instruction::add_verification_method(&controller.pubkey(), args),
+            };
+            write_transaction(&[ix], &controller, &tx)
+        }
+        Command::AddRelationship {
+            did,
+            relationship,
+            key_id,
+            validity,
+            controller,
+            lock,
+            tx,
+        } => {
+            validation::validate_key_id(&did, &key_id)?;
+            let controller = read_keypair(&controller)?;
+            let ix = match (lock.propose, lock.execute) {
+                (Some(id), _) => instruction::propose_operation(
+                    &controller.pubkey(),
+                    &did,
+                    id,
+                    DidOperation::AddVerificationRelationship {
+                        relationship: relationship.into(),
+                        key_id: key_id.clone(),
+                        valid_from: validity.valid_from,
+                        valid_until: validity.valid_until,
+                    },
+                ),
+                (None, Some(id)) => instruction::execute_add_verification_relationship(
+                    &controller.pubkey(),
+                    &did,
+                    relationship.into(),
+                    &key_id,
+                    validity.valid_from,
+                    validity.valid_until,
+                    id,
+                ),
+                (None, None) => instruction::add_verification_relationship(
+                    &controller.pubkey(),
+                    &did,
+                    relationship.into(),
+                    &key_id,
+                    validity.valid_from,
+                    validity.valid_until,
+                ),
+            };
+            write_transaction(&[ix], &controller, &tx)
+        }
+        Command::SetAlsoKnownAs {
+            did,
+            aliases,
+            controller,
+            tx,
+        } => {
+            validation::validate_also_known_as(&did, &aliases)?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::set_also_known_as(&controller.pubkey(), &did, aliases);
+            write_transaction(&[ix], &controller, &tx)
+        }
+        Command::IssueCredential {
+            credential,
+            expire_at,
+            holder,
+            schema,
+            field_merkle_root,
+            index_page,
+            tx,
+        } => {
+            validation::validate_credential_id(&credential.credential_id)?;
+            let holder = read_holder(holder)?;
+            let field_merkle_root = field_merkle_root
+                .as_deref()
+                .map(parse_field_element)
+                .transpose()?;
+            let fee_payer = read_keypair(&credential.fee_payer)?;
+            let issuer_key = read_issuer_key(&credential.secp256k1_key, &credential.solana_key)?;
+            let signature;
+            let ix = instruction::add_credential(
+                &fee_payer.pubkey(),
+                &credential.did,
+                &credential.authentication_id,
+                &credential.credential_id,
+                expire_at,
+                holder.as_ref(),
+                schema.as_ref(),
+                field_merkle_root.as_ref(),
+                match &issuer_key {
+                    IssuerKey::Secp256k1(signer) => {
+                        signature = signer.sign_add_credential(
+                            &credential.credential_id,
+                            expire_at,
+                            holder.as_ref(),
+                            schema.as_ref(),
+                            field_merkle_root.as_ref(),
+                        );
+                        IssuerAuthentication::Secp256k1(&signature)
+                    }
+                    IssuerKey::Solana(keypair) => IssuerAuthentication::Signer(keypair.pubkey()),
+                },
+                index_page,
+            );
+            write_transaction_signed_by(&[ix], &fee_payer, &issuer_key, &tx)
+        }
+        Command::RevokeCredential { credential, tx } => {
+            let fee_payer = read_keypair(&credential.fee_payer)?;
+            let issuer_key = read_issuer_key(&credential.secp256k1_key, &credential.solana_key)?;
+            let signature;
+            let ix = instruction::revoke_credential(
+                &fee_payer.pubkey(),
+                &credential.did,
+                &credential.authentication_id,
+                &credential.credential_id,
+                match &issuer_key {
+                    IssuerKey::Secp256k1(signer) => {
+                        signature = signer
+                            .sign_revoke_credential(&credential.did, &credential.credential_id);
+                        IssuerAuthentication::Secp256k1(&signature)
+                    }
+                    IssuerKey::Solana(keypair) => IssuerAuthentication::Signer(keypair.pubkey()),
+                },
+            );
+            write_transaction_signed_by(&[ix], &fee_payer, &issuer_key, &tx)
+        }
+        Command::CloseCredential {
+            did,
+            credential_id,
+            payer,
+            authentication_id,
+            secp256k1_key,
+            index_page,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let signature = match &secp256k1_key {
+                Some(path) => Some(read_secp256k1_key(path)?.sign_close_credential(&credential_id)),
+                None => None,
+            };
+            // Without a secp256k1 key the issuer key is a Solana key closing the credential.
+            let key_authentication = match &signature {
+                Some(signature) => IssuerAuthentication::Secp256k1(signature),
+                None => IssuerAuthentication::Signer(fee_payer.pubkey()),
+            };
+            let issuer_authentication = authentication_id
+                .as_deref()
+                .map(|authentication_id| (authentication_id, key_authentication));
+            let ix = instruction::close_credential(
+                &fee_payer.pubkey(),
+                &payer,
+                &did,
+                &credential_id,
+                index_page,
+                issuer_authentication,
+            );
+            write_transaction(&[ix], &fee_payer, &tx)
+        }
+        Command::AcknowledgeCredential {
+            credential_id,
+            holder_did,
+            authentication_id,
+            secp256k1_key,
+            solana_key,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let holder_key = read_issuer_key(&secp256k1_key, &solana_key)?;
+            let signature;
+            let holder_did = holder_did.as_deref().zip(authentication_id.as_deref());
+            let ix = instruction::acknowledge_credential(
+                &credential_id,
+                holder_did,
+                match &holder_key {
+                    IssuerKey::Secp256k1(signer) => {
+                        signature = signer.sign_acknowledge_credential(&credential_id);
+                        IssuerAuthentication::Secp256k1(&signature)
+                    }
+                    IssuerKey::Solana(keypair) => IssuerAuthentication::Signer(keypair.pubkey()),
+                },
+            );
+            write_transaction_signed_by(&[ix], &fee_payer, &holder_key, &tx)
+        }
+        Command::MigrateAccount {
+            account,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let ix = instruction::migrate_account(&fee_payer.pubkey(), &account);
+            write_transaction(&[ix], &fee_payer, &tx)
+        }
+        Command::CheckCredential { account, at } => {
+            let credential = match RegistryAccount::decode(&read_account_data(&account)?)? {
+                RegistryAccount::CredentialState(credential) => credential,
+                other => bail!("expected a CredentialState account, found {}", other.name()),
+            };
+            let at = match at {
+                Some(at) => at,
+                None => now_millis()?,
+            };
+            let expired = matches!(credential.expire_at, Some(expire_at) if expire_at <= at);
+            println!("issuer:     {}", credential.issuer_did);
+            println!("credential: {}", credential.credential_id);
+            println!("status:     {:?}", credential.status);
+            println!(
+                "expire at:  {}",
+                credential
+                    .expire_at
+                    .map_or("never".to_string(), |e| e.to_string())
+            );
+            if let Some(holder) = &credential.holder {
+                println!("holder:     {:?}", holder);
+                println!(
+                    "acknowledged: {}",
+                    credential
+                        .acknowledged_at
+                        .map_or("never".to_string(), |a| a.to_string())
+                );
+            }
+            if let Some(schema) = &credential.schema {
+                println!("schema:     {}", schema);
+            }
+            if let Some(root) = &credential.field_merkle_root {
+                println!("field root: {}", format_field_element(root));
+            }
+            let valid = credential.status == CredentialStatus::Active && !expired;
+            println!("valid:      {}", valid);
+            Ok(())
+        }
+        Command::Inspect { account } => {
+            let account = RegistryAccount::decode(&read_account_data(&account)?)?;
+            println!("{:#?}", account);
+            Ok(())
+        }
+        Command::Address { account } => {
+            let (address, bump) = match account {
+                AddressCommand::Config => find_registry_config_address(&ID),
+                AddressCommand::Did { did } => find_did_document_address(&did, &ID),
+                AddressCommand::Method { did, key_id } => {
+                    find_verification_method_address(&did, &key_id, &ID)
+                }
+                AddressCommand::Relationship {
+                    did,
+                    relationship,
+                    key_id,
+                } => {
+                    find_verification_relationship_address(&did, &relationship.into(), &key_id, &ID)
+                }
+                AddressCommand::Credential { credential_id } => {
+                    find_credential_state_address(&credential_id, &ID)
+                }
+                AddressCommand::Schema {
+                    did,
+                    schema_id,
+                    schema_version,
+                } => find_credential_schema_address(&did, &schema_id, schema_version, &ID),
+                AddressCommand::VerificationSchema {
+                    did,
+                    schema_id,
+                    schema_version,
+                } => find_verification_schema_address(&did, &schema_id, schema_version, &ID),
+                AddressCommand::Accreditation {
+                    did,
+                    issuer_did,
+                    scope,
+                } => find_accreditation_address(&did, &issuer_did, &read_scope(scope), &ID),
+                AddressCommand::ControllerIndex { controller, page } => {
+                    find_controller_did_index_address(&controller, page, &ID)
+                }
+                AddressCommand::IssuerIndex { did, page } => {
+                    find_issuer_credential_index_address(&did, page, &ID)
+                }
+                AddressCommand::Delegation { did, delegate, id } => {
+                    find_delegation_address(&did, &delegate, id, &ID)
+                }
+                AddressCommand::Recovery { did } => find_recovery_address(&did, &ID),
+                AddressCommand::ChangeLog { did } => find_did_change_log_address(&did, &ID),
+                AddressCommand::Operation { did, id } => {
+                    find_pending_operation_address(&did, id, &ID)
+                }
+                AddressCommand::AuthChallenge { did, session_key } => {
+                    find_auth_challenge_address(&did, &session_key, &ID)
+                }
+                AddressCommand::AuthSession { did, session_key } => {
+                    find_auth_session_address(&did, &session_key, &ID)
+                }
+            };
+            println!("{address} (bump {bump})");
+            Ok(())
+        }
+        Command::Config { action } => run_config(action),
+        Command::Delegation { action } => run_delegation(action),
+        Command::Schema { action } => run_schema(action),
+        Command::VerificationSchema { action } => run_verification_schema(action),
+        Command::Accreditation { action } => run_accreditation(action),
+        Command::Recovery { action } => run_recovery(action),
+        Command::Operation { action } => run_operation(action),
+        Command::Auth { action } => run_auth(action),
+        Command::PublicKey { secp256k1_key } => {
+            println!(
+                "{}",
+                read_secp256k1_key(&secp256k1_key)?.public_key_multibase()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn run_config(action: ConfigCommand) -> Result<()> {
+    let (admin, update, tx) = match action {
+        ConfigCommand::Init {
+            admin,
+            allowed_key_types,
+            issuance_fee,
+            authority,
+            tx,
+        } => {
+            let authority = read_keypair(&authority)?;
+            let ix = instruction::initialize_config(
+                &authority.pubkey(),
+                &admin,
+                allowed_key_types,
+                issuance_fee,
+            );
+            return write_transaction(&[ix], &authority, &tx);
+        }
+        ConfigCommand::WithdrawFees {
+            recipient,
+            amount,
+            admin,
+            tx,
+        } => {
+            let admin = read_keypair(&admin)?;
+            let ix = instruction::withdraw_fees(&admin.pubkey(), &recipient, amount);
+            return write_transaction(&[ix], &admin, &tx);
+        }
+        ConfigCommand::Pause { admin, tx } => (admin, ConfigUpdate::Paused(true), tx),
+        ConfigCommand::Unpause { admin, tx } => (admin, ConfigUpdate::Paused(false), tx),
+        ConfigCommand::SetAdmin {
+            new_admin,
+            admin,
+            tx,
+        } => (admin, ConfigUpdate::Admin(new_admin), tx),
+        ConfigCommand::SetKeyTypes {
+            allowed_key_types,
+            admin,
+            tx,
+        } => (admin, ConfigUpdate::AllowedKeyTypes(allowed_key_types), tx),
+        ConfigCommand::SetFee {
+            issuance_fee,
+            admin,
+            tx,
+        } => (admin, ConfigUpdate::IssuanceFee(issuance_fee), tx),
+    };
+    let admin = read_keypair(&admin)?;
+    let ix = instruction::update_config(&admin.pubkey(), update);
+    write_transaction(&[ix], &admin, &tx)
+}
+
+fn run_delegation(action: DelegationCommand) -> Result<()> {
+    match action {
+        DelegationCommand::Create {
+            did,
+            delegate,
+            id,
+            schema_id_prefix,
+            max_credentials,
+            can_revoke,
+            expire_at,
+            controller,
+            tx,
+        } => {
+            validation::validate_schema_id_prefix(&schema_id_prefix)?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::create_delegation(
+                &controller.pubkey(),
+                CreateDelegationArgs {
+                    did: &did,
+                    delegate,
+                    id,
+                    schema_id_prefix: &schema_id_prefix,
+                    max_credentials,
+                    can_revoke,
+                    expire_at,
+                },
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+        DelegationCommand::Revoke {
+            did,
+            delegate,
+            id,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::revoke_delegation(&controller.pubkey(), &did, &delegate, id);
+            write_transaction(&[ix], &controller, &tx)
+        }
+        DelegationCommand::IssueCredential {
+            did,
+            credential_id,
+            expire_at,
+            holder,
+            schema,
+            field_merkle_root,
+            index_page,
+            delegate,
+            delegation_id,
+            tx,
+        } => {
+            validation::validate_credential_id(&credential_id)?;
+            let holder = read_holder(holder)?;
+            let field_merkle_root = field_merkle_root
+                .as_deref()
+                .map(parse_field_element)
+                .transpose()?;
+            let delegate = read_keypair(&delegate)?;
+            let ix = instruction::add_delegated_credential(
+                &delegate.pubkey(),
+                &delegate.pubkey(),
+                delegation_id,
+                &did,
+                &credential_id,
+                expire_at,
+                holder.as_ref(),
+                &schema,
+                field_merkle_root.as_ref(),
+                index_page,
+            );
+            write_transaction(&[ix], &delegate, &tx)
+        }
+        DelegationCommand::RevokeCredential {
+            did,
+            credential_id,
+            delegate,
+            delegation_id,
+            tx,
+        } => {
+            let delegate = read_keypair(&delegate)?;
+            let ix = instruction::revoke_delegated_credential(
+                &delegate.pubkey(),
+                delegation_id,
+                &did,
+                &credential_id,
+            );
+            write_transaction(&[ix], &delegate, &tx)
+        }
+    }
+}
+
+fn run_schema(action: SchemaCommand) -> Result<()> {
+    match action {
+        SchemaCommand::Create {
+            did,
+            schema_id,
+            schema_version,
+            name,
+            fields,
+            json_schema,
+            controller,
+            tx,
+        } => {
+            let fields = fields
+                .iter()
+                .map(|field| parse_schema_field(field))
+                .collect::<Result<Vec<_>>>()?;
+            validation::validate_credential_schema(&schema_id, &name, &fields)?;
+            let document = fs::read(&json_schema)
+                .with_context(|| format!("failed to read {}", json_schema.display()))?;
+            serde_json::from_slice::<serde_json::Value>(&document)
+                .with_context(|| format!("{} is not valid JSON", json_schema.display()))?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::create_credential_schema(
+                &controller.pubkey(),
+                CreateCredentialSchemaArgs {
+                    did: &did,
+                    schema_id: &schema_id,
+                    schema_version,
+                    name: &name,
+                    fields,
+                    content_hash: solana_sdk::hash::hash(&document).to_bytes(),
+                },
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+        SchemaCommand::Deprecate {
+            did,
+            schema_id,
+            schema_version,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::deprecate_credential_schema(
+                &controller.pubkey(),
+                &did,
+                &schema_id,
+                schema_version,
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+    }
+}
+
+fn run_verification_schema(action: VerificationSchemaCommand) -> Result<()> {
+    match action {
+        VerificationSchemaCommand::Create {
+            did,
+            schema_id,
+            schema_version,
+            name,
+            check_roots,
+            requested_fields,
+            controller,
+            tx,
+        } => {
+            let check_roots = check_roots
+                .iter()
+                .map(|root| parse_field_element(root))
+                .collect::<Result<Vec<_>, _>>()?;
+            validation::validate_verification_schema(
+                &schema_id,
+                &name,
+                &check_roots,
+                &requested_fields,
+            )?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::create_verification_schema(
+                &controller.pubkey(),
+                CreateVerificationSchemaArgs {
+                    did: &did,
+                    schema_id: &schema_id,
+                    schema_version,
+                    name: &name,
+                    check_roots,
+                    requested_fields,
+                },
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+        VerificationSchemaCommand::Deprecate {
+            did,
+            schema_id,
+            schema_version,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::deprecate_verification_schema(
+                &controller.pubkey(),
+                &did,
+                &schema_id,
+                schema_version,
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+    }
+}
+
+fn run_accreditation(action: AccreditationCommand) -> Result<()> {
+    match action {
+        AccreditationCommand::Grant {
+            did,
+            issuer_did,
+            scope,
+            max_depth,
+            expire_at,
+            controller,
+            tx,
+        } => {
+            let scope = read_scope(scope);
+            validation::validate_accreditation(&scope, max_depth)?;
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::accredit_issuer(
+                &controller.pubkey(),
+                AccreditIssuerArgs {
+                    did: &did,
+                    issuer_did: &issuer_did,
+                    scope,
+                    max_depth,
+                    expire_at,
+                },
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+        AccreditationCommand::Revoke {
+            did,
+            issuer_did,
+            scope,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::revoke_accreditation(
+                &controller.pubkey(),
+                &did,
+                &issuer_did,
+                read_scope(scope),
+            );
+            write_transaction(&[ix], &controller, &tx)
+        }
+        AccreditationCommand::Check {
+            root_did,
+            via,
+            issuer_did,
+            scope,
+            credential_id,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let via: Vec<&str> = via.iter().map(String::as_str).collect();
+            let ix = instruction::check_accreditation(
+                &root_did,
+                &via,
+                &issuer_did,
+                read_scope(scope),
+                credential_id.as_deref(),
+            );
+            write_transaction(&[ix], &fee_payer, &tx)
+        }
+    }
+}
+
+fn run_recovery(action: RecoveryCommand) -> Result<()> {
+    match action {
+        RecoveryCommand::SetGuardians {
+            did,
+            guardian_keys,
+            guardian_dids,
+            threshold,
+            delay,
+            controller,
+            tx,
+        } => {
+            let guardians = guardian_keys
+                .into_iter()
+                .map(Guardian::Key)
+                .chain(
+                    guardian_dids
+                        .iter()
+                        .map(|did| Guardian::Did(find_did_document_address(did, &ID).0)),
+                )
+                .collect();
+            let controller = read_keypair(&controller)?;
+            let ix =
+                instruction::set_guardians(&controller.pubkey(), &did, guardians, threshold, delay);
+            write_transaction(&[ix], &controller, &tx)
+        }
+        RecoveryCommand::Initiate {
+            did,
+            new_controller,
+            guardian,
+            tx,
+        } => {
+            let signer = read_keypair(&guardian.guardian)?;
+            let ix = instruction::initiate_recovery(
+                &signer.pubkey(),
+                &signer.pubkey(),
+                guardian.guardian_did.as_deref(),
+                &did,
+                &new_controller,
+            );
+            write_transaction(&[ix], &signer, &tx)
+        }
+        RecoveryCommand::Approve { did, guardian, tx } => {
+            let signer = read_keypair(&guardian.guardian)?;
+            let ix = instruction::approve_recovery(
+                &signer.pubkey(),
+                guardian.guardian_did.as_deref(),
+                &did,
+            );
+            write_transaction(&[ix], &signer, &tx)
+        }
+        RecoveryCommand::Cancel {
+            did,
+            payer,
+            controller,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let controller = controller.as_deref().map(read_keypair).transpose()?;
+            let ix = instruction::cancel_recovery(
+                controller.as_ref().map(Keypair::pubkey).as_ref(),
+                &payer,
+                &did,
+            );
+            let mut signers = vec![&fee_payer];
+            signers.extend(controller.as_ref());
+            write_transaction_with(&[ix], &signers, &tx)
+        }
+        RecoveryCommand::Execute {
+            did,
+            controller,
+            new_controller,
+            payer,
+            from_index_page,
+            to_index_page,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let ix = instruction::execute_recovery(
+                &fee_payer.pubkey(),
+                &payer,
+                &did,
+                &controller,
+                &new_controller,
+                from_index_page,
+                to_index_page,
+            );
+            write_transaction(&[ix], &fee_payer, &tx)
+        }
+    }
+}
+
+fn run_operation(action: OperationCommand) -> Result<()> {
+    match action {
+        OperationCommand::SetDelay {
+            did,
+            delay,
+            lock,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = match (lock.propose, lock.execute) {
+                (Some(id), _) => instruction::propose_operation(
+                    &controller.pubkey(),
+                    &did,
+                    id,
+                    DidOperation::SetOperationDelay {
+                        operation_delay: delay,
+                    },
+                ),
+                (None, Some(id)) => {
+                    instruction::execute_set_operation_delay(&controller.pubkey(), &did, delay, id)
+                }
+                (None, None) => instruction::set_operation_delay(&controller.pubkey(), &did, delay),
+            };
+            write_transaction(&[ix], &controller, &tx)
+        }
+        OperationCommand::Cancel {
+            did,
+            id,
+            controller,
+            tx,
+        } => {
+            let controller = read_keypair(&controller)?;
+            let ix = instruction::cancel_operation(&controller.pubkey(), &did, id);
+            write_transaction(&[ix], &controller, &tx)
+        }
+    }
+}
+
+fn run_auth(action: AuthCommand) -> Result<()> {
+    match action {
+        AuthCommand::Challenge {
+            did,
+            audience,
+            session_key,
+            fee_payer,
+            tx,
+        } => {
+            let fee_payer = read_keypair(&fee_payer)?;
+            let session_key = read_keypair(&session_key)?;
+            let ix = instruction::request_auth_challenge(
+                &fee_payer.pubkey(),
+                &session_key.pubkey(),
+                &did,
+                &audience,
+            );
+            write_transaction_with(&[ix], &[&fee_payer, &session_key], &tx)
+        }
+        AuthCommand::Login {
+            did,
+            authentication_id,
+            challenge,
+            duration,
+            secp256k1_key,
+            solana_key,
+            fee_payer,
+            tx,
+        } => {
+            let challenge = match RegistryAccount::decode(&read_account_data(&challenge)?)? {
+                RegistryAccount::AuthChallenge(challenge) => challenge,
+                other => bail!("expected an AuthChallenge account, found {}", other.name()),
+            };
+            if challenge.expire_at <= now_millis()? {
+                bail!("the challenge has expired, request a new one");
+            }
+            let fee_payer = read_keypair(&fee_payer)?;
+            let authentication_key = read_issuer_key(&secp256k1_key, &solana_key)?;
+            let signature;
+            let ix = instruction::create_auth_session(
+                &fee_payer.pubkey(),
+                &challenge.payer,
+                &did,
+                &challenge.session_key,
+                &authentication_id,
+                duration,
+                match &authentication_key {
+                    IssuerKey::Secp256k1(signer) => {
+                        signature = signer.sign_auth_challenge(&challenge);
+                        IssuerAuthentication::Secp256k1(&signature)
+                    }
+                    IssuerKey::Solana(keypair) => IssuerAuthentication::Signer(keypair.pubkey()),
+                },
+            );
+            write_transaction_signed_by(&[ix], &fee_payer, &authentication_key, &tx)
+        }
+        AuthCommand::Logout {
+            did,
+            session_key,
+            payer,
+            closer,
+            tx,
+        } => {
+            let closer = read_keypair(&closer)?;
+            let ix = instruction::close_auth_session(&closer.pubkey(), &payer, &did, &session_key);
+            write_transaction(&[ix], &closer, &tx)
+        }
+    }
+}
+
+fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path)
+        .map_err(|err| anyhow!("failed to read keypair {}: {err}", path.display()))
+}
+
+fn read_secp256k1_key(path: &Path) -> Result<Secp256k1Signer> {
+    let hex =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(Secp256k1Signer::from_hex(hex.trim())?)
+}
+
+fn read_holder(holder: HolderArgs) -> Result<Option<CredentialHolder>> {
+    match (holder.holder_did, holder.holder_public_key) {
+        (Some(did), _) => {
+            validation::validate_did(&did)?;
+            Ok(Some(CredentialHolder::Did(did)))
+        }
+        (None, Some(public_key)) => Ok(Some(holder_key_commitment(&public_key)?)),
+        (None, None) => Ok(None),
+    }
+}
+
+// Exactly one of the flags is set, see `ScopeArgs`.
+fn read_scope(scope: ScopeArgs) -> AccreditationScope {
+    match (scope.credential_type, scope.schema) {
+        (_, Some(schema)) => AccreditationScope::Schema(schema),
+        (credential_type, None) => {
+            AccreditationScope::CredentialType(credential_type.unwrap_or_default())
+        }
+    }
+}
+
+// `name:type`, see `SchemaCommand::Create`.
+fn parse_schema_field(field: &str) -> Result<SchemaField> {
+    let (name, field_type) = field
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("expected `name:type`, got {field}"))?;
+    let field_type = match field_type {
+        "string" => SchemaFieldType::String,
+        "integer" => SchemaFieldType::Integer,
+        "number" => SchemaFieldType::Number,
+        "boolean" => SchemaFieldType::Boolean,
+        "date" => SchemaFieldType::Date,
+        "bytes" => SchemaFieldType::Bytes,
+        other => bail!("unknown field type {other} of {name}"),
+    };
+    Ok(SchemaField {
+        name: name.to_string(),
+        field_type,
+    })
+}
+
+// Authentication key of an issuer or a DID logging in, see `CredentialArgs`.
+enum IssuerKey {
+    Secp256k1(Secp256k1Signer),
+    Solana(Keypair),
+}
+
+fn read_issuer_key(
+    secp256k1_key: &Option<PathBuf>,
+    solana_key: &Option<PathBuf>,
+) -> Result<IssuerKey> {
+    match (secp256k1_key, solana_key) {
+        (Some(path), _) => Ok(IssuerKey::Secp256k1(read_secp256k1_key(path)?)),
+        (None, Some(path)) => Ok(IssuerKey::Solana(read_keypair(path)?)),
+        (None, None) => bail!("either --secp256k1-key or --solana-key is required"),
+    }
+}
+
+// A Solana issuer key co-signs the transaction paid by `fee_payer`.
+fn write_transaction_signed_by(
+    instructions: &[Instruction],
+    fee_payer: &Keypair,
+    issuer_key: &IssuerKey,
+    tx: &TxArgs,
+) -> Result<()> {
+    match issuer_key {
+        IssuerKey::Secp256k1(_) => write_transaction(instructions, fee_payer, tx),
+        IssuerKey::Solana(keypair) => {
+            write_transaction_with(instructions, &[fee_payer, keypair], tx)
+        }
+    }
+}
+
+fn write_transaction(instructions: &[Instruction], signer: &Keypair, tx: &TxArgs) -> Result<()> {
+    write_transaction_with(instructions, &[signer], tx)
+}
+
+fn write_transaction_with(
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+    tx: &TxArgs,
+) -> Result<()> {
+    let transaction = build_signed_transaction(instructions, signers, tx.blockhash);
+    let serialized = bincode::serialize(&transaction)?;
+    fs::write(&tx.out, STANDARD.encode(serialized))
+        .with_context(|| format!("failed to write {}", tx.out.display()))?;
+    println!("signature: {}", transaction.signatures[0]);
+    println!("written:   {}", tx.out.display());
+    Ok(())
+}
+
+fn now_millis() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
@@ -0,0 +1,9 @@
+mod account_file;
+mod cli;
+mod commands;
+
+use clap::Parser;
+
+fn main() -> anyhow::Result<()> {
+    commands::run(cli::Cli::parse())
+}
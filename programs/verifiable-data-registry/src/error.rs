@@ -8,4 +8,146 @@ pub enum VerifiableDataRegistryError {
     NotSupportKeyType,
     #[msg("Invalid multibase")]
     InvalidMultibase,
+    #[msg("Invalid validity window")]
+    InvalidValidityWindow,
+    #[msg("Verification method is not valid at this time")]
+    VerificationMethodNotValid,
+    #[msg("Verification relationship is not valid at this time")]
+    VerificationRelationshipNotValid,
+    #[msg("Index page is full")]
+    IndexPageFull,
+    #[msg("Index entry not found")]
+    IndexEntryNotFound,
+    #[msg("New controller is the current controller")]
+    SameController,
+    #[msg("Credential cannot be closed before its expiry grace period has passed")]
+    CredentialNotClosable,
+    #[msg("Account layout is outdated, migrate it first")]
+    AccountVersionMismatch,
+    #[msg("Account is already on the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Account does not match any known layout")]
+    AccountNotMigratable,
+    #[msg("Identifier must be ASCII, percent-encode other characters")]
+    NonAsciiIdentifier,
+    #[msg("DID is too long")]
+    DidTooLong,
+    #[msg("DID does not match did:<method>:<method-specific-id>")]
+    InvalidDid,
+    #[msg("Key id is too long")]
+    KeyIdTooLong,
+    #[msg("Key id must be <did>#<fragment> of the DID")]
+    InvalidKeyId,
+    #[msg("Key type is too long")]
+    KeyTypeTooLong,
+    #[msg("Key type must be alphanumeric")]
+    InvalidKeyType,
+    #[msg("Public key multibase is too long")]
+    PublicKeyMultibaseTooLong,
+    #[msg("Credential id is too long")]
+    CredentialIdTooLong,
+    #[msg("Credential id must be visible ASCII")]
+    InvalidCredentialId,
+    #[msg("Registry is paused")]
+    RegistryPaused,
+    #[msg("Too many allowed key types")]
+    TooManyKeyTypes,
+    #[msg("Not enough collected fees")]
+    InsufficientFees,
+    #[msg("Delegation has expired")]
+    DelegationExpired,
+    #[msg("Delegation has issued its maximum number of credentials")]
+    DelegationLimitReached,
+    #[msg("Credential schema is outside the scope of the delegation")]
+    CredentialNotInDelegationScope,
+    #[msg("Delegation does not allow revocation")]
+    DelegationCannotRevoke,
+    #[msg("Too many guardians")]
+    TooManyGuardians,
+    #[msg("Guardian is listed twice")]
+    DuplicateGuardian,
+    #[msg("Guardian threshold must be between 1 and the number of guardians")]
+    InvalidGuardianThreshold,
+    #[msg("Signer is not a guardian of the DID")]
+    NotGuardian,
+    #[msg("Guardian already approved the recovery")]
+    RecoveryAlreadyApproved,
+    #[msg("Recovery is not approved or still in its waiting period")]
+    RecoveryNotExecutable,
+    #[msg("DID operations are time-locked, propose them first")]
+    OperationTimeLocked,
+    #[msg("Pending operation is still in its waiting period")]
+    OperationNotExecutable,
+    #[msg("Pending operation does not match the executed one")]
+    OperationMismatch,
+    #[msg("Public key is not a Solana public key")]
+    InvalidSolanaPublicKey,
+    #[msg("Authentication challenge has expired")]
+    AuthChallengeExpired,
+    #[msg("Session duration must be between 1 and the maximum session duration")]
+    InvalidAuthSessionDuration,
+    #[msg("Authentication session has expired")]
+    AuthSessionExpired,
+    #[msg("Authentication session is for another audience")]
+    AuthSessionAudienceMismatch,
+    #[msg("Public key is not an X25519 public key")]
+    InvalidX25519PublicKey,
+    #[msg("Key type cannot be used for this relationship")]
+    RelationshipNotSupportedByKeyType,
+    #[msg("Too many alsoKnownAs entries")]
+    TooManyAlsoKnownAs,
+    #[msg("alsoKnownAs entries must be distinct and differ from the DID")]
+    InvalidAlsoKnownAs,
+    #[msg("Invalid P-256 public key")]
+    InvalidP256PublicKey,
+    #[msg("Invalid WebAuthn assertion")]
+    InvalidWebAuthnAssertion,
+    #[msg("Credential has no holder able to acknowledge it on-chain")]
+    HolderCannotAcknowledge,
+    #[msg("Credential is revoked or expired")]
+    CredentialNotActive,
+    #[msg("Credential was already acknowledged by its holder")]
+    CredentialAlreadyAcknowledged,
+    #[msg("Schema id must be visible ASCII")]
+    InvalidSchemaId,
+    #[msg("Schema name must be printable ASCII")]
+    InvalidSchemaName,
+    #[msg("Schema must have between 1 and the maximum number of fields")]
+    InvalidSchemaFieldCount,
+    #[msg("Schema field names must be distinct visible ASCII")]
+    InvalidSchemaField,
+    #[msg("Credential schema is deprecated")]
+    CredentialSchemaDeprecated,
+    #[msg("Credential schema is already deprecated")]
+    CredentialSchemaAlreadyDeprecated,
+    #[msg("Verification schema must have between 1 and the maximum number of credential checks")]
+    InvalidSchemaCheckCount,
+    #[msg("Requested fields must be distinct `<check index>:<field>` entries")]
+    InvalidRequestedField,
+    #[msg("Verification schema is already deprecated")]
+    VerificationSchemaAlreadyDeprecated,
+    #[msg("Credential type must be visible ASCII")]
+    InvalidCredentialType,
+    #[msg("Accreditation allows more levels below it than the maximum depth")]
+    InvalidAccreditationDepth,
+    #[msg("A DID cannot accredit itself")]
+    SelfAccreditation,
+    #[msg("Accreditation chain does not link the issuer to its authorities for this scope")]
+    AccreditationChainMismatch,
+    #[msg("Accreditation is expired")]
+    AccreditationExpired,
+    #[msg("Issuer is not accredited by the root authority")]
+    IssuerNotAccredited,
+    #[msg("Credential is not covered by the accreditation scope")]
+    CredentialNotInAccreditationScope,
+    #[msg("Credential was not issued with this field Merkle root")]
+    FieldMerkleRootMismatch,
+    #[msg("Credential id belongs to a closed credential and cannot be issued again")]
+    CredentialIdClosed,
+    #[msg("Recovery was not approved by enough guardians in time")]
+    RecoveryExpired,
+    #[msg("Recovery can still be approved or executed")]
+    RecoveryNotExpired,
+    #[msg("Credential would expire after the delegation it is issued under")]
+    CredentialOutlivesDelegation,
 }
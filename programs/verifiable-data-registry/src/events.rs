@@ -0,0 +1,289 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct DidInitialized {
+    pub did: String,
+    pub controller: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DidControllerTransferred {
+    pub did: String,
+    pub previous_controller: Pubkey,
+    pub new_controller: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VerificationMethodAdded {
+    pub did: String,
+    pub key_id: String,
+    pub r#type: String,
+    pub controller: Pubkey,
+    pub valid_from: Option<u64>,
+    pub valid_until: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RelationshipAdded {
+    pub did: String,
+    pub relationship: Relationship,
+    pub key_id: String,
+    pub valid_from: Option<u64>,
+    pub valid_until: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialAdded {
+    pub issuer_did: String,
+    pub authentication_id: String,
+    pub credential_id: String,
+    pub expire_at: Option<u64>,
+    pub holder: Option<CredentialHolder>,
+    pub field_merkle_root: Option<[u8; 32]>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialRevoked {
+    pub issuer_did: String,
+    pub authentication_id: String,
+    pub credential_id: String,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialClosed {
+    pub issuer_did: String,
+    pub credential_id: String,
+    pub closed_by: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialAcknowledged {
+    pub issuer_did: String,
+    pub credential_id: String,
+    pub holder: CredentialHolder,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RegistryConfigUpdated {
+    pub admin: Pubkey,
+    pub allowed_key_types: Vec<String>,
+    pub issuance_fee: Option<u64>,
+    pub paused: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegationCreated {
+    pub did: String,
+    pub delegate: Pubkey,
+    pub id: u64,
+    pub schema_id_prefix: String,
+    pub max_credentials: Option<u32>,
+    pub can_revoke: bool,
+    pub expire_at: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegationRevoked {
+    pub did: String,
+    pub delegate: Pubkey,
+    pub id: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegatedCredentialAdded {
+    pub issuer_did: String,
+    pub delegation: Pubkey,
+    pub credential_id: String,
+    pub expire_at: Option<u64>,
+    pub holder: Option<CredentialHolder>,
+    pub field_merkle_root: Option<[u8; 32]>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegatedCredentialRevoked {
+    pub issuer_did: String,
+    pub delegation: Pubkey,
+    pub credential_id: String,
+    pub slot: u64,
+}
+
+#[event]
+pub struct GuardiansUpdated {
+    pub did: String,
+    pub guardians: Vec<Guardian>,
+    pub guardian_threshold: u8,
+    pub recovery_delay: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RecoveryApproved {
+    pub did: String,
+    pub new_controller: Pubkey,
+    pub guardian: Guardian,
+    pub executable_at: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RecoveryCancelled {
+    pub did: String,
+    pub new_controller: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RecoveryExecuted {
+    pub did: String,
+    pub previous_controller: Pubkey,
+    pub new_controller: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OperationProposed {
+    pub did: String,
+    pub id: u64,
+    pub operation: DidOperation,
+    pub executable_at: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OperationExecuted {
+    pub did: String,
+    pub id: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OperationCancelled {
+    pub did: String,
+    pub id: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OperationDelayUpdated {
+    pub did: String,
+    pub operation_delay: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlsoKnownAsUpdated {
+    pub did: String,
+    pub also_known_as: Vec<String>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthChallengeIssued {
+    pub did: String,
+    pub session_key: Pubkey,
+    pub audience: Pubkey,
+    pub nonce: [u8; 32],
+    pub expire_at: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthSessionCreated {
+    pub did: String,
+    pub authentication_id: String,
+    pub session_key: Pubkey,
+    pub audience: Pubkey,
+    pub expire_at: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthSessionClosed {
+    pub did: String,
+    pub session_key: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialSchemaCreated {
+    pub author_did: String,
+    pub schema: Pubkey,
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub content_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct CredentialSchemaDeprecated {
+    pub author_did: String,
+    pub schema: Pubkey,
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VerificationSchemaCreated {
+    pub verifier_did: String,
+    pub schema: Pubkey,
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VerificationSchemaDeprecated {
+    pub verifier_did: String,
+    pub schema: Pubkey,
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct IssuerAccredited {
+    pub authority_did: String,
+    pub issuer_did: String,
+    pub scope: AccreditationScope,
+    pub max_depth: u8,
+    pub expire_at: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AccreditationRevoked {
+    pub authority_did: String,
+    pub issuer_did: String,
+    pub scope: AccreditationScope,
+    pub slot: u64,
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::IssuerAccredited, state::*,
+    validation::validate_accreditation,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, issuer_did: String, scope: AccreditationScope)]
+pub struct AccreditIssuer<'info> {
+    #[account(
+        init,
+        seeds = [
+            ACCREDITATION_SEED,
+            did_document.key().as_ref(),
+            issuer_did_document.key().as_ref(),
+            scope.seed().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = Accreditation::space(&scope)
+    )]
+    pub accreditation: Account<'info, Accreditation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [keccak::hash(issuer_did.as_bytes()).as_ref()],
+        bump,
+        constraint = issuer_did_document.did == issuer_did,
+    )]
+    pub issuer_did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Whether `did` is itself accredited is only looked at by `check_accreditation`, against
+// the root authority the verifier trusts.
+pub fn accredit_issuer_handler(
+    ctx: Context<AccreditIssuer>,
+    did: String,
+    issuer_did: String,
+    scope: AccreditationScope,
+    max_depth: u8,
+    expire_at: Option<u64>,
+) -> Result<()> {
+    validate_accreditation(&scope, max_depth)?;
+    require_keys_neq!(
+        ctx.accounts.did_document.key(),
+        ctx.accounts.issuer_did_document.key(),
+        VerifiableDataRegistryError::SelfAccreditation
+    );
+
+    let accreditation = &mut ctx.accounts.accreditation;
+    accreditation.version = Accreditation::VERSION;
+    accreditation.authority = ctx.accounts.did_document.key();
+    accreditation.issuer = ctx.accounts.issuer_did_document.key();
+    accreditation.scope = scope.clone();
+    accreditation.max_depth = max_depth;
+    accreditation.expire_at = expire_at;
+
+    emit!(IssuerAccredited {
+        authority_did: did,
+        issuer_did,
+        scope,
+        max_depth,
+        expire_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, state::*, utils::current_timestamp};
+
+#[derive(Accounts)]
+#[instruction(root_did: String, issuer_did: String)]
+pub struct CheckAccreditation<'info> {
+    #[account(
+        seeds = [keccak::hash(root_did.as_bytes()).as_ref()],
+        bump,
+        constraint = root_did_document.did == root_did,
+    )]
+    pub root_did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [keccak::hash(issuer_did.as_bytes()).as_ref()],
+        bump,
+        constraint = issuer_did_document.did == issuer_did,
+    )]
+    pub issuer_did_document: Account<'info, DidDocument>,
+    // Checked to be active and issued by `issuer_did` when given.
+    pub credential_state: Option<Account<'info, CredentialState>>,
+}
+
+// Meant to be called by other programs: succeeds only when `issuer_did` is the root authority
+// itself or is linked to it for `scope` by the `Accreditation` accounts passed as remaining
+// accounts, ordered from the one granted to the issuer up to the one granted by the root.
+// Each of them must allow at least as many levels below it as it has in the chain. Read-only,
+// so not gated by the registry pause.
+pub fn check_accreditation_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CheckAccreditation<'info>>,
+    _root_did: String,
+    issuer_did: String,
+    scope: AccreditationScope,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    if let Some(credential_state) = &ctx.accounts.credential_state {
+        require!(
+            credential_state.issuer_did == issuer_did,
+            VerifiableDataRegistryError::CredentialNotInAccreditationScope
+        );
+        require!(
+            credential_state.is_active_at(now),
+            VerifiableDataRegistryError::CredentialNotActive
+        );
+        if let AccreditationScope::Schema(schema) = &scope {
+            require!(
+                credential_state.schema.as_ref() == Some(schema),
+                VerifiableDataRegistryError::CredentialNotInAccreditationScope
+            );
+        }
+    }
+
+    require!(
+        ctx.remaining_accounts.len() <= MAX_ACCREDITATION_DEPTH as usize,
+        VerifiableDataRegistryError::IssuerNotAccredited
+    );
+    let mut subject = ctx.accounts.issuer_did_document.key();
+    for (levels_below, account) in ctx.remaining_accounts.iter().enumerate() {
+        let accreditation = Account::<Accreditation>::try_from(account)?;
+        require!(
+            accreditation.version == Accreditation::VERSION,
+            VerifiableDataRegistryError::AccountVersionMismatch
+        );
+        require!(
+            accreditation.issuer == subject && accreditation.scope == scope,
+            VerifiableDataRegistryError::AccreditationChainMismatch
+        );
+        require!(
+            accreditation.max_depth as usize >= levels_below,
+            VerifiableDataRegistryError::IssuerNotAccredited
+        );
+        require!(
+            !accreditation.is_expired_at(now),
+            VerifiableDataRegistryError::AccreditationExpired
+        );
+        subject = accreditation.authority;
+    }
+    require_keys_eq!(
+        subject,
+        ctx.accounts.root_did_document.key(),
+        VerifiableDataRegistryError::IssuerNotAccredited
+    );
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod accredit_issuer;
+pub use accredit_issuer::*;
+
+pub mod revoke_accreditation;
+pub use revoke_accreditation::*;
+
+pub mod check_accreditation;
+pub use check_accreditation::*;
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::AccreditationRevoked, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, issuer_did: String, scope: AccreditationScope)]
+pub struct RevokeAccreditation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            ACCREDITATION_SEED,
+            did_document.key().as_ref(),
+            issuer_did_document.key().as_ref(),
+            scope.seed().as_ref()
+        ],
+        bump,
+        close = controller
+    )]
+    pub accreditation: Account<'info, Accreditation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [keccak::hash(issuer_did.as_bytes()).as_ref()],
+        bump,
+        constraint = issuer_did_document.did == issuer_did,
+    )]
+    pub issuer_did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+}
+
+// Closing the accreditation breaks every chain going through it, so the issuer and whoever
+// it accredited stop passing `check_accreditation`. Credentials already issued are left to
+// the verifier to judge.
+pub fn revoke_accreditation_handler(
+    _ctx: Context<RevokeAccreditation>,
+    did: String,
+    issuer_did: String,
+    scope: AccreditationScope,
+) -> Result<()> {
+    emit!(AccreditationRevoked {
+        authority_did: did,
+        issuer_did,
+        scope,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, state::*, utils::current_timestamp};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct CheckAuthSession<'info> {
+    #[account(
+        seeds = [
+            AUTH_SESSION_SEED,
+            did_document.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump,
+        constraint =
+            auth_session.version == AuthSession::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub auth_session: Account<'info, AuthSession>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    pub session_key: Signer<'info>,
+}
+
+// Meant to be called by other programs: succeeds only when the transaction is signed by a
+// session key currently logged in to `did` for `audience`. Read-only, so not gated by the
+// registry pause.
+pub fn check_auth_session_handler(
+    ctx: Context<CheckAuthSession>,
+    _did: String,
+    audience: Pubkey,
+) -> Result<()> {
+    let auth_session = &ctx.accounts.auth_session;
+    require_keys_eq!(
+        auth_session.audience,
+        audience,
+        VerifiableDataRegistryError::AuthSessionAudienceMismatch
+    );
+    require!(
+        current_timestamp()? < auth_session.expire_at,
+        VerifiableDataRegistryError::AuthSessionExpired
+    );
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::AuthSessionClosed, state::*,
+    utils::current_timestamp,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct CloseAuthSession<'info> {
+    #[account(
+        mut,
+        seeds = [
+            AUTH_SESSION_SEED,
+            did_document.key().as_ref(),
+            auth_session.session_key.as_ref()
+        ],
+        bump,
+        has_one = payer,
+        close = payer
+    )]
+    pub auth_session: Account<'info, AuthSession>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    /// CHECK: checked against `auth_session.payer`, receives the rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+}
+
+// The session key logs out and the DID controller ends a session at any time; once expired,
+// anyone may close the session. Not gated by the registry pause so sessions can always end.
+pub fn close_auth_session_handler(ctx: Context<CloseAuthSession>, did: String) -> Result<()> {
+    let auth_session = &ctx.accounts.auth_session;
+    let closer = ctx.accounts.closer.key();
+    require!(
+        closer == auth_session.session_key
+            || closer == ctx.accounts.did_document.controller
+            || auth_session.expire_at <= current_timestamp()?,
+        VerifiableDataRegistryError::Unauthorized
+    );
+
+    emit!(AuthSessionClosed {
+        did,
+        session_key: auth_session.session_key,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
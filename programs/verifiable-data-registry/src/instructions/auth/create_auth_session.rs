@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use solana_program::{keccak, sysvar::instructions as sysvar_instructions};
+
+use crate::{
+    error::VerifiableDataRegistryError,
+    events::AuthSessionCreated,
+    state::*,
+    utils::{current_timestamp, verify_authentication},
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, authentication_id: String)]
+pub struct CreateAuthSession<'info> {
+    #[account(
+        init,
+        seeds = [
+            AUTH_SESSION_SEED,
+            did_document.key().as_ref(),
+            challenge.session_key.as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = AUTH_SESSION_SPACE
+    )]
+    pub auth_session: Account<'info, AuthSession>,
+    // Consumed by the session, a nonce answers a single login.
+    #[account(
+        mut,
+        seeds = [
+            AUTH_CHALLENGE_SEED,
+            did_document.key().as_ref(),
+            challenge.session_key.as_ref()
+        ],
+        bump,
+        close = challenge_payer,
+        constraint =
+            challenge.version == AuthChallenge::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub challenge: Account<'info, AuthChallenge>,
+    /// CHECK: checked against `challenge.payer`, receives the rent of the challenge.
+    #[account(mut, address = challenge.payer)]
+    pub challenge_payer: UncheckedAccount<'info>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == authentication.did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [keccak::hash([did.as_bytes(), authentication_id.as_bytes()].concat().as_slice()).as_ref()],
+        bump,
+        constraint = verification_method.key_id == authentication.key_id
+    )]
+    pub verification_method: Account<'info, VerificationMethod>,
+    #[account(
+        seeds = [keccak::hash([
+            did.as_bytes(),
+            Relationship::Authentication.as_bytes(),
+            authentication_id.as_bytes()
+        ].concat().as_slice()).as_ref()],
+        bump
+    )]
+    pub authentication: Account<'info, VerificationRelationship>,
+    // Only required when the authentication key is a Solana key.
+    pub authentication_signer: Option<Signer<'info>>,
+    // Only required when the authentication key is a passkey.
+    /// CHECK: the instructions sysvar, read for the secp256r1 instruction of the assertion.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_auth_session_handler(
+    ctx: Context<CreateAuthSession>,
+    did: String,
+    authentication_id: String,
+    duration: u64,
+    secp256k1_signature: Option<Secp256k1Signature>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    let challenge = &ctx.accounts.challenge;
+    require!(
+        now < challenge.expire_at,
+        VerifiableDataRegistryError::AuthChallengeExpired
+    );
+    require!(
+        duration > 0 && duration <= MAX_AUTH_SESSION_DURATION,
+        VerifiableDataRegistryError::InvalidAuthSessionDuration
+    );
+    verify_authentication(
+        &ctx.accounts.verification_method,
+        &ctx.accounts.authentication,
+        &ctx.accounts.config,
+        challenge.message_hash().as_ref(),
+        secp256k1_signature.as_ref(),
+        ctx.accounts.authentication_signer.as_ref(),
+        webauthn_assertion
+            .as_ref()
+            .zip(ctx.accounts.instructions_sysvar.as_deref()),
+        now,
+    )?;
+
+    let auth_session = &mut ctx.accounts.auth_session;
+    auth_session.version = AuthSession::VERSION;
+    auth_session.did_document = ctx.accounts.did_document.key();
+    auth_session.session_key = challenge.session_key;
+    auth_session.audience = challenge.audience;
+    auth_session.expire_at = now.saturating_add(duration);
+    auth_session.payer = ctx.accounts.payer.key();
+
+    emit!(AuthSessionCreated {
+        did,
+        authentication_id,
+        session_key: auth_session.session_key,
+        audience: auth_session.audience,
+        expire_at: auth_session.expire_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,11 @@
+pub mod request_auth_challenge;
+pub use request_auth_challenge::*;
+
+pub mod create_auth_session;
+pub use create_auth_session::*;
+
+pub mod check_auth_session;
+pub use check_auth_session::*;
+
+pub mod close_auth_session;
+pub use close_auth_session::*;
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::AuthChallengeIssued, state::*,
+    utils::current_timestamp,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct RequestAuthChallenge<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            AUTH_CHALLENGE_SEED,
+            did_document.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = AUTH_CHALLENGE_SPACE
+    )]
+    pub challenge: Account<'info, AuthChallenge>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    // Signs so that nobody else can replace the challenge it is about to answer.
+    pub session_key: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Requesting again replaces the nonce, which is how an unanswered challenge is refreshed.
+pub fn request_auth_challenge_handler(
+    ctx: Context<RequestAuthChallenge>,
+    did: String,
+    audience: Pubkey,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    let slot = Clock::get()?.slot;
+    let challenge = &mut ctx.accounts.challenge;
+    if challenge.did_document == Pubkey::default() {
+        challenge.version = AuthChallenge::VERSION;
+        challenge.did_document = ctx.accounts.did_document.key();
+        challenge.session_key = ctx.accounts.session_key.key();
+        challenge.payer = ctx.accounts.payer.key();
+    }
+    require!(
+        challenge.version == AuthChallenge::VERSION,
+        VerifiableDataRegistryError::AccountVersionMismatch
+    );
+    challenge.audience = audience;
+    challenge.nonce = keccak::hash(
+        [
+            challenge.key().as_ref(),
+            audience.as_ref(),
+            slot.to_le_bytes().as_ref(),
+            now.to_le_bytes().as_ref(),
+        ]
+        .concat()
+        .as_slice(),
+    )
+    .to_bytes();
+    challenge.expire_at = now.saturating_add(AUTH_CHALLENGE_TTL);
+
+    emit!(AuthChallengeIssued {
+        did,
+        session_key: challenge.session_key,
+        audience,
+        nonce: challenge.nonce,
+        expire_at: challenge.expire_at,
+        slot,
+    });
+    Ok(())
+}
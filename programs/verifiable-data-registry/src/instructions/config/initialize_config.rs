@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::RegistryConfigUpdated;
+use crate::program::VerifiableDataRegistry;
+use crate::state::*;
+use crate::validation::validate_key_type;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        payer = authority,
+        space = REGISTRY_CONFIG_SPACE
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, VerifiableDataRegistry>,
+    #[account(
+        constraint =
+            program_data.upgrade_authority_address == Some(authority.key())
+            @ VerifiableDataRegistryError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    // Only the upgrade authority may create the config, so it cannot be front-run.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_config_handler(
+    ctx: Context<InitializeConfig>,
+    admin: Pubkey,
+    allowed_key_types: Vec<String>,
+    issuance_fee: Option<u64>,
+) -> Result<()> {
+    validate_allowed_key_types(&allowed_key_types)?;
+
+    let config = &mut ctx.accounts.config;
+    config.version = RegistryConfig::VERSION;
+    config.admin = admin;
+    config.allowed_key_types = allowed_key_types;
+    config.issuance_fee = issuance_fee;
+    config.paused = false;
+
+    emit_config_updated(config)
+}
+
+pub(crate) fn emit_config_updated(config: &RegistryConfig) -> Result<()> {
+    emit!(RegistryConfigUpdated {
+        admin: config.admin,
+        allowed_key_types: config.allowed_key_types.clone(),
+        issuance_fee: config.issuance_fee,
+        paused: config.paused,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+pub(crate) fn validate_allowed_key_types(allowed_key_types: &[String]) -> Result<()> {
+    require!(
+        allowed_key_types.len() <= MAX_ALLOWED_KEY_TYPES,
+        VerifiableDataRegistryError::TooManyKeyTypes
+    );
+    allowed_key_types
+        .iter()
+        .try_for_each(|r#type| validate_key_type(r#type))
+}
@@ -0,0 +1,8 @@
+mod initialize_config;
+pub use initialize_config::*;
+
+mod update_config;
+pub use update_config::*;
+
+mod withdraw_fees;
+pub use withdraw_fees::*;
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use super::{emit_config_updated, validate_allowed_key_types};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [REGISTRY_CONFIG_SEED], bump, has_one = admin)]
+    pub config: Account<'info, RegistryConfig>,
+    pub admin: Signer<'info>,
+}
+
+pub fn update_config_handler(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    match update {
+        ConfigUpdate::Admin(admin) => config.admin = admin,
+        ConfigUpdate::AllowedKeyTypes(allowed_key_types) => {
+            validate_allowed_key_types(&allowed_key_types)?;
+            config.allowed_key_types = allowed_key_types;
+        }
+        ConfigUpdate::IssuanceFee(issuance_fee) => config.issuance_fee = issuance_fee,
+        ConfigUpdate::Paused(paused) => config.paused = paused,
+    }
+
+    emit_config_updated(config)
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::FeesWithdrawn;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut, seeds = [REGISTRY_CONFIG_SEED], bump, has_one = admin)]
+    pub config: Account<'info, RegistryConfig>,
+    pub admin: Signer<'info>,
+    /// CHECK: any account chosen by the admin to receive the fees.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+pub fn withdraw_fees_handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    let config = ctx.accounts.config.to_account_info();
+    let rent = Rent::get()?.minimum_balance(config.data_len());
+    require!(
+        config.lamports().saturating_sub(rent) >= amount,
+        VerifiableDataRegistryError::InsufficientFees
+    );
+
+    **config.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
+
+    emit!(FeesWithdrawn {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
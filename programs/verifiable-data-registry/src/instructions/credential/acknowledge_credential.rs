@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use solana_program::{keccak, secp256k1_recover, sysvar::instructions as sysvar_instructions};
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::CredentialAcknowledged;
+use crate::state::*;
+use crate::utils::{current_timestamp, verify_authentication};
+
+// Lets the holder recorded at issuance confirm, once, that it received the credential, so
+// verifiers reading the `CredentialState` know the subject holds the keys it was bound to.
+pub fn acknowledge_credential_handler(
+    ctx: Context<AcknowledgeCredential>,
+    credential_id: String,
+    secp256k1_signature: Option<Secp256k1Signature>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    let credential_state = &ctx.accounts.credential_state;
+    require!(
+        credential_state.is_active_at(now),
+        VerifiableDataRegistryError::CredentialNotActive
+    );
+    require!(
+        credential_state.acknowledged_at.is_none(),
+        VerifiableDataRegistryError::CredentialAlreadyAcknowledged
+    );
+    let holder = credential_state
+        .holder
+        .clone()
+        .ok_or(VerifiableDataRegistryError::HolderCannotAcknowledge)?;
+    let hash = CredentialState::acknowledgement_message_hash(&credential_id);
+
+    match (
+        &holder,
+        &ctx.accounts.verification_method,
+        &ctx.accounts.authentication,
+    ) {
+        // A holder DID signs with one of its authentication keys.
+        (CredentialHolder::Did(did), Some(verification_method), Some(authentication)) => {
+            require!(
+                verification_method.did == *did
+                    && authentication.did == *did
+                    && authentication.relationship == Relationship::Authentication
+                    && authentication.key_id == verification_method.key_id,
+                VerifiableDataRegistryError::Unauthorized
+            );
+            verify_authentication(
+                verification_method,
+                authentication,
+                &ctx.accounts.config,
+                hash.as_ref(),
+                secp256k1_signature.as_ref(),
+                ctx.accounts.authentication_signer.as_ref(),
+                webauthn_assertion
+                    .as_ref()
+                    .zip(ctx.accounts.instructions_sysvar.as_deref()),
+                now,
+            )?;
+        }
+        // A committed secp256k1 key signs the message, revealing the key that was hashed.
+        (CredentialHolder::KeccakCommitment(commitment), None, None) => {
+            let secp256k1_signature =
+                secp256k1_signature.ok_or(VerifiableDataRegistryError::Unauthorized)?;
+            let signed_pubkey = secp256k1_recover::secp256k1_recover(
+                hash.as_ref(),
+                secp256k1_signature.recovery_id,
+                secp256k1_signature.signature.as_ref(),
+            )
+            .map_err(|_| VerifiableDataRegistryError::Unauthorized)?;
+            require!(
+                keccak::hash(signed_pubkey.0.as_ref()).to_bytes() == *commitment,
+                VerifiableDataRegistryError::Unauthorized
+            );
+        }
+        // Poseidon is not available on-chain, such holders prove themselves in presentations.
+        (CredentialHolder::PoseidonCommitment(_), None, None) => {
+            return err!(VerifiableDataRegistryError::HolderCannotAcknowledge)
+        }
+        _ => return err!(VerifiableDataRegistryError::Unauthorized),
+    }
+
+    let credential_state = &mut ctx.accounts.credential_state;
+    credential_state.acknowledged_at = Some(now);
+
+    emit!(CredentialAcknowledged {
+        issuer_did: credential_state.issuer_did.clone(),
+        credential_id,
+        holder,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(credential_id: String)]
+pub struct AcknowledgeCredential<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            credential_state.version == CredentialState::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub credential_state: Account<'info, CredentialState>,
+    // Only required when the holder is a DID, for the authentication key it signs with.
+    pub verification_method: Option<Account<'info, VerificationMethod>>,
+    pub authentication: Option<Account<'info, VerificationRelationship>>,
+    // Only required when the authentication key is a Solana key.
+    pub authentication_signer: Option<Signer<'info>>,
+    // Only required when the authentication key is a passkey.
+    /// CHECK: the instructions sysvar, read for the secp256r1 instruction of the assertion.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+}
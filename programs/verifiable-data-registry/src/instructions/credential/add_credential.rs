@@ -1,43 +1,80 @@
 use anchor_lang::prelude::*;
-use solana_program::{keccak, secp256k1_recover};
+use solana_program::{keccak, sysvar::instructions as sysvar_instructions};
 
 use crate::error::VerifiableDataRegistryError;
+use crate::events::CredentialAdded;
 use crate::state::*;
+use crate::utils::{charge_issuance_fee, current_timestamp, issuable_schema, verify_authentication};
+use crate::validation::{validate_credential_holder, validate_credential_id};
 
+#[allow(clippy::too_many_arguments)]
 pub fn add_credential_handler(
     ctx: Context<AddCredential>,
     did: String,
-    _authentication_id: String,
+    authentication_id: String,
     credential_id: String,
     expire_at: Option<u64>,
-    secp256k1_signature: Secp256k1Signature,
+    holder: Option<CredentialHolder>,
+    field_merkle_root: Option<[u8; 32]>,
+    secp256k1_signature: Option<Secp256k1Signature>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
+    issuer_index_page: u32,
 ) -> Result<()> {
-    msg!("secp: {:?}", secp256k1_signature.signature);
-    require!(
-        ctx.accounts
-            .verification_method
-            .r#type
-            .eq("EcdsaSecp256k1VerificationKey2019"),
-        VerifiableDataRegistryError::NotSupportKeyType
-    );
-    let hash = keccak::hash(credential_id.as_bytes());
-    let signed_pubkey = secp256k1_recover::secp256k1_recover(
-        hash.0.as_ref(),
-        secp256k1_signature.recovery_id,
-        secp256k1_signature.signature.as_ref(),
-    )
-    .unwrap();
-    let (_, verification_pubkey) =
-        multibase::decode(&ctx.accounts.verification_method.public_key_multibase).unwrap();
-    require!(
-        signed_pubkey.0.to_ascii_lowercase() == verification_pubkey.to_ascii_lowercase(),
-        VerifiableDataRegistryError::Unauthorized
+    validate_credential_id(&credential_id)?;
+    if let Some(holder) = &holder {
+        validate_credential_holder(holder)?;
+    }
+    let schema = issuable_schema(ctx.accounts.credential_schema.as_ref())?;
+    let hash = CredentialState::issuance_message_hash(
+        &credential_id,
+        expire_at,
+        holder.as_ref(),
+        schema.as_ref(),
+        field_merkle_root.as_ref(),
     );
+    verify_authentication(
+        &ctx.accounts.verification_method,
+        &ctx.accounts.authentication,
+        &ctx.accounts.config,
+        hash.as_ref(),
+        secp256k1_signature.as_ref(),
+        ctx.accounts.authentication_signer.as_ref(),
+        webauthn_assertion
+            .as_ref()
+            .zip(ctx.accounts.instructions_sysvar.as_deref()),
+        current_timestamp()?,
+    )?;
 
-    ctx.accounts.credential_state.issuer_did = did;
-    ctx.accounts.credential_state.credential_id = credential_id;
+    ctx.accounts.credential_state.version = CredentialState::VERSION;
+    ctx.accounts.credential_state.issuer_did = did.clone();
+    ctx.accounts.credential_state.credential_id = credential_id.clone();
     ctx.accounts.credential_state.status = CredentialStatus::Active;
     ctx.accounts.credential_state.expire_at = expire_at;
+    ctx.accounts.credential_state.payer = ctx.accounts.payer.key();
+    ctx.accounts.credential_state.delegation = None;
+    ctx.accounts.credential_state.holder = holder.clone();
+    ctx.accounts.credential_state.acknowledged_at = None;
+    ctx.accounts.credential_state.schema = schema;
+    ctx.accounts.credential_state.field_merkle_root = field_merkle_root;
+
+    let issuer_index = &mut ctx.accounts.issuer_index;
+    issuer_index.init_or_check(ctx.accounts.did_document.key(), issuer_index_page)?;
+    push_index_entry(&mut issuer_index.entries, ctx.accounts.credential_state.key())?;
+    charge_issuance_fee(
+        &ctx.accounts.config,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit!(CredentialAdded {
+        issuer_did: did,
+        authentication_id,
+        credential_id,
+        expire_at,
+        holder,
+        field_merkle_root,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
 
@@ -45,7 +82,14 @@ pub fn add_credential_handler(
 #[instruction(
     did: String,
     authentication_id: String,
-    credential_id: String
+    credential_id: String,
+    expire_at: Option<u64>,
+    holder: Option<CredentialHolder>,
+    field_merkle_root: Option<[u8; 32]>,
+    recovery_id: Option<u8>,
+    signature: Option<[u8; 64]>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
+    issuer_index_page: u32
 )]
 pub struct AddCredential<'info> {
     #[account(
@@ -53,9 +97,18 @@ pub struct AddCredential<'info> {
         seeds=[keccak::hash(credential_id.as_bytes()).as_ref()],
         bump,
         payer = payer,
-        space = 8 + (4 + did_document.did.len()) + (4 + credential_id.len()) + 1 + (1 + 8)
+        space = CredentialState::space(&did_document.did, &credential_id, holder.as_ref())
     )]
     pub credential_state: Account<'info, CredentialState>,
+    // Must not exist: the id was never used by a credential that has been closed.
+    /// CHECK: only its emptiness is read.
+    #[account(
+        seeds = [CLOSED_CREDENTIAL_SEED, keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            closed_credential.data_is_empty() @ VerifiableDataRegistryError::CredentialIdClosed
+    )]
+    pub closed_credential: UncheckedAccount<'info>,
     #[account(
         seeds=[keccak::hash(did.as_bytes()).as_ref()],
         bump,
@@ -77,6 +130,33 @@ pub struct AddCredential<'info> {
         bump
     )]
     pub authentication: Account<'info, VerificationRelationship>,
+    // Only required when the authentication key is a Solana key.
+    pub authentication_signer: Option<Signer<'info>>,
+    // Only required when the authentication key is a passkey.
+    /// CHECK: the instructions sysvar, read for the secp256r1 instruction of the assertion.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    // Only required when the credential declares its schema.
+    pub credential_schema: Option<Account<'info, CredentialSchema>>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            ISSUER_CREDENTIAL_INDEX_SEED,
+            did_document.key().as_ref(),
+            issuer_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub issuer_index: Account<'info, IssuerCredentialIndex>,
+    #[account(
+        mut,
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::DelegatedCredentialAdded;
+use crate::state::*;
+use crate::utils::{charge_issuance_fee, current_timestamp, issuable_schema};
+use crate::validation::{validate_credential_holder, validate_credential_id};
+
+pub fn add_delegated_credential_handler(
+    ctx: Context<AddDelegatedCredential>,
+    did: String,
+    credential_id: String,
+    expire_at: Option<u64>,
+    holder: Option<CredentialHolder>,
+    field_merkle_root: Option<[u8; 32]>,
+    issuer_index_page: u32,
+) -> Result<()> {
+    validate_credential_id(&credential_id)?;
+    if let Some(holder) = &holder {
+        validate_credential_holder(holder)?;
+    }
+
+    let delegation = &mut ctx.accounts.delegation;
+    require!(
+        !delegation.is_expired_at(current_timestamp()?),
+        VerifiableDataRegistryError::DelegationExpired
+    );
+    require!(
+        ctx.accounts.credential_schema.schema_id.starts_with(&delegation.schema_id_prefix),
+        VerifiableDataRegistryError::CredentialNotInDelegationScope
+    );
+    require!(
+        delegation.covers_expiry(expire_at),
+        VerifiableDataRegistryError::CredentialOutlivesDelegation
+    );
+    require!(
+        !delegation.is_exhausted(),
+        VerifiableDataRegistryError::DelegationLimitReached
+    );
+    delegation.issued_credentials += 1;
+    let schema = issuable_schema(Some(&ctx.accounts.credential_schema))?;
+
+    ctx.accounts.credential_state.version = CredentialState::VERSION;
+    ctx.accounts.credential_state.issuer_did = did.clone();
+    ctx.accounts.credential_state.credential_id = credential_id.clone();
+    ctx.accounts.credential_state.status = CredentialStatus::Active;
+    ctx.accounts.credential_state.expire_at = expire_at;
+    ctx.accounts.credential_state.payer = ctx.accounts.payer.key();
+    ctx.accounts.credential_state.delegation = Some(ctx.accounts.delegation.key());
+    ctx.accounts.credential_state.holder = holder.clone();
+    ctx.accounts.credential_state.acknowledged_at = None;
+    ctx.accounts.credential_state.schema = schema;
+    ctx.accounts.credential_state.field_merkle_root = field_merkle_root;
+
+    let issuer_index = &mut ctx.accounts.issuer_index;
+    issuer_index.init_or_check(ctx.accounts.did_document.key(), issuer_index_page)?;
+    push_index_entry(&mut issuer_index.entries, ctx.accounts.credential_state.key())?;
+    charge_issuance_fee(
+        &ctx.accounts.config,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit!(DelegatedCredentialAdded {
+        issuer_did: did,
+        delegation: ctx.accounts.delegation.key(),
+        credential_id,
+        expire_at,
+        holder,
+        field_merkle_root,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(
+    did: String,
+    credential_id: String,
+    expire_at: Option<u64>,
+    holder: Option<CredentialHolder>,
+    field_merkle_root: Option<[u8; 32]>,
+    issuer_index_page: u32
+)]
+pub struct AddDelegatedCredential<'info> {
+    #[account(
+        init,
+        seeds=[keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        payer = payer,
+        space = CredentialState::space(&did_document.did, &credential_id, holder.as_ref())
+    )]
+    pub credential_state: Account<'info, CredentialState>,
+    // Must not exist: the id was never used by a credential that has been closed.
+    /// CHECK: only its emptiness is read.
+    #[account(
+        seeds = [CLOSED_CREDENTIAL_SEED, keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            closed_credential.data_is_empty() @ VerifiableDataRegistryError::CredentialIdClosed
+    )]
+    pub closed_credential: UncheckedAccount<'info>,
+    #[account(
+        seeds=[keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        mut,
+        seeds = [
+            DELEGATION_SEED,
+            did_document.key().as_ref(),
+            delegate.key().as_ref(),
+            delegation.id.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint =
+            delegation.version == Delegation::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub delegation: Account<'info, Delegation>,
+    // The delegation scopes the schemas the delegate may issue against.
+    pub credential_schema: Account<'info, CredentialSchema>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            ISSUER_CREDENTIAL_INDEX_SEED,
+            did_document.key().as_ref(),
+            issuer_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub issuer_index: Account<'info, IssuerCredentialIndex>,
+    #[account(
+        mut,
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub delegate: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, state::*, utils::current_timestamp};
+
+#[derive(Accounts)]
+#[instruction(credential_id: String)]
+pub struct CheckCredential<'info> {
+    #[account(
+        seeds = [keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            credential_state.version == CredentialState::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub credential_state: Account<'info, CredentialState>,
+}
+
+// Meant to be called by other programs before accepting a field-check proof: succeeds only
+// when `credential_id` is active and its issuer signed `field_merkle_root` at issuance.
+// Read-only, so not gated by the registry pause.
+pub fn check_credential_handler(
+    ctx: Context<CheckCredential>,
+    _credential_id: String,
+    field_merkle_root: [u8; 32],
+) -> Result<()> {
+    let credential_state = &ctx.accounts.credential_state;
+    require!(
+        credential_state.is_active_at(current_timestamp()?),
+        VerifiableDataRegistryError::CredentialNotActive
+    );
+    require!(
+        credential_state.field_merkle_root == Some(field_merkle_root),
+        VerifiableDataRegistryError::FieldMerkleRootMismatch
+    );
+    Ok(())
+}
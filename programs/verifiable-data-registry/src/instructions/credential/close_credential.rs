@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use solana_program::{keccak, sysvar::instructions as sysvar_instructions};
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::CredentialClosed;
+use crate::state::*;
+use crate::utils::{current_timestamp, verify_authentication};
+
+pub fn close_credential_handler(
+    ctx: Context<CloseCredential>,
+    credential_id: String,
+    _issuer_index_page: u32,
+    secp256k1_signature: Option<Secp256k1Signature>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    let credential_state = &ctx.accounts.credential_state;
+
+    match (
+        &ctx.accounts.verification_method,
+        &ctx.accounts.authentication,
+    ) {
+        // The issuer may close its credentials at any time, signing the close message or,
+        // with a Solana key, as the closer.
+        (Some(verification_method), Some(authentication)) => {
+            require!(
+                verification_method.did == credential_state.issuer_did
+                    && authentication.did == credential_state.issuer_did
+                    && authentication.relationship == Relationship::Authentication
+                    && authentication.key_id == verification_method.key_id,
+                VerifiableDataRegistryError::Unauthorized
+            );
+            let hash = CredentialState::close_message_hash(&credential_id);
+            verify_authentication(
+                verification_method,
+                authentication,
+                &ctx.accounts.config,
+                hash.as_ref(),
+                secp256k1_signature.as_ref(),
+                Some(&ctx.accounts.closer),
+                webauthn_assertion
+                    .as_ref()
+                    .zip(ctx.accounts.instructions_sysvar.as_deref()),
+                now,
+            )?;
+        }
+        // Anyone may close a credential once it has been expired for the grace period.
+        (None, None) => {
+            let expire_at = credential_state
+                .expire_at
+                .ok_or(VerifiableDataRegistryError::CredentialNotClosable)?;
+            require!(
+                now >= expire_at.saturating_add(CREDENTIAL_CLOSE_GRACE_PERIOD),
+                VerifiableDataRegistryError::CredentialNotClosable
+            );
+        }
+        _ => return err!(VerifiableDataRegistryError::Unauthorized),
+    }
+
+    ctx.accounts.closed_credential.version = ClosedCredential::VERSION;
+    ctx.accounts.closed_credential.credential_id = credential_id.clone();
+    ctx.accounts.closed_credential.closed_at = now;
+
+    remove_index_entry(
+        &mut ctx.accounts.issuer_index.entries,
+        &ctx.accounts.credential_state.key(),
+    );
+
+    emit!(CredentialClosed {
+        issuer_did: ctx.accounts.credential_state.issuer_did.clone(),
+        credential_id,
+        closed_by: ctx.accounts.closer.key(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(credential_id: String, issuer_index_page: u32)]
+pub struct CloseCredential<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        close = payer,
+        constraint =
+            credential_state.version == CredentialState::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub credential_state: Account<'info, CredentialState>,
+    #[account(
+        init,
+        seeds = [CLOSED_CREDENTIAL_SEED, keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        // The closer funds the tombstone, `payer` gets the whole rent of the credential back.
+        payer = closer,
+        space = ClosedCredential::space(&credential_id)
+    )]
+    pub closed_credential: Account<'info, ClosedCredential>,
+    #[account(
+        seeds = [keccak::hash(credential_state.issuer_did.as_bytes()).as_ref()],
+        bump
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        mut,
+        seeds = [
+            ISSUER_CREDENTIAL_INDEX_SEED,
+            did_document.key().as_ref(),
+            issuer_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint =
+            issuer_index.version == IssuerCredentialIndex::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub issuer_index: Account<'info, IssuerCredentialIndex>,
+    // Only required when the issuer closes the credential.
+    pub verification_method: Option<Account<'info, VerificationMethod>>,
+    pub authentication: Option<Account<'info, VerificationRelationship>>,
+    // Only required when the issuer closes the credential with a passkey.
+    /// CHECK: the instructions sysvar, read for the secp256r1 instruction of the assertion.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    /// CHECK: checked against the rent recipient of `credential_state`, receives the rent.
+    #[account(
+        mut,
+        address = credential_state.rent_recipient(&did_document.controller)
+            @ VerifiableDataRegistryError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub closer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -3,3 +3,18 @@ pub use add_credential::*;
 
 pub mod revoke_credential;
 pub use revoke_credential::*;
+
+pub mod close_credential;
+pub use close_credential::*;
+
+pub mod add_delegated_credential;
+pub use add_delegated_credential::*;
+
+pub mod revoke_delegated_credential;
+pub use revoke_delegated_credential::*;
+
+pub mod acknowledge_credential;
+pub use acknowledge_credential::*;
+
+pub mod check_credential;
+pub use check_credential::*;
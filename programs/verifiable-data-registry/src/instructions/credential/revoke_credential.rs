@@ -1,38 +1,41 @@
 use anchor_lang::prelude::*;
-use solana_program::{keccak, secp256k1_recover};
+use solana_program::{keccak, sysvar::instructions as sysvar_instructions};
 
 use crate::error::VerifiableDataRegistryError;
+use crate::events::CredentialRevoked;
 use crate::state::*;
+use crate::utils::{current_timestamp, verify_authentication};
 
 pub fn revoke_credential_handler(
     ctx: Context<RevokeCredential>,
-    _did: String,
-    _authentication_id: String,
+    did: String,
+    authentication_id: String,
     credential_id: String,
-    secp256k1_signature: Secp256k1Signature,
+    secp256k1_signature: Option<Secp256k1Signature>,
+    webauthn_assertion: Option<WebAuthnAssertion>,
 ) -> Result<()> {
-    require!(
-        ctx.accounts
-            .verification_method
-            .r#type
-            .eq("EcdsaSecp256k1VerificationKey2019"),
-        VerifiableDataRegistryError::NotSupportKeyType
-    );
-    let hash = keccak::hash([credential_id.as_bytes(), b"REVOKE"].concat().as_ref());
-    let signed_pubkey = secp256k1_recover::secp256k1_recover(
-        hash.0.as_ref(),
-        secp256k1_signature.recovery_id,
-        secp256k1_signature.signature.as_ref(),
-    )
-    .unwrap();
-    let (_, verification_pubkey) =
-        multibase::decode(&ctx.accounts.verification_method.public_key_multibase).unwrap();
-    require!(
-        signed_pubkey.0.to_ascii_lowercase() == verification_pubkey.to_ascii_lowercase(),
-        VerifiableDataRegistryError::Unauthorized
-    );
+    let hash = CredentialState::revocation_message_hash(&did, &credential_id);
+    verify_authentication(
+        &ctx.accounts.verification_method,
+        &ctx.accounts.authentication,
+        &ctx.accounts.config,
+        hash.as_ref(),
+        secp256k1_signature.as_ref(),
+        ctx.accounts.authentication_signer.as_ref(),
+        webauthn_assertion
+            .as_ref()
+            .zip(ctx.accounts.instructions_sysvar.as_deref()),
+        current_timestamp()?,
+    )?;
 
     ctx.accounts.credential_state.status = CredentialStatus::Revoked;
+
+    emit!(CredentialRevoked {
+        issuer_did: did,
+        authentication_id,
+        credential_id,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
 
@@ -47,7 +50,13 @@ pub struct RevokeCredential<'info> {
         mut,
         seeds = [keccak::hash(credential_id.as_bytes()).as_ref()],
         bump,
-        constraint = credential_state.status != CredentialStatus::Revoked
+        constraint = credential_state.status != CredentialStatus::Revoked,
+        constraint =
+            credential_state.version == CredentialState::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+        constraint =
+            credential_state.issuer_did == did
+            @ VerifiableDataRegistryError::Unauthorized
     )]
     pub credential_state: Account<'info, CredentialState>,
     #[account(
@@ -74,6 +83,18 @@ pub struct RevokeCredential<'info> {
         bump
     )]
     pub authentication: Account<'info, VerificationRelationship>,
+    // Only required when the authentication key is a Solana key.
+    pub authentication_signer: Option<Signer<'info>>,
+    // Only required when the authentication key is a passkey.
+    /// CHECK: the instructions sysvar, read for the secp256r1 instruction of the assertion.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
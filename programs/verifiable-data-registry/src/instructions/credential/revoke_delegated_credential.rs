@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::DelegatedCredentialRevoked;
+use crate::state::*;
+use crate::utils::current_timestamp;
+
+pub fn revoke_delegated_credential_handler(
+    ctx: Context<RevokeDelegatedCredential>,
+    did: String,
+    credential_id: String,
+) -> Result<()> {
+    let delegation = &ctx.accounts.delegation;
+    require!(
+        delegation.can_revoke,
+        VerifiableDataRegistryError::DelegationCannotRevoke
+    );
+    require!(
+        !delegation.is_expired_at(current_timestamp()?),
+        VerifiableDataRegistryError::DelegationExpired
+    );
+
+    ctx.accounts.credential_state.status = CredentialStatus::Revoked;
+
+    emit!(DelegatedCredentialRevoked {
+        issuer_did: did,
+        delegation: ctx.accounts.delegation.key(),
+        credential_id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(did: String, credential_id: String)]
+pub struct RevokeDelegatedCredential<'info> {
+    // A delegate may only revoke what was issued under its own delegation.
+    #[account(
+        mut,
+        seeds = [keccak::hash(credential_id.as_bytes()).as_ref()],
+        bump,
+        constraint = credential_state.status != CredentialStatus::Revoked,
+        constraint =
+            credential_state.version == CredentialState::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+        constraint =
+            credential_state.delegation == Some(delegation.key())
+            @ VerifiableDataRegistryError::Unauthorized
+    )]
+    pub credential_state: Account<'info, CredentialState>,
+    #[account(
+        seeds=[keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [
+            DELEGATION_SEED,
+            did_document.key().as_ref(),
+            delegate.key().as_ref(),
+            delegation.id.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint =
+            delegation.version == Delegation::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub delegate: Signer<'info>,
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::DelegationCreated, state::*,
+    validation::validate_schema_id_prefix,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, delegate: Pubkey, id: u64, schema_id_prefix: String)]
+pub struct CreateDelegation<'info> {
+    #[account(
+        init,
+        seeds = [
+            DELEGATION_SEED,
+            did_document.key().as_ref(),
+            delegate.as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space =
+            8 + 1 + (4 + did_document.did.len()) + 32 + 8 + (4 + schema_id_prefix.len())
+            + (1 + 4) + 4 + 1 + (1 + 8)
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_delegation_handler(
+    ctx: Context<CreateDelegation>,
+    did: String,
+    delegate: Pubkey,
+    id: u64,
+    schema_id_prefix: String,
+    max_credentials: Option<u32>,
+    can_revoke: bool,
+    expire_at: Option<u64>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    validate_schema_id_prefix(&schema_id_prefix)?;
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.version = Delegation::VERSION;
+    delegation.did = did.clone();
+    delegation.delegate = delegate;
+    delegation.id = id;
+    delegation.schema_id_prefix = schema_id_prefix.clone();
+    delegation.max_credentials = max_credentials;
+    delegation.issued_credentials = 0;
+    delegation.can_revoke = can_revoke;
+    delegation.expire_at = expire_at;
+
+    emit!(DelegationCreated {
+        did,
+        delegate,
+        id,
+        schema_id_prefix,
+        max_credentials,
+        can_revoke,
+        expire_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
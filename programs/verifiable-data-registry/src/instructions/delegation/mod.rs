@@ -0,0 +1,5 @@
+pub mod create_delegation;
+pub use create_delegation::*;
+
+pub mod revoke_delegation;
+pub use revoke_delegation::*;
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::DelegationRevoked, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, delegate: Pubkey, id: u64)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            DELEGATION_SEED,
+            did_document.key().as_ref(),
+            delegate.as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump,
+        close = controller
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+}
+
+// Closing the delegation stops further issuance; credentials already issued stay valid.
+pub fn revoke_delegation_handler(
+    _ctx: Context<RevokeDelegation>,
+    did: String,
+    delegate: Pubkey,
+    id: u64,
+) -> Result<()> {
+    emit!(DelegationRevoked {
+        did,
+        delegate,
+        id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use solana_program::keccak;
 
-use crate::{state::*, error::VerifiableDataRegistryError};
+use crate::{state::*, error::VerifiableDataRegistryError, events::VerificationMethodAdded, utils::{p256_public_key, record_did_change, solana_public_key, x25519_public_key, P256_KEY_TYPE, SOLANA_KEY_TYPES, X25519_KEY_TYPE}, validation::*};
 
 #[derive(Accounts)]
 #[instruction(did: String, key_id: String, r#type: String, public_key_multibase: String)]
@@ -12,8 +12,9 @@ pub struct AddVerificationMethod<'info> {
         bump,
         payer = controller,
         space = 
-            8 + 32 + 4 + did_document.did.len() + 4 + key_id.len() 
+            8 + 1 + 32 + 4 + did_document.did.len() + 4 + key_id.len() 
             + 4 + r#type.len() + 4 + public_key_multibase.len()
+            + (1 + 8) + (1 + 8)
     )]
     pub verification_method: Account<'info, VerificationMethod>,
     #[account(
@@ -22,13 +23,31 @@ pub struct AddVerificationMethod<'info> {
         constraint = 
             did_document.controller == controller.key()
             && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
     )]
     pub did_document: Account<'info, DidDocument>,
+    #[account(
+        init_if_needed,
+        seeds = [DID_CHANGE_LOG_SEED, did_document.key().as_ref()],
+        bump,
+        payer = controller,
+        space = DID_CHANGE_LOG_SPACE
+    )]
+    pub change_log: Account<'info, DidChangeLog>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
     #[account(mut)]
     pub controller: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn add_verification_method_handler(
     ctx: Context<AddVerificationMethod>,
     did: String,
@@ -36,14 +55,88 @@ pub fn add_verification_method_handler(
     r#type: String,
     public_key_multibase: String,
     controller: Pubkey,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    apply_add_verification_method(
+        ctx.accounts,
+        did,
+        key_id,
+        r#type,
+        public_key_multibase,
+        controller,
+        valid_from,
+        valid_until,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_add_verification_method(
+    accounts: &mut AddVerificationMethod,
+    did: String,
+    key_id: String,
+    r#type: String,
+    public_key_multibase: String,
+    controller: Pubkey,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
 ) -> Result<()> {
+    validate_key_id(&did, &key_id)?;
+    validate_key_type(&r#type)?;
+    require!(
+        accounts.config.is_key_type_allowed(&r#type),
+        VerifiableDataRegistryError::NotSupportKeyType
+    );
+    validate_public_key_multibase(&public_key_multibase)?;
     let decode_multibase_res = multibase::decode(&public_key_multibase);
     require!(decode_multibase_res.is_ok(), VerifiableDataRegistryError::InvalidMultibase);
-    
-    ctx.accounts.verification_method.controller = controller; // controller of key, not did
-    ctx.accounts.verification_method.did = did;
-    ctx.accounts.verification_method.key_id = key_id;
-    ctx.accounts.verification_method.r#type = r#type;
-    ctx.accounts.verification_method.public_key_multibase = public_key_multibase;
+    require!(
+        !SOLANA_KEY_TYPES.contains(&r#type.as_str())
+            || solana_public_key(&public_key_multibase).is_some(),
+        VerifiableDataRegistryError::InvalidSolanaPublicKey
+    );
+    require!(
+        r#type != X25519_KEY_TYPE || x25519_public_key(&public_key_multibase).is_some(),
+        VerifiableDataRegistryError::InvalidX25519PublicKey
+    );
+    require!(
+        r#type != P256_KEY_TYPE || p256_public_key(&public_key_multibase).is_some(),
+        VerifiableDataRegistryError::InvalidP256PublicKey
+    );
+    require!(
+        is_valid_validity_window(valid_from, valid_until),
+        VerifiableDataRegistryError::InvalidValidityWindow
+    );
+
+    accounts.verification_method.version = VerificationMethod::VERSION;
+    accounts.verification_method.controller = controller; // controller of key, not did
+    accounts.verification_method.did = did.clone();
+    accounts.verification_method.key_id = key_id.clone();
+    accounts.verification_method.r#type = r#type.clone();
+    accounts.verification_method.public_key_multibase = public_key_multibase;
+    accounts.verification_method.valid_from = valid_from;
+    accounts.verification_method.valid_until = valid_until;
+
+    record_did_change(
+        &mut accounts.change_log,
+        accounts.did_document.key(),
+        DidChangeOp::VerificationMethodAdded,
+        key_id.clone(),
+        accounts.verification_method.public_key_multibase.as_bytes(),
+    )?;
+
+    emit!(VerificationMethodAdded {
+        did,
+        key_id,
+        r#type,
+        controller,
+        valid_from,
+        valid_until,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
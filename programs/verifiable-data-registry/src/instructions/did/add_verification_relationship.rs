@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use solana_program::keccak;
 
-use crate::state::*;
+use crate::{state::*, error::VerifiableDataRegistryError, events::RelationshipAdded, utils::{record_did_change, X25519_KEY_TYPE}, validation::validate_key_id};
 
 #[derive(Accounts)]
 #[instruction(did: String, relationship: Relationship, key_id: String, )]
@@ -15,7 +15,9 @@ pub struct AddVerificationRelationship<'info> {
         ].concat().as_slice()).as_ref()],
         bump,
         payer = controller,
-        space = 8 + (4 + verification_method.did.len()) + 1 + (4 + verification_method.key_id.len())
+        space = 
+            8 + 1 + (4 + verification_method.did.len()) + 1 + (4 + verification_method.key_id.len())
+            + (1 + 8) + (1 + 8)
     )]
     pub verification_relationship: Account<'info, VerificationRelationship>,
     #[account(
@@ -23,15 +25,35 @@ pub struct AddVerificationRelationship<'info> {
         bump,
         constraint = 
             did_document.controller == controller.key()
-            && did_document.did == did
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
     )]
     pub did_document: Account<'info, DidDocument>,
+    #[account(
+        init_if_needed,
+        seeds = [DID_CHANGE_LOG_SEED, did_document.key().as_ref()],
+        bump,
+        payer = controller,
+        space = DID_CHANGE_LOG_SPACE
+    )]
+    pub change_log: Account<'info, DidChangeLog>,
     #[account(
         seeds=[keccak::hash([did.as_bytes(), key_id.as_bytes()].concat().as_slice()).as_ref()], 
         bump,
-        constraint = verification_method.key_id == key_id
+        constraint = verification_method.key_id == key_id,
+        constraint =
+            verification_method.version == VerificationMethod::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
     )]
     pub verification_method: Account<'info, VerificationMethod>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
     #[account(mut)]
     pub controller: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -42,10 +64,68 @@ pub fn add_verification_relationship_handler(
     did: String, 
     relationship: Relationship,
     key_id: String, 
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
 ) -> Result<()> {
-    ctx.accounts.verification_relationship.did = did;
-    ctx.accounts.verification_relationship.relationship = relationship;
-    ctx.accounts.verification_relationship.key_id = key_id;
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    apply_add_verification_relationship(
+        ctx.accounts,
+        did,
+        relationship,
+        key_id,
+        valid_from,
+        valid_until,
+    )
+}
+
+pub(crate) fn apply_add_verification_relationship(
+    accounts: &mut AddVerificationRelationship,
+    did: String,
+    relationship: Relationship,
+    key_id: String,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+) -> Result<()> {
+    validate_key_id(&did, &key_id)?;
+    require!(
+        is_valid_validity_window(valid_from, valid_until),
+        VerifiableDataRegistryError::InvalidValidityWindow
+    );
+    // X25519 keys cannot sign and no other key type can agree on a secret.
+    require!(
+        (accounts.verification_method.r#type == X25519_KEY_TYPE)
+            == (relationship == Relationship::KeyAgreement),
+        VerifiableDataRegistryError::RelationshipNotSupportedByKeyType
+    );
+
+    accounts.verification_relationship.version = VerificationRelationship::VERSION;
+    accounts.verification_relationship.did = did.clone();
+    accounts.verification_relationship.relationship = relationship.clone();
+    accounts.verification_relationship.key_id = key_id.clone();
+    accounts.verification_relationship.valid_from = valid_from;
+    accounts.verification_relationship.valid_until = valid_until;
+
+    record_did_change(
+        &mut accounts.change_log,
+        accounts.did_document.key(),
+        DidChangeOp::VerificationRelationshipAdded {
+            relationship: relationship.clone(),
+        },
+        key_id.clone(),
+        accounts.verification_method.public_key_multibase.as_bytes(),
+    )?;
+
+    emit!(RelationshipAdded {
+        did,
+        relationship,
+        key_id,
+        valid_from,
+        valid_until,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
 
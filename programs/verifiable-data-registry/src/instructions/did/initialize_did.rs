@@ -1,25 +1,81 @@
 use anchor_lang::prelude::*;
 use solana_program::keccak;
 
-use crate::state::*;
+use crate::{error::VerifiableDataRegistryError, events::DidInitialized, state::*, utils::record_did_change, validation::validate_did};
 
 #[derive(Accounts)]
-#[instruction(did: String)]
+#[instruction(did: String, controller_index_page: u32)]
 pub struct InitializeDID<'info> {
     #[account(
         init,
         seeds = [keccak::hash(did.as_bytes()).as_ref()],
         bump,
         payer = controller,
-        space = 8 + 32 + 4 + did.len())]
+        space = DidDocument::space(&did, 0, &[]))]
     pub did_document: Account<'info, DidDocument>,
+    #[account(
+        init,
+        seeds = [DID_CHANGE_LOG_SEED, did_document.key().as_ref()],
+        bump,
+        payer = controller,
+        space = DID_CHANGE_LOG_SPACE
+    )]
+    pub change_log: Account<'info, DidChangeLog>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            CONTROLLER_DID_INDEX_SEED,
+            controller.key().as_ref(),
+            controller_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub controller_index: Account<'info, ControllerDidIndex>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
     #[account(mut)]
     pub controller: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_did_handler(ctx: Context<InitializeDID>, did: String) -> Result<()> {
+pub fn initialize_did_handler(
+    ctx: Context<InitializeDID>,
+    did: String,
+    controller_index_page: u32,
+) -> Result<()> {
+    validate_did(&did)?;
+
+    ctx.accounts.did_document.version = DidDocument::VERSION;
     ctx.accounts.did_document.controller = ctx.accounts.controller.key();
-    ctx.accounts.did_document.did = did;
+    ctx.accounts.did_document.did = did.clone();
+    ctx.accounts.did_document.guardians = Vec::new();
+    ctx.accounts.did_document.guardian_threshold = 0;
+    ctx.accounts.did_document.recovery_delay = 0;
+    ctx.accounts.did_document.operation_delay = 0;
+    ctx.accounts.did_document.also_known_as = Vec::new();
+
+    let controller_index = &mut ctx.accounts.controller_index;
+    controller_index.init_or_check(ctx.accounts.controller.key(), controller_index_page)?;
+    push_index_entry(&mut controller_index.entries, ctx.accounts.did_document.key())?;
+
+    record_did_change(
+        &mut ctx.accounts.change_log,
+        ctx.accounts.did_document.key(),
+        DidChangeOp::Created,
+        String::new(),
+        ctx.accounts.controller.key().as_ref(),
+    )?;
+
+    emit!(DidInitialized {
+        did,
+        controller: ctx.accounts.controller.key(),
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
@@ -6,3 +6,9 @@ pub use add_verification_method::*;
 
 mod add_verification_relationship;
 pub use add_verification_relationship::*;
+
+mod transfer_did_controller;
+pub use transfer_did_controller::*;
+
+mod set_also_known_as;
+pub use set_also_known_as::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::AlsoKnownAsUpdated, state::*,
+    validation::validate_also_known_as,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, also_known_as: Vec<String>)]
+pub struct SetAlsoKnownAs<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+        realloc = DidDocument::space(&did, did_document.guardians.len(), &also_known_as),
+        realloc::payer = controller,
+        realloc::zero = false
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Replaces the whole list; an empty list removes every alias.
+pub fn set_also_known_as_handler(
+    ctx: Context<SetAlsoKnownAs>,
+    did: String,
+    also_known_as: Vec<String>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    validate_also_known_as(&did, &also_known_as)?;
+    ctx.accounts.did_document.also_known_as = also_known_as.clone();
+
+    emit!(AlsoKnownAsUpdated {
+        did,
+        also_known_as,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
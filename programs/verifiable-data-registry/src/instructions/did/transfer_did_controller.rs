@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::DidControllerTransferred, state::*, utils::record_did_change};
+
+#[derive(Accounts)]
+#[instruction(did: String, new_controller: Pubkey, from_index_page: u32, to_index_page: u32)]
+pub struct TransferDidController<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        init_if_needed,
+        seeds = [DID_CHANGE_LOG_SEED, did_document.key().as_ref()],
+        bump,
+        payer = controller,
+        space = DID_CHANGE_LOG_SPACE
+    )]
+    pub change_log: Account<'info, DidChangeLog>,
+    // Created empty for controllers of DIDs from before controller indexes were kept.
+    #[account(
+        init_if_needed,
+        seeds = [
+            CONTROLLER_DID_INDEX_SEED,
+            controller.key().as_ref(),
+            from_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub from_controller_index: Account<'info, ControllerDidIndex>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            CONTROLLER_DID_INDEX_SEED,
+            new_controller.as_ref(),
+            to_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub to_controller_index: Account<'info, ControllerDidIndex>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn transfer_did_controller_handler(
+    ctx: Context<TransferDidController>,
+    did: String,
+    new_controller: Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    apply_transfer_did_controller(ctx.accounts, did, new_controller, from_index_page, to_index_page)
+}
+
+pub(crate) fn apply_transfer_did_controller(
+    accounts: &mut TransferDidController,
+    did: String,
+    new_controller: Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> Result<()> {
+    require_keys_neq!(
+        new_controller,
+        accounts.controller.key(),
+        VerifiableDataRegistryError::SameController
+    );
+
+    let did_document_key = accounts.did_document.key();
+    let previous_controller = accounts.did_document.controller;
+
+    let from_controller_index = &mut accounts.from_controller_index;
+    from_controller_index.init_or_check(previous_controller, from_index_page)?;
+    remove_index_entry(&mut from_controller_index.entries, &did_document_key);
+    let to_controller_index = &mut accounts.to_controller_index;
+    to_controller_index.init_or_check(new_controller, to_index_page)?;
+    push_index_entry(&mut to_controller_index.entries, did_document_key)?;
+
+    accounts.did_document.controller = new_controller;
+    record_did_change(
+        &mut accounts.change_log,
+        did_document_key,
+        DidChangeOp::ControllerTransferred,
+        String::new(),
+        new_controller.as_ref(),
+    )?;
+
+    emit!(DidControllerTransferred {
+        did,
+        previous_controller,
+        new_controller,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
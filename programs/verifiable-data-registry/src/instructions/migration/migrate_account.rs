@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::events::AccountMigrated;
+use crate::migration::upgrade_account_data;
+use crate::state::*;
+
+// Anyone may migrate an account: the upgrade only rewrites the layout, never the contents.
+// The payer funds the larger layout and is not recorded on the account.
+pub fn migrate_account_handler(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let upgrade = upgrade_account_data(
+        ctx.program_id,
+        account.key,
+        &account.try_borrow_data()?,
+    )?
+    .ok_or(VerifiableDataRegistryError::AccountAlreadyMigrated)?;
+
+    let rent = Rent::get()?.minimum_balance(upgrade.data.len());
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.realloc(upgrade.data.len(), false)?;
+    account
+        .try_borrow_mut_data()?
+        .copy_from_slice(&upgrade.data);
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        from_version: upgrade.from_version,
+        to_version: upgrade.to_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: any registry account; its layout is checked against its address by
+    /// `upgrade_account_data`.
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
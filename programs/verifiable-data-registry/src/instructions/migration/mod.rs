@@ -0,0 +1,2 @@
+mod migrate_account;
+pub use migrate_account::*;
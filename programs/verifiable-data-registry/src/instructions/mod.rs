@@ -3,3 +3,27 @@ pub use did::*;
 
 mod credential;
 pub use credential::*;
+
+mod config;
+pub use config::*;
+
+mod migration;
+pub use migration::*;
+
+mod delegation;
+pub use delegation::*;
+
+mod recovery;
+pub use recovery::*;
+
+mod operation;
+pub use operation::*;
+
+mod auth;
+pub use auth::*;
+
+mod schema;
+pub use schema::*;
+
+mod accreditation;
+pub use accreditation::*;
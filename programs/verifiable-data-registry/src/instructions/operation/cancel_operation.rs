@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{events::OperationCancelled, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, id: u64)]
+pub struct CancelOperation<'info> {
+    #[account(
+        mut,
+        seeds = [PENDING_OPERATION_SEED, did_document.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        close = controller
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+}
+
+// Not gated by the registry pause, like `cancel_recovery`.
+pub fn cancel_operation_handler(
+    _ctx: Context<CancelOperation>,
+    did: String,
+    id: u64,
+) -> Result<()> {
+    emit!(OperationCancelled {
+        did,
+        id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::OperationExecuted, instructions::*, state::*,
+    utils::current_timestamp,
+};
+
+// Each `Execute*` wraps the accounts of the instruction it executes; the instruction
+// arguments are the wrapped ones followed by the id of the pending operation.
+
+#[derive(Accounts)]
+#[instruction(did: String, new_controller: Pubkey, from_index_page: u32, to_index_page: u32, id: u64)]
+pub struct ExecuteTransferDidController<'info> {
+    pub operation: TransferDidController<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PENDING_OPERATION_SEED,
+            operation.did_document.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    did: String,
+    key_id: String,
+    r#type: String,
+    public_key_multibase: String,
+    controller: Pubkey,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    id: u64
+)]
+pub struct ExecuteAddVerificationMethod<'info> {
+    pub operation: AddVerificationMethod<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PENDING_OPERATION_SEED,
+            operation.did_document.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    did: String,
+    relationship: Relationship,
+    key_id: String,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    id: u64
+)]
+pub struct ExecuteAddVerificationRelationship<'info> {
+    pub operation: AddVerificationRelationship<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PENDING_OPERATION_SEED,
+            operation.did_document.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+}
+
+#[derive(Accounts)]
+#[instruction(did: String, operation_delay: u64, id: u64)]
+pub struct ExecuteSetOperationDelay<'info> {
+    pub operation: SetOperationDelay<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PENDING_OPERATION_SEED,
+            operation.did_document.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+}
+
+pub fn execute_transfer_did_controller_handler(
+    ctx: Context<ExecuteTransferDidController>,
+    did: String,
+    new_controller: Pubkey,
+    from_index_page: u32,
+    to_index_page: u32,
+    id: u64,
+) -> Result<()> {
+    check_pending_operation(
+        &ctx.accounts.pending_operation,
+        &DidOperation::TransferController { new_controller },
+    )?;
+    apply_transfer_did_controller(
+        &mut ctx.accounts.operation,
+        did.clone(),
+        new_controller,
+        from_index_page,
+        to_index_page,
+    )?;
+    close_pending_operation(
+        &ctx.accounts.pending_operation,
+        &ctx.accounts.operation.controller,
+        did,
+        id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_add_verification_method_handler(
+    ctx: Context<ExecuteAddVerificationMethod>,
+    did: String,
+    key_id: String,
+    r#type: String,
+    public_key_multibase: String,
+    controller: Pubkey,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    id: u64,
+) -> Result<()> {
+    check_pending_operation(
+        &ctx.accounts.pending_operation,
+        &DidOperation::AddVerificationMethod {
+            key_id: key_id.clone(),
+            r#type: r#type.clone(),
+            public_key_multibase: public_key_multibase.clone(),
+            controller,
+            valid_from,
+            valid_until,
+        },
+    )?;
+    apply_add_verification_method(
+        &mut ctx.accounts.operation,
+        did.clone(),
+        key_id,
+        r#type,
+        public_key_multibase,
+        controller,
+        valid_from,
+        valid_until,
+    )?;
+    close_pending_operation(
+        &ctx.accounts.pending_operation,
+        &ctx.accounts.operation.controller,
+        did,
+        id,
+    )
+}
+
+pub fn execute_add_verification_relationship_handler(
+    ctx: Context<ExecuteAddVerificationRelationship>,
+    did: String,
+    relationship: Relationship,
+    key_id: String,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    id: u64,
+) -> Result<()> {
+    check_pending_operation(
+        &ctx.accounts.pending_operation,
+        &DidOperation::AddVerificationRelationship {
+            relationship: relationship.clone(),
+            key_id: key_id.clone(),
+            valid_from,
+            valid_until,
+        },
+    )?;
+    apply_add_verification_relationship(
+        &mut ctx.accounts.operation,
+        did.clone(),
+        relationship,
+        key_id,
+        valid_from,
+        valid_until,
+    )?;
+    close_pending_operation(
+        &ctx.accounts.pending_operation,
+        &ctx.accounts.operation.controller,
+        did,
+        id,
+    )
+}
+
+pub fn execute_set_operation_delay_handler(
+    ctx: Context<ExecuteSetOperationDelay>,
+    did: String,
+    operation_delay: u64,
+    id: u64,
+) -> Result<()> {
+    check_pending_operation(
+        &ctx.accounts.pending_operation,
+        &DidOperation::SetOperationDelay { operation_delay },
+    )?;
+    apply_set_operation_delay(&mut ctx.accounts.operation, did.clone(), operation_delay)?;
+    close_pending_operation(
+        &ctx.accounts.pending_operation,
+        &ctx.accounts.operation.controller,
+        did,
+        id,
+    )
+}
+
+fn check_pending_operation(
+    pending_operation: &PendingOperation,
+    operation: &DidOperation,
+) -> Result<()> {
+    require!(
+        pending_operation.version == PendingOperation::VERSION,
+        VerifiableDataRegistryError::AccountVersionMismatch
+    );
+    require!(
+        pending_operation.operation == *operation,
+        VerifiableDataRegistryError::OperationMismatch
+    );
+    require!(
+        pending_operation.executable_at <= current_timestamp()?,
+        VerifiableDataRegistryError::OperationNotExecutable
+    );
+    Ok(())
+}
+
+fn close_pending_operation<'info>(
+    pending_operation: &Account<'info, PendingOperation>,
+    controller: &Signer<'info>,
+    did: String,
+    id: u64,
+) -> Result<()> {
+    pending_operation.close(controller.to_account_info())?;
+    emit!(OperationExecuted {
+        did,
+        id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,11 @@
+pub mod set_operation_delay;
+pub use set_operation_delay::*;
+
+pub mod propose_operation;
+pub use propose_operation::*;
+
+pub mod cancel_operation;
+pub use cancel_operation::*;
+
+pub mod execute_operation;
+pub use execute_operation::*;
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::OperationProposed, state::*,
+    utils::current_timestamp,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, id: u64)]
+pub struct ProposeOperation<'info> {
+    #[account(
+        init,
+        seeds = [PENDING_OPERATION_SEED, did_document.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        payer = controller,
+        space = PENDING_OPERATION_SPACE
+    )]
+    pub pending_operation: Account<'info, PendingOperation>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_operation_handler(
+    ctx: Context<ProposeOperation>,
+    did: String,
+    id: u64,
+    operation: DidOperation,
+) -> Result<()> {
+    let executable_at =
+        current_timestamp()?.saturating_add(ctx.accounts.did_document.operation_delay);
+
+    let pending_operation = &mut ctx.accounts.pending_operation;
+    pending_operation.version = PendingOperation::VERSION;
+    pending_operation.did_document = ctx.accounts.did_document.key();
+    pending_operation.id = id;
+    pending_operation.operation = operation.clone();
+    pending_operation.executable_at = executable_at;
+
+    emit!(OperationProposed {
+        did,
+        id,
+        operation,
+        executable_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::OperationDelayUpdated, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct SetOperationDelay<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+}
+
+// Raising the delay takes effect at once, lowering it is itself a time-locked operation.
+pub fn set_operation_delay_handler(
+    ctx: Context<SetOperationDelay>,
+    did: String,
+    operation_delay: u64,
+) -> Result<()> {
+    require!(
+        operation_delay >= ctx.accounts.did_document.operation_delay,
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    apply_set_operation_delay(ctx.accounts, did, operation_delay)
+}
+
+pub(crate) fn apply_set_operation_delay(
+    accounts: &mut SetOperationDelay,
+    did: String,
+    operation_delay: u64,
+) -> Result<()> {
+    accounts.did_document.operation_delay = operation_delay;
+
+    emit!(OperationDelayUpdated {
+        did,
+        operation_delay,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError,
+    events::RecoveryApproved,
+    state::*,
+    utils::{current_timestamp, signing_guardian},
+};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct ApproveRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [RECOVERY_SEED, did_document.key().as_ref()],
+        bump,
+        constraint =
+            recovery.version == Recovery::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub recovery: Account<'info, Recovery>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    // Only required when the guardian is a DID, signed for by its controller.
+    pub guardian_did_document: Option<Account<'info, DidDocument>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub guardian: Signer<'info>,
+}
+
+pub fn approve_recovery_handler(ctx: Context<ApproveRecovery>, did: String) -> Result<()> {
+    let guardian = signing_guardian(
+        &ctx.accounts.guardian,
+        ctx.accounts.guardian_did_document.as_ref(),
+    )?;
+    let recovery = &mut ctx.accounts.recovery;
+    recovery.approve(guardian, &ctx.accounts.did_document, current_timestamp()?)?;
+
+    emit!(RecoveryApproved {
+        did,
+        new_controller: recovery.new_controller,
+        guardian,
+        executable_at: recovery.executable_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
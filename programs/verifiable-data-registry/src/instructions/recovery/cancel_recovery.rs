@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::RecoveryCancelled, state::*,
+    utils::current_timestamp,
+};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct CancelRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [RECOVERY_SEED, did_document.key().as_ref()],
+        bump,
+        has_one = payer,
+        close = payer,
+        constraint =
+            recovery.version == Recovery::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub recovery: Account<'info, Recovery>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    /// CHECK: checked against `recovery.payer`, receives the rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    // Only required to cancel a recovery that has not expired.
+    pub controller: Option<Signer<'info>>,
+}
+
+// Not gated by the registry pause: a controller must always be able to stop a hijack.
+pub fn cancel_recovery_handler(ctx: Context<CancelRecovery>, did: String) -> Result<()> {
+    let did_document = &ctx.accounts.did_document;
+    match &ctx.accounts.controller {
+        Some(controller) => require_keys_eq!(
+            controller.key(),
+            did_document.controller,
+            VerifiableDataRegistryError::Unauthorized
+        ),
+        // Anyone may close a recovery guardians did not approve in time, making room for
+        // another one.
+        None => require!(
+            ctx.accounts.recovery.is_expired_at(did_document, current_timestamp()?),
+            VerifiableDataRegistryError::RecoveryNotExpired
+        ),
+    }
+
+    emit!(RecoveryCancelled {
+        did,
+        new_controller: ctx.accounts.recovery.new_controller,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
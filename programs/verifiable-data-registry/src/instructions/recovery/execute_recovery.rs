@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::RecoveryExecuted, state::*,
+    utils::{current_timestamp, record_did_change},
+};
+
+#[derive(Accounts)]
+#[instruction(did: String, from_index_page: u32, to_index_page: u32)]
+pub struct ExecuteRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [RECOVERY_SEED, did_document.key().as_ref()],
+        bump,
+        has_one = payer,
+        close = payer,
+        constraint =
+            recovery.version == Recovery::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch
+    )]
+    pub recovery: Account<'info, Recovery>,
+    #[account(
+        mut,
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        init_if_needed,
+        seeds = [DID_CHANGE_LOG_SEED, did_document.key().as_ref()],
+        bump,
+        payer = executor,
+        space = DID_CHANGE_LOG_SPACE
+    )]
+    pub change_log: Account<'info, DidChangeLog>,
+    // Created empty for controllers of DIDs from before controller indexes were kept.
+    #[account(
+        init_if_needed,
+        seeds = [
+            CONTROLLER_DID_INDEX_SEED,
+            did_document.controller.as_ref(),
+            from_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = executor,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub from_controller_index: Account<'info, ControllerDidIndex>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            CONTROLLER_DID_INDEX_SEED,
+            recovery.new_controller.as_ref(),
+            to_index_page.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = executor,
+        space = INDEX_PAGE_SPACE
+    )]
+    pub to_controller_index: Account<'info, ControllerDidIndex>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    /// CHECK: checked against `recovery.payer`, receives the rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Anyone may execute a recovery once it is approved and its waiting period is over.
+pub fn execute_recovery_handler(
+    ctx: Context<ExecuteRecovery>,
+    did: String,
+    from_index_page: u32,
+    to_index_page: u32,
+) -> Result<()> {
+    let now = current_timestamp()?;
+    let recovery = &ctx.accounts.recovery;
+    let did_document = &ctx.accounts.did_document;
+    require!(
+        recovery.is_approved(did_document)
+            && matches!(recovery.executable_at, Some(at) if at <= now),
+        VerifiableDataRegistryError::RecoveryNotExecutable
+    );
+
+    let did_document_key = did_document.key();
+    let previous_controller = did_document.controller;
+    let new_controller = recovery.new_controller;
+
+    let from_controller_index = &mut ctx.accounts.from_controller_index;
+    from_controller_index.init_or_check(previous_controller, from_index_page)?;
+    remove_index_entry(&mut from_controller_index.entries, &did_document_key);
+    let to_controller_index = &mut ctx.accounts.to_controller_index;
+    to_controller_index.init_or_check(new_controller, to_index_page)?;
+    push_index_entry(&mut to_controller_index.entries, did_document_key)?;
+
+    ctx.accounts.did_document.controller = new_controller;
+    record_did_change(
+        &mut ctx.accounts.change_log,
+        did_document_key,
+        DidChangeOp::ControllerTransferred,
+        String::new(),
+        new_controller.as_ref(),
+    )?;
+
+    emit!(RecoveryExecuted {
+        did,
+        previous_controller,
+        new_controller,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError,
+    events::RecoveryApproved,
+    state::*,
+    utils::{current_timestamp, signing_guardian},
+};
+
+#[derive(Accounts)]
+#[instruction(did: String)]
+pub struct InitiateRecovery<'info> {
+    #[account(
+        init,
+        seeds = [RECOVERY_SEED, did_document.key().as_ref()],
+        bump,
+        payer = payer,
+        space = RECOVERY_SPACE
+    )]
+    pub recovery: Account<'info, Recovery>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint = did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    // Only required when the guardian is a DID, signed for by its controller.
+    pub guardian_did_document: Option<Account<'info, DidDocument>>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub guardian: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Starting a recovery counts as the approval of the guardian who started it.
+pub fn initiate_recovery_handler(
+    ctx: Context<InitiateRecovery>,
+    did: String,
+    new_controller: Pubkey,
+) -> Result<()> {
+    let did_document = &ctx.accounts.did_document;
+    require_keys_neq!(
+        new_controller,
+        did_document.controller,
+        VerifiableDataRegistryError::SameController
+    );
+    let guardian = signing_guardian(
+        &ctx.accounts.guardian,
+        ctx.accounts.guardian_did_document.as_ref(),
+    )?;
+
+    let now = current_timestamp()?;
+    let recovery = &mut ctx.accounts.recovery;
+    recovery.version = Recovery::VERSION;
+    recovery.did_document = did_document.key();
+    recovery.new_controller = new_controller;
+    recovery.expire_at = now.saturating_add(RECOVERY_APPROVAL_PERIOD);
+    recovery.payer = ctx.accounts.payer.key();
+    recovery.approve(guardian, did_document, now)?;
+
+    emit!(RecoveryApproved {
+        did,
+        new_controller,
+        guardian,
+        executable_at: recovery.executable_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,14 @@
+pub mod set_guardians;
+pub use set_guardians::*;
+
+pub mod initiate_recovery;
+pub use initiate_recovery::*;
+
+pub mod approve_recovery;
+pub use approve_recovery::*;
+
+pub mod cancel_recovery;
+pub use cancel_recovery::*;
+
+pub mod execute_recovery;
+pub use execute_recovery::*;
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::GuardiansUpdated, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, guardians: Vec<Guardian>)]
+pub struct SetGuardians<'info> {
+    #[account(
+        mut,
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+        realloc = DidDocument::space(&did, guardians.len(), &did_document.also_known_as),
+        realloc::payer = controller,
+        realloc::zero = false
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// An empty guardian set with a zero threshold turns recovery off.
+pub fn set_guardians_handler(
+    ctx: Context<SetGuardians>,
+    did: String,
+    guardians: Vec<Guardian>,
+    guardian_threshold: u8,
+    recovery_delay: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.did_document.is_time_locked(),
+        VerifiableDataRegistryError::OperationTimeLocked
+    );
+    require!(
+        guardians.len() <= MAX_GUARDIANS,
+        VerifiableDataRegistryError::TooManyGuardians
+    );
+    for (i, guardian) in guardians.iter().enumerate() {
+        require!(
+            !guardians[..i].contains(guardian),
+            VerifiableDataRegistryError::DuplicateGuardian
+        );
+    }
+    require!(
+        (guardian_threshold as usize) <= guardians.len()
+            && (guardian_threshold > 0 || guardians.is_empty()),
+        VerifiableDataRegistryError::InvalidGuardianThreshold
+    );
+
+    let did_document = &mut ctx.accounts.did_document;
+    did_document.guardians = guardians.clone();
+    did_document.guardian_threshold = guardian_threshold;
+    did_document.recovery_delay = recovery_delay;
+
+    emit!(GuardiansUpdated {
+        did,
+        guardians,
+        guardian_threshold,
+        recovery_delay,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
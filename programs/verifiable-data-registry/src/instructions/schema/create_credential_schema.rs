@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::CredentialSchemaCreated, state::*,
+    validation::validate_credential_schema,
+};
+
+#[derive(Accounts)]
+#[instruction(
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+    name: String,
+    fields: Vec<SchemaField>
+)]
+pub struct CreateCredentialSchema<'info> {
+    #[account(
+        init,
+        seeds = [
+            CREDENTIAL_SCHEMA_SEED,
+            did_document.key().as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = CredentialSchema::space(&schema_id, &name, &fields)
+    )]
+    pub credential_schema: Account<'info, CredentialSchema>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Schemas describe claims and bind no key, so they are not time-locked like key changes.
+pub fn create_credential_schema_handler(
+    ctx: Context<CreateCredentialSchema>,
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+    name: String,
+    fields: Vec<SchemaField>,
+    content_hash: [u8; 32],
+) -> Result<()> {
+    validate_credential_schema(&schema_id, &name, &fields)?;
+
+    let credential_schema = &mut ctx.accounts.credential_schema;
+    credential_schema.version = CredentialSchema::VERSION;
+    credential_schema.author = ctx.accounts.did_document.key();
+    credential_schema.schema_id = schema_id.clone();
+    credential_schema.schema_version = schema_version;
+    credential_schema.name = name;
+    credential_schema.fields = fields;
+    credential_schema.content_hash = content_hash;
+    credential_schema.deprecated = false;
+
+    emit!(CredentialSchemaCreated {
+        author_did: did,
+        schema: credential_schema.key(),
+        schema_id,
+        schema_version,
+        content_hash,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
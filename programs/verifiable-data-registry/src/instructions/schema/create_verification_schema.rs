@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    error::VerifiableDataRegistryError, events::VerificationSchemaCreated, state::*,
+    validation::validate_verification_schema,
+};
+
+#[derive(Accounts)]
+#[instruction(
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+    name: String,
+    check_roots: Vec<[u8; 32]>,
+    requested_fields: Vec<String>
+)]
+pub struct CreateVerificationSchema<'info> {
+    #[account(
+        init,
+        seeds = [
+            VERIFICATION_SCHEMA_SEED,
+            did_document.key().as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = controller,
+        space = VerificationSchema::space(&schema_id, &name, &check_roots, &requested_fields)
+    )]
+    pub verification_schema: Account<'info, VerificationSchema>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+        constraint =
+            did_document.version == DidDocument::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    #[account(mut)]
+    pub controller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// The roots are Poseidon hashes computed off-chain; without a syscall for them the program
+// stores what the verifier signs for, holders recompute them from the check objects.
+pub fn create_verification_schema_handler(
+    ctx: Context<CreateVerificationSchema>,
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+    name: String,
+    check_roots: Vec<[u8; 32]>,
+    requested_fields: Vec<String>,
+) -> Result<()> {
+    validate_verification_schema(&schema_id, &name, &check_roots, &requested_fields)?;
+
+    let verification_schema = &mut ctx.accounts.verification_schema;
+    verification_schema.version = VerificationSchema::VERSION;
+    verification_schema.verifier = ctx.accounts.did_document.key();
+    verification_schema.schema_id = schema_id.clone();
+    verification_schema.schema_version = schema_version;
+    verification_schema.name = name;
+    verification_schema.check_roots = check_roots;
+    verification_schema.requested_fields = requested_fields;
+    verification_schema.deprecated = false;
+
+    emit!(VerificationSchemaCreated {
+        verifier_did: did,
+        schema: verification_schema.key(),
+        schema_id,
+        schema_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::CredentialSchemaDeprecated, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, schema_id: String, schema_version: u32)]
+pub struct DeprecateCredentialSchema<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CREDENTIAL_SCHEMA_SEED,
+            did_document.key().as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint =
+            credential_schema.version == CredentialSchema::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub credential_schema: Account<'info, CredentialSchema>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub controller: Signer<'info>,
+}
+
+// The schema stays readable for the credentials already issued against it, only new
+// issuance is refused.
+pub fn deprecate_credential_schema_handler(
+    ctx: Context<DeprecateCredentialSchema>,
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+) -> Result<()> {
+    let credential_schema = &mut ctx.accounts.credential_schema;
+    require!(
+        !credential_schema.deprecated,
+        VerifiableDataRegistryError::CredentialSchemaAlreadyDeprecated
+    );
+    credential_schema.deprecated = true;
+
+    emit!(CredentialSchemaDeprecated {
+        author_did: did,
+        schema: credential_schema.key(),
+        schema_id,
+        schema_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
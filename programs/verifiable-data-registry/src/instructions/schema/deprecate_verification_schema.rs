@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{error::VerifiableDataRegistryError, events::VerificationSchemaDeprecated, state::*};
+
+#[derive(Accounts)]
+#[instruction(did: String, schema_id: String, schema_version: u32)]
+pub struct DeprecateVerificationSchema<'info> {
+    #[account(
+        mut,
+        seeds = [
+            VERIFICATION_SCHEMA_SEED,
+            did_document.key().as_ref(),
+            keccak::hash(schema_id.as_bytes()).as_ref(),
+            schema_version.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint =
+            verification_schema.version == VerificationSchema::VERSION
+            @ VerifiableDataRegistryError::AccountVersionMismatch,
+    )]
+    pub verification_schema: Account<'info, VerificationSchema>,
+    #[account(
+        seeds = [keccak::hash(did.as_bytes()).as_ref()],
+        bump,
+        constraint =
+            did_document.controller == controller.key()
+            && did_document.did == did,
+    )]
+    pub did_document: Account<'info, DidDocument>,
+    #[account(
+        seeds = [REGISTRY_CONFIG_SEED],
+        bump,
+        constraint = !config.paused @ VerifiableDataRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, RegistryConfig>,
+    pub controller: Signer<'info>,
+}
+
+// Holders stop answering the request; proofs already verified against it are unaffected.
+pub fn deprecate_verification_schema_handler(
+    ctx: Context<DeprecateVerificationSchema>,
+    did: String,
+    schema_id: String,
+    schema_version: u32,
+) -> Result<()> {
+    let verification_schema = &mut ctx.accounts.verification_schema;
+    require!(
+        !verification_schema.deprecated,
+        VerifiableDataRegistryError::VerificationSchemaAlreadyDeprecated
+    );
+    verification_schema.deprecated = true;
+
+    emit!(VerificationSchemaDeprecated {
+        verifier_did: did,
+        schema: verification_schema.key(),
+        schema_id,
+        schema_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,11 @@
+pub mod create_credential_schema;
+pub use create_credential_schema::*;
+
+pub mod deprecate_credential_schema;
+pub use deprecate_credential_schema::*;
+
+pub mod create_verification_schema;
+pub use create_verification_schema::*;
+
+pub mod deprecate_verification_schema;
+pub use deprecate_verification_schema::*;
@@ -1,8 +1,13 @@
 use anchor_lang::prelude::*;
 
-mod error;
+pub mod error;
+pub mod events;
 mod instructions;
-mod state;
+pub mod migration;
+pub mod state;
+pub mod utils;
+pub mod validation;
+pub mod webauthn;
 
 use instructions::*;
 use state::*;
@@ -13,10 +18,25 @@ declare_id!("Vc3VmF38ZyWdBxbQgFKAGHWoZeaFDSemgLLicFU4q9R");
 pub mod verifiable_data_registry {
     use super::*;
 
-    pub fn initialize_did(ctx: Context<InitializeDID>, did: String) -> Result<()> {
-        initialize_did_handler(ctx, did)
+    pub fn initialize_did(
+        ctx: Context<InitializeDID>,
+        did: String,
+        controller_index_page: u32,
+    ) -> Result<()> {
+        initialize_did_handler(ctx, did, controller_index_page)
+    }
+
+    pub fn transfer_did_controller(
+        ctx: Context<TransferDidController>,
+        did: String,
+        new_controller: Pubkey,
+        from_index_page: u32,
+        to_index_page: u32,
+    ) -> Result<()> {
+        transfer_did_controller_handler(ctx, did, new_controller, from_index_page, to_index_page)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn add_verification_method(
         ctx: Context<AddVerificationMethod>,
         did: String,
@@ -24,8 +44,19 @@ pub mod verifiable_data_registry {
         r#type: String,
         public_key_multibase: String,
         controller: Pubkey,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
     ) -> Result<()> {
-        add_verification_method_handler(ctx, did, key_id, r#type, public_key_multibase, controller)
+        add_verification_method_handler(
+            ctx,
+            did,
+            key_id,
+            r#type,
+            public_key_multibase,
+            controller,
+            valid_from,
+            valid_until,
+        )
     }
 
     pub fn add_verification_relationship(
@@ -33,19 +64,44 @@ pub mod verifiable_data_registry {
         did: String,
         relationship: Relationship,
         key_id: String,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
     ) -> Result<()> {
-        add_verification_relationship_handler(ctx, did, relationship, key_id)
+        add_verification_relationship_handler(
+            ctx,
+            did,
+            relationship,
+            key_id,
+            valid_from,
+            valid_until,
+        )
     }
 
+    pub fn set_also_known_as(
+        ctx: Context<SetAlsoKnownAs>,
+        did: String,
+        also_known_as: Vec<String>,
+    ) -> Result<()> {
+        set_also_known_as_handler(ctx, did, also_known_as)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn add_credential(
         ctx: Context<AddCredential>,
         did: String,
         authentication_id: String,
         credential_id: String,
         expire_at: Option<u64>,
-        // secp256k1_signature: Secp256k1Signature,
-        recovery_id: u8,
-        signature: [u8; 64],
+        // bound into the issuer's signed message when set
+        holder: Option<CredentialHolder>,
+        // Poseidon root of the credential fields, also bound into the signed message when set
+        field_merkle_root: Option<[u8; 32]>,
+        // secp256k1_signature: Secp256k1Signature, none for Solana authentication keys
+        recovery_id: Option<u8>,
+        signature: Option<[u8; 64]>,
+        // only for passkey authentication keys
+        webauthn_assertion: Option<WebAuthnAssertion>,
+        issuer_index_page: u32,
     ) -> Result<()> {
         add_credential_handler(
             ctx,
@@ -53,10 +109,11 @@ pub mod verifiable_data_registry {
             authentication_id,
             credential_id,
             expire_at,
-            Secp256k1Signature {
-                recovery_id,
-                signature,
-            },
+            holder,
+            field_merkle_root,
+            secp256k1_signature(recovery_id, signature),
+            webauthn_assertion,
+            issuer_index_page,
         )
     }
 
@@ -65,19 +122,409 @@ pub mod verifiable_data_registry {
         did: String,
         authentication_id: String,
         credential_id: String,
-        // secp256k1_signature: Secp256k1Signature,
-        recovery_id: u8,
-        signature: [u8; 64],
+        // secp256k1_signature: Secp256k1Signature, none for Solana authentication keys
+        recovery_id: Option<u8>,
+        signature: Option<[u8; 64]>,
+        // only for passkey authentication keys
+        webauthn_assertion: Option<WebAuthnAssertion>,
     ) -> Result<()> {
         revoke_credential_handler(
             ctx,
             did,
             authentication_id,
             credential_id,
-            Secp256k1Signature {
-                recovery_id,
-                signature,
-            },
+            secp256k1_signature(recovery_id, signature),
+            webauthn_assertion,
+        )
+    }
+    pub fn close_credential(
+        ctx: Context<CloseCredential>,
+        credential_id: String,
+        issuer_index_page: u32,
+        recovery_id: Option<u8>,
+        signature: Option<[u8; 64]>,
+        // only for passkey authentication keys
+        webauthn_assertion: Option<WebAuthnAssertion>,
+    ) -> Result<()> {
+        close_credential_handler(
+            ctx,
+            credential_id,
+            issuer_index_page,
+            secp256k1_signature(recovery_id, signature),
+            webauthn_assertion,
+        )
+    }
+
+    pub fn acknowledge_credential(
+        ctx: Context<AcknowledgeCredential>,
+        credential_id: String,
+        // secp256k1_signature: Secp256k1Signature, none for Solana authentication keys
+        recovery_id: Option<u8>,
+        signature: Option<[u8; 64]>,
+        // only for passkey authentication keys
+        webauthn_assertion: Option<WebAuthnAssertion>,
+    ) -> Result<()> {
+        acknowledge_credential_handler(
+            ctx,
+            credential_id,
+            secp256k1_signature(recovery_id, signature),
+            webauthn_assertion,
+        )
+    }
+
+    pub fn check_credential(
+        ctx: Context<CheckCredential>,
+        credential_id: String,
+        field_merkle_root: [u8; 32],
+    ) -> Result<()> {
+        check_credential_handler(ctx, credential_id, field_merkle_root)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_delegation(
+        ctx: Context<CreateDelegation>,
+        did: String,
+        delegate: Pubkey,
+        id: u64,
+        schema_id_prefix: String,
+        max_credentials: Option<u32>,
+        can_revoke: bool,
+        expire_at: Option<u64>,
+    ) -> Result<()> {
+        create_delegation_handler(
+            ctx,
+            did,
+            delegate,
+            id,
+            schema_id_prefix,
+            max_credentials,
+            can_revoke,
+            expire_at,
+        )
+    }
+
+    pub fn revoke_delegation(
+        ctx: Context<RevokeDelegation>,
+        did: String,
+        delegate: Pubkey,
+        id: u64,
+    ) -> Result<()> {
+        revoke_delegation_handler(ctx, did, delegate, id)
+    }
+
+    pub fn add_delegated_credential(
+        ctx: Context<AddDelegatedCredential>,
+        did: String,
+        credential_id: String,
+        expire_at: Option<u64>,
+        holder: Option<CredentialHolder>,
+        field_merkle_root: Option<[u8; 32]>,
+        issuer_index_page: u32,
+    ) -> Result<()> {
+        add_delegated_credential_handler(
+            ctx,
+            did,
+            credential_id,
+            expire_at,
+            holder,
+            field_merkle_root,
+            issuer_index_page,
+        )
+    }
+
+    pub fn revoke_delegated_credential(
+        ctx: Context<RevokeDelegatedCredential>,
+        did: String,
+        credential_id: String,
+    ) -> Result<()> {
+        revoke_delegated_credential_handler(ctx, did, credential_id)
+    }
+
+    pub fn create_credential_schema(
+        ctx: Context<CreateCredentialSchema>,
+        did: String,
+        schema_id: String,
+        schema_version: u32,
+        name: String,
+        fields: Vec<SchemaField>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        create_credential_schema_handler(
+            ctx,
+            did,
+            schema_id,
+            schema_version,
+            name,
+            fields,
+            content_hash,
         )
     }
+
+    pub fn deprecate_credential_schema(
+        ctx: Context<DeprecateCredentialSchema>,
+        did: String,
+        schema_id: String,
+        schema_version: u32,
+    ) -> Result<()> {
+        deprecate_credential_schema_handler(ctx, did, schema_id, schema_version)
+    }
+
+    pub fn create_verification_schema(
+        ctx: Context<CreateVerificationSchema>,
+        did: String,
+        schema_id: String,
+        schema_version: u32,
+        name: String,
+        check_roots: Vec<[u8; 32]>,
+        requested_fields: Vec<String>,
+    ) -> Result<()> {
+        create_verification_schema_handler(
+            ctx,
+            did,
+            schema_id,
+            schema_version,
+            name,
+            check_roots,
+            requested_fields,
+        )
+    }
+
+    pub fn deprecate_verification_schema(
+        ctx: Context<DeprecateVerificationSchema>,
+        did: String,
+        schema_id: String,
+        schema_version: u32,
+    ) -> Result<()> {
+        deprecate_verification_schema_handler(ctx, did, schema_id, schema_version)
+    }
+
+    pub fn accredit_issuer(
+        ctx: Context<AccreditIssuer>,
+        did: String,
+        issuer_did: String,
+        scope: AccreditationScope,
+        max_depth: u8,
+        expire_at: Option<u64>,
+    ) -> Result<()> {
+        accredit_issuer_handler(ctx, did, issuer_did, scope, max_depth, expire_at)
+    }
+
+    pub fn revoke_accreditation(
+        ctx: Context<RevokeAccreditation>,
+        did: String,
+        issuer_did: String,
+        scope: AccreditationScope,
+    ) -> Result<()> {
+        revoke_accreditation_handler(ctx, did, issuer_did, scope)
+    }
+
+    pub fn check_accreditation<'info>(
+        ctx: Context<'_, '_, '_, 'info, CheckAccreditation<'info>>,
+        root_did: String,
+        issuer_did: String,
+        scope: AccreditationScope,
+    ) -> Result<()> {
+        check_accreditation_handler(ctx, root_did, issuer_did, scope)
+    }
+
+    pub fn set_guardians(
+        ctx: Context<SetGuardians>,
+        did: String,
+        guardians: Vec<Guardian>,
+        guardian_threshold: u8,
+        recovery_delay: u64,
+    ) -> Result<()> {
+        set_guardians_handler(ctx, did, guardians, guardian_threshold, recovery_delay)
+    }
+
+    pub fn initiate_recovery(
+        ctx: Context<InitiateRecovery>,
+        did: String,
+        new_controller: Pubkey,
+    ) -> Result<()> {
+        initiate_recovery_handler(ctx, did, new_controller)
+    }
+
+    pub fn approve_recovery(ctx: Context<ApproveRecovery>, did: String) -> Result<()> {
+        approve_recovery_handler(ctx, did)
+    }
+
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>, did: String) -> Result<()> {
+        cancel_recovery_handler(ctx, did)
+    }
+
+    pub fn execute_recovery(
+        ctx: Context<ExecuteRecovery>,
+        did: String,
+        from_index_page: u32,
+        to_index_page: u32,
+    ) -> Result<()> {
+        execute_recovery_handler(ctx, did, from_index_page, to_index_page)
+    }
+
+    pub fn set_operation_delay(
+        ctx: Context<SetOperationDelay>,
+        did: String,
+        operation_delay: u64,
+    ) -> Result<()> {
+        set_operation_delay_handler(ctx, did, operation_delay)
+    }
+
+    pub fn propose_operation(
+        ctx: Context<ProposeOperation>,
+        did: String,
+        id: u64,
+        operation: DidOperation,
+    ) -> Result<()> {
+        propose_operation_handler(ctx, did, id, operation)
+    }
+
+    pub fn cancel_operation(ctx: Context<CancelOperation>, did: String, id: u64) -> Result<()> {
+        cancel_operation_handler(ctx, did, id)
+    }
+
+    pub fn execute_transfer_did_controller(
+        ctx: Context<ExecuteTransferDidController>,
+        did: String,
+        new_controller: Pubkey,
+        from_index_page: u32,
+        to_index_page: u32,
+        id: u64,
+    ) -> Result<()> {
+        execute_transfer_did_controller_handler(
+            ctx,
+            did,
+            new_controller,
+            from_index_page,
+            to_index_page,
+            id,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_add_verification_method(
+        ctx: Context<ExecuteAddVerificationMethod>,
+        did: String,
+        key_id: String,
+        r#type: String,
+        public_key_multibase: String,
+        controller: Pubkey,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
+        id: u64,
+    ) -> Result<()> {
+        execute_add_verification_method_handler(
+            ctx,
+            did,
+            key_id,
+            r#type,
+            public_key_multibase,
+            controller,
+            valid_from,
+            valid_until,
+            id,
+        )
+    }
+
+    pub fn execute_add_verification_relationship(
+        ctx: Context<ExecuteAddVerificationRelationship>,
+        did: String,
+        relationship: Relationship,
+        key_id: String,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
+        id: u64,
+    ) -> Result<()> {
+        execute_add_verification_relationship_handler(
+            ctx,
+            did,
+            relationship,
+            key_id,
+            valid_from,
+            valid_until,
+            id,
+        )
+    }
+
+    pub fn execute_set_operation_delay(
+        ctx: Context<ExecuteSetOperationDelay>,
+        did: String,
+        operation_delay: u64,
+        id: u64,
+    ) -> Result<()> {
+        execute_set_operation_delay_handler(ctx, did, operation_delay, id)
+    }
+
+    pub fn request_auth_challenge(
+        ctx: Context<RequestAuthChallenge>,
+        did: String,
+        audience: Pubkey,
+    ) -> Result<()> {
+        request_auth_challenge_handler(ctx, did, audience)
+    }
+
+    pub fn create_auth_session(
+        ctx: Context<CreateAuthSession>,
+        did: String,
+        authentication_id: String,
+        duration: u64,
+        // secp256k1_signature: Secp256k1Signature, none for Solana authentication keys
+        recovery_id: Option<u8>,
+        signature: Option<[u8; 64]>,
+        // only for passkey authentication keys
+        webauthn_assertion: Option<WebAuthnAssertion>,
+    ) -> Result<()> {
+        create_auth_session_handler(
+            ctx,
+            did,
+            authentication_id,
+            duration,
+            secp256k1_signature(recovery_id, signature),
+            webauthn_assertion,
+        )
+    }
+
+    pub fn check_auth_session(
+        ctx: Context<CheckAuthSession>,
+        did: String,
+        audience: Pubkey,
+    ) -> Result<()> {
+        check_auth_session_handler(ctx, did, audience)
+    }
+
+    pub fn close_auth_session(ctx: Context<CloseAuthSession>, did: String) -> Result<()> {
+        close_auth_session_handler(ctx, did)
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        allowed_key_types: Vec<String>,
+        issuance_fee: Option<u64>,
+    ) -> Result<()> {
+        initialize_config_handler(ctx, admin, allowed_key_types, issuance_fee)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+        update_config_handler(ctx, update)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        withdraw_fees_handler(ctx, amount)
+    }
+
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account_handler(ctx)
+    }
+}
+
+// Signature arguments of the authentication instructions, none unless both parts are set.
+fn secp256k1_signature(
+    recovery_id: Option<u8>,
+    signature: Option<[u8; 64]>,
+) -> Option<Secp256k1Signature> {
+    Some(Secp256k1Signature {
+        recovery_id: recovery_id?,
+        signature: signature?,
+    })
 }
@@ -0,0 +1,522 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use solana_program::keccak;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::state::*;
+
+// Accounts written before layouts carried a version byte.
+pub const LEGACY_VERSION: u8 = 0;
+
+/// Account that can be upgraded in place from its legacy layout.
+pub trait Migratable:
+    Versioned + AccountSerialize + AccountDeserialize + Discriminator + Sized
+{
+    /// Decodes `body`, the data after the discriminator, with the layout the account was
+    /// deployed with before layouts carried a version byte. Accounts introduced since have
+    /// no legacy layout.
+    fn decode_legacy(_body: &[u8]) -> Option<Legacy<Self>> {
+        None
+    }
+
+    /// Address the account must live at given its contents. Since every registry account
+    /// is a PDA of its own fields, this tells apart layouts that decode the same bytes.
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey;
+}
+
+/// Account decoded from its legacy layout and converted to the current one.
+pub struct Legacy<T> {
+    pub account: T,
+    // Bytes of the body used by the legacy layout.
+    pub len: usize,
+}
+
+pub struct Upgrade {
+    pub from_version: u8,
+    pub to_version: u8,
+    pub data: Vec<u8>,
+}
+
+/// Rewrites the data of a registry account in the current layout of its type. Returns
+/// `None` when the account already uses the current layout.
+pub fn upgrade_account_data(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    data: &[u8],
+) -> Result<Option<Upgrade>> {
+    let discriminator = data
+        .get(..8)
+        .ok_or(VerifiableDataRegistryError::AccountNotMigratable)?;
+
+    if discriminator == DidDocument::discriminator() {
+        upgrade::<DidDocument>(program_id, address, data)
+    } else if discriminator == VerificationMethod::discriminator() {
+        upgrade::<VerificationMethod>(program_id, address, data)
+    } else if discriminator == VerificationRelationship::discriminator() {
+        upgrade::<VerificationRelationship>(program_id, address, data)
+    } else if discriminator == CredentialState::discriminator() {
+        upgrade::<CredentialState>(program_id, address, data)
+    } else if discriminator == ControllerDidIndex::discriminator() {
+        upgrade::<ControllerDidIndex>(program_id, address, data)
+    } else if discriminator == IssuerCredentialIndex::discriminator() {
+        upgrade::<IssuerCredentialIndex>(program_id, address, data)
+    } else if discriminator == RegistryConfig::discriminator() {
+        upgrade::<RegistryConfig>(program_id, address, data)
+    } else if discriminator == Delegation::discriminator() {
+        upgrade::<Delegation>(program_id, address, data)
+    } else if discriminator == Recovery::discriminator() {
+        upgrade::<Recovery>(program_id, address, data)
+    } else if discriminator == PendingOperation::discriminator() {
+        upgrade::<PendingOperation>(program_id, address, data)
+    } else if discriminator == DidChangeLog::discriminator() {
+        upgrade::<DidChangeLog>(program_id, address, data)
+    } else if discriminator == AuthChallenge::discriminator() {
+        upgrade::<AuthChallenge>(program_id, address, data)
+    } else if discriminator == AuthSession::discriminator() {
+        upgrade::<AuthSession>(program_id, address, data)
+    } else if discriminator == CredentialSchema::discriminator() {
+        upgrade::<CredentialSchema>(program_id, address, data)
+    } else if discriminator == VerificationSchema::discriminator() {
+        upgrade::<VerificationSchema>(program_id, address, data)
+    } else if discriminator == Accreditation::discriminator() {
+        upgrade::<Accreditation>(program_id, address, data)
+    } else if discriminator == ClosedCredential::discriminator() {
+        upgrade::<ClosedCredential>(program_id, address, data)
+    } else {
+        err!(VerifiableDataRegistryError::AccountNotMigratable)
+    }
+}
+
+/// Decodes an account written with the current or the legacy layout of `T`.
+pub fn decode_versioned<T: Migratable>(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    data: &[u8],
+) -> Result<T> {
+    match upgrade::<T>(program_id, address, data)? {
+        Some(upgrade) => T::try_deserialize(&mut upgrade.data.as_slice()),
+        None => T::try_deserialize(&mut &data[..]),
+    }
+}
+
+fn upgrade<T: Migratable>(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    data: &[u8],
+) -> Result<Option<Upgrade>> {
+    require!(
+        data.starts_with(&T::discriminator()),
+        VerifiableDataRegistryError::AccountNotMigratable
+    );
+    if let Ok(account) = T::try_deserialize(&mut &data[..]) {
+        if account.version() == T::VERSION && account.expected_address(program_id) == *address {
+            return Ok(None);
+        }
+    }
+
+    let body = &data[8..];
+    let legacy = T::decode_legacy(body)
+        .filter(|legacy| legacy.account.expected_address(program_id) == *address)
+        .ok_or(VerifiableDataRegistryError::AccountNotMigratable)?;
+
+    let mut upgraded = Vec::with_capacity(data.len());
+    legacy.account.try_serialize(&mut upgraded)?;
+    // Accounts keep the capacity they were allocated beyond their legacy layout.
+    upgraded.resize(upgraded.len() + body.len() - legacy.len, 0);
+    Ok(Some(Upgrade {
+        from_version: LEGACY_VERSION,
+        to_version: T::VERSION,
+        data: upgraded,
+    }))
+}
+
+// Decodes `body` with the legacy layout `L` and converts it with `convert`.
+fn decode_layout<L: AnchorDeserialize, T>(
+    body: &[u8],
+    convert: impl FnOnce(L) -> T,
+) -> Option<Legacy<T>> {
+    let mut rest = body;
+    let layout = L::deserialize(&mut rest).ok()?;
+    Some(Legacy {
+        account: convert(layout),
+        len: body.len() - rest.len(),
+    })
+}
+
+fn find_address(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+// Layouts deployed before accounts carried a version byte.
+pub mod legacy {
+    use super::*;
+
+    #[derive(AnchorDeserialize)]
+    pub struct DidDocumentV0 {
+        pub controller: Pubkey,
+        pub did: String,
+    }
+
+    #[derive(AnchorDeserialize)]
+    pub struct VerificationMethodV0 {
+        pub controller: Pubkey,
+        pub did: String,
+        pub key_id: String,
+        pub r#type: String,
+        pub public_key_multibase: String,
+    }
+
+    #[derive(AnchorDeserialize)]
+    pub struct VerificationRelationshipV0 {
+        pub did: String,
+        pub relationship: Relationship,
+        pub key_id: String,
+    }
+
+    #[derive(AnchorDeserialize)]
+    pub struct CredentialStateV0 {
+        pub issuer_did: String,
+        pub credential_id: String,
+        pub status: CredentialStatus,
+        pub expire_at: Option<u64>,
+    }
+}
+
+impl Migratable for DidDocument {
+    fn decode_legacy(body: &[u8]) -> Option<Legacy<Self>> {
+        decode_layout(body, |legacy: legacy::DidDocumentV0| Self {
+            version: Self::VERSION,
+            controller: legacy.controller,
+            did: legacy.did,
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            recovery_delay: 0,
+            operation_delay: 0,
+            also_known_as: Vec::new(),
+        })
+    }
+
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(&[keccak::hash(self.did.as_bytes()).as_ref()], program_id)
+    }
+}
+
+impl Migratable for VerificationMethod {
+    fn decode_legacy(body: &[u8]) -> Option<Legacy<Self>> {
+        decode_layout(body, |legacy: legacy::VerificationMethodV0| Self {
+            version: Self::VERSION,
+            controller: legacy.controller,
+            did: legacy.did,
+            key_id: legacy.key_id,
+            r#type: legacy.r#type,
+            public_key_multibase: legacy.public_key_multibase,
+            valid_from: None,
+            valid_until: None,
+        })
+    }
+
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        let seed = keccak::hash([self.did.as_bytes(), self.key_id.as_bytes()].concat().as_slice());
+        find_address(&[seed.as_ref()], program_id)
+    }
+}
+
+impl Migratable for VerificationRelationship {
+    fn decode_legacy(body: &[u8]) -> Option<Legacy<Self>> {
+        decode_layout(body, |legacy: legacy::VerificationRelationshipV0| Self {
+            version: Self::VERSION,
+            did: legacy.did,
+            relationship: legacy.relationship,
+            key_id: legacy.key_id,
+            valid_from: None,
+            valid_until: None,
+        })
+    }
+
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        let seed = keccak::hash(
+            [
+                self.did.as_bytes(),
+                self.relationship.as_bytes(),
+                self.key_id.as_bytes(),
+            ]
+            .concat()
+            .as_slice(),
+        );
+        find_address(&[seed.as_ref()], program_id)
+    }
+}
+
+impl Migratable for CredentialState {
+    fn decode_legacy(body: &[u8]) -> Option<Legacy<Self>> {
+        decode_layout(body, |legacy: legacy::CredentialStateV0| Self {
+            version: Self::VERSION,
+            issuer_did: legacy.issuer_did,
+            credential_id: legacy.credential_id,
+            status: legacy.status,
+            expire_at: legacy.expire_at,
+            // The payer was not recorded: closing refunds the issuer's controller.
+            payer: Pubkey::default(),
+            delegation: None,
+            holder: None,
+            acknowledged_at: None,
+            schema: None,
+            field_merkle_root: None,
+        })
+    }
+
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[keccak::hash(self.credential_id.as_bytes()).as_ref()],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for ControllerDidIndex {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                CONTROLLER_DID_INDEX_SEED,
+                self.controller.as_ref(),
+                self.page.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for IssuerCredentialIndex {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                ISSUER_CREDENTIAL_INDEX_SEED,
+                self.issuer.as_ref(),
+                self.page.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for RegistryConfig {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(&[REGISTRY_CONFIG_SEED], program_id)
+    }
+}
+
+impl Migratable for Delegation {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        let did_document = find_address(&[keccak::hash(self.did.as_bytes()).as_ref()], program_id);
+        find_address(
+            &[
+                DELEGATION_SEED,
+                did_document.as_ref(),
+                self.delegate.as_ref(),
+                self.id.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for Recovery {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(&[RECOVERY_SEED, self.did_document.as_ref()], program_id)
+    }
+}
+
+impl Migratable for PendingOperation {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                PENDING_OPERATION_SEED,
+                self.did_document.as_ref(),
+                self.id.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for DidChangeLog {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(&[DID_CHANGE_LOG_SEED, self.did_document.as_ref()], program_id)
+    }
+}
+
+impl Migratable for AuthChallenge {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                AUTH_CHALLENGE_SEED,
+                self.did_document.as_ref(),
+                self.session_key.as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for AuthSession {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                AUTH_SESSION_SEED,
+                self.did_document.as_ref(),
+                self.session_key.as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for CredentialSchema {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                CREDENTIAL_SCHEMA_SEED,
+                self.author.as_ref(),
+                keccak::hash(self.schema_id.as_bytes()).as_ref(),
+                self.schema_version.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for VerificationSchema {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                VERIFICATION_SCHEMA_SEED,
+                self.verifier.as_ref(),
+                keccak::hash(self.schema_id.as_bytes()).as_ref(),
+                self.schema_version.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for Accreditation {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                ACCREDITATION_SEED,
+                self.authority.as_ref(),
+                self.issuer.as_ref(),
+                self.scope.seed().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl Migratable for ClosedCredential {
+    fn expected_address(&self, program_id: &Pubkey) -> Pubkey {
+        find_address(
+            &[
+                CLOSED_CREDENTIAL_SEED,
+                keccak::hash(self.credential_id.as_bytes()).as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:zuni:solana:legacy";
+
+    fn legacy_data<T: Discriminator>(layout: impl AnchorSerialize) -> Vec<u8> {
+        let mut data = T::discriminator().to_vec();
+        layout.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_upgrade_legacy_did_document() {
+        let controller = Pubkey::new_unique();
+        let address = find_address(&[keccak::hash(DID.as_bytes()).as_ref()], &crate::ID);
+        let data = legacy_data::<DidDocument>((controller, DID.to_string()));
+
+        let upgrade = upgrade_account_data(&crate::ID, &address, &data).unwrap().unwrap();
+        assert_eq!(upgrade.from_version, LEGACY_VERSION);
+        assert_eq!(upgrade.to_version, DidDocument::VERSION);
+        let document = DidDocument::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(document.controller, controller);
+        assert_eq!(document.did, DID);
+        assert!(document.guardians.is_empty());
+        assert_eq!(document.guardian_threshold, 0);
+        assert!(upgrade_account_data(&crate::ID, &address, &upgrade.data).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_transfer_unindexed_legacy_did() {
+        let controller = Pubkey::new_unique();
+        let address = find_address(&[keccak::hash(DID.as_bytes()).as_ref()], &crate::ID);
+        let data = legacy_data::<DidDocument>((controller, DID.to_string()));
+        let upgrade = upgrade_account_data(&crate::ID, &address, &data).unwrap().unwrap();
+        let document = DidDocument::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+
+        // The controller had no index page listing the DID, `init_if_needed` zeroes one.
+        let mut from_index = ControllerDidIndex {
+            version: 0,
+            controller: Pubkey::default(),
+            page: 0,
+            entries: Vec::new(),
+        };
+        from_index.init_or_check(document.controller, 0).unwrap();
+        let other = Pubkey::new_unique();
+        from_index.entries.push(other);
+        remove_index_entry(&mut from_index.entries, &address);
+        assert_eq!(from_index.entries, [other]);
+    }
+
+    #[test]
+    fn test_upgrade_legacy_credential_state() {
+        let address = find_address(&[keccak::hash(b"credential-1").as_ref()], &crate::ID);
+        let mut data = legacy_data::<CredentialState>((
+            DID.to_string(),
+            "credential-1".to_string(),
+            CredentialStatus::Revoked,
+            Some(1u64),
+        ));
+        // Unused capacity of the account is kept.
+        data.extend_from_slice(&[0; 16]);
+
+        let upgrade = upgrade_account_data(&crate::ID, &address, &data).unwrap().unwrap();
+        let credential = CredentialState::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(credential.version, CredentialState::VERSION);
+        assert_eq!(credential.issuer_did, DID);
+        assert_eq!(credential.status, CredentialStatus::Revoked);
+        assert_eq!(credential.expire_at, Some(1));
+        assert_eq!(credential.payer, Pubkey::default());
+        let mut current = Vec::new();
+        credential.try_serialize(&mut current).unwrap();
+        assert_eq!(upgrade.data.len(), current.len() + 16);
+    }
+
+    #[test]
+    fn test_reject_legacy_layout_at_another_address() {
+        let data = legacy_data::<DidDocument>((Pubkey::new_unique(), DID.to_string()));
+        assert_eq!(
+            upgrade_account_data(&crate::ID, &Pubkey::new_unique(), &data).map(|_| ()),
+            Err(VerifiableDataRegistryError::AccountNotMigratable.into())
+        );
+        // Accounts introduced with a version byte have no legacy layout.
+        let controller = Pubkey::new_unique();
+        let address = find_address(
+            &[CONTROLLER_DID_INDEX_SEED, controller.as_ref(), 0u32.to_le_bytes().as_ref()],
+            &crate::ID,
+        );
+        let data = legacy_data::<ControllerDidIndex>((controller, 0u32, Vec::<Pubkey>::new()));
+        assert_eq!(
+            upgrade_account_data(&crate::ID, &address, &data).map(|_| ()),
+            Err(VerifiableDataRegistryError::AccountNotMigratable.into())
+        );
+    }
+}
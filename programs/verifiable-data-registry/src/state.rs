@@ -1,25 +1,204 @@
 use anchor_lang::prelude::*;
 
+use crate::validation::{MAX_KEY_ID_LENGTH, MAX_KEY_TYPE_LENGTH, MAX_PUBLIC_KEY_MULTIBASE_LENGTH};
+
+// Every account stores its layout version right after the discriminator, so a layout can
+// change without breaking accounts that were written with an older one (see `migration`).
+pub trait Versioned {
+    const VERSION: u8;
+
+    fn version(&self) -> u8;
+}
+
+macro_rules! impl_versioned {
+    ($($account:ty => $version:expr),* $(,)?) => {
+        $(impl Versioned for $account {
+            const VERSION: u8 = $version;
+
+            fn version(&self) -> u8 {
+                self.version
+            }
+        })*
+    };
+}
+
+impl_versioned! {
+    DidDocument => 4,
+    VerificationMethod => 1,
+    VerificationRelationship => 1,
+    CredentialState => 5,
+    ControllerDidIndex => 1,
+    IssuerCredentialIndex => 1,
+    RegistryConfig => 1,
+    Delegation => 1,
+    Recovery => 1,
+    PendingOperation => 1,
+    DidChangeLog => 1,
+    AuthChallenge => 1,
+    AuthSession => 1,
+    CredentialSchema => 1,
+    VerificationSchema => 1,
+    Accreditation => 1,
+    ClosedCredential => 1,
+}
+
 #[account]
+#[derive(Debug)]
 pub struct DidDocument {
+    pub version: u8,
     pub controller: Pubkey,
     pub did: String,
+    pub guardians: Vec<Guardian>, // may hand the DID over to a new controller, see `Recovery`
+    pub guardian_threshold: u8,   // approvals needed to start a recovery
+    pub recovery_delay: u64,      // milliseconds the controller has to cancel a recovery
+    pub operation_delay: u64,     // milliseconds a `PendingOperation` waits before it executes
+    pub also_known_as: Vec<String>, // other identifiers of the subject, e.g. did:key or did:pkh
+}
+
+impl DidDocument {
+    pub fn space(did: &str, guardians: usize, also_known_as: &[String]) -> usize {
+        8 + 1 + 32 + (4 + did.len()) + (4 + GUARDIAN_SPACE * guardians) + 1 + 8 + 8
+            + (4 + also_known_as.iter().map(|alias| 4 + alias.len()).sum::<usize>())
+    }
+
+    // Sensitive operations must go through a `PendingOperation` once a delay is set; those
+    // without one (guardians, aliases, delegations) wait until the delay is lowered back to 0.
+    pub fn is_time_locked(&self) -> bool {
+        self.operation_delay > 0
+    }
+
+    pub fn is_guardian(&self, guardian: &Guardian) -> bool {
+        self.guardians.contains(guardian)
+    }
 }
 
+pub const MAX_GUARDIANS: usize = 8;
+const GUARDIAN_SPACE: usize = 1 + 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Guardian {
+    Key(Pubkey),
+    Did(Pubkey), // `DidDocument` address, approvals are signed by its controller
+}
+
+pub const RECOVERY_SEED: &[u8] = b"recovery";
+pub const RECOVERY_SPACE: usize =
+    8 + 1 + 32 + 32 + (4 + GUARDIAN_SPACE * MAX_GUARDIANS) + (1 + 8) + 8 + 32;
+// Time guardians have to approve a recovery once started, in milliseconds.
+pub const RECOVERY_APPROVAL_PERIOD: u64 = 7 * 24 * 60 * 60 * 1000;
+
+// Pending hand-over of `did_document` to `new_controller`, started by one of its guardians.
+// Once `guardian_threshold` guardians approved, it can be executed after the recovery delay,
+// until then the current controller may cancel it. Short of approvals at `expire_at`, anyone
+// may close it, so a lone guardian cannot hold the only recovery slot of the DID.
 #[account]
+#[derive(Debug)]
+pub struct Recovery {
+    pub version: u8,
+    pub did_document: Pubkey,
+    pub new_controller: Pubkey,
+    pub approvals: Vec<Guardian>,
+    pub executable_at: Option<u64>, // Unix timestamps in milliseconds
+    pub expire_at: u64,
+    pub payer: Pubkey, // receives the rent back when the recovery is closed
+}
+
+impl Recovery {
+    // Approvals of guardians removed since they approved no longer count.
+    pub fn approvals_of(&self, did_document: &DidDocument) -> usize {
+        self.approvals
+            .iter()
+            .filter(|guardian| did_document.is_guardian(guardian))
+            .count()
+    }
+
+    // A DID whose guardians were all removed approves no recovery, even one approved before.
+    pub fn is_approved(&self, did_document: &DidDocument) -> bool {
+        did_document.guardian_threshold > 0
+            && self.approvals_of(did_document) >= did_document.guardian_threshold as usize
+    }
+
+    pub fn is_expired_at(&self, did_document: &DidDocument, timestamp: u64) -> bool {
+        self.expire_at <= timestamp && !self.is_approved(did_document)
+    }
+
+    pub fn approve(&mut self, guardian: Guardian, did_document: &DidDocument, now: u64) -> Result<()> {
+        require!(
+            did_document.is_guardian(&guardian),
+            crate::error::VerifiableDataRegistryError::NotGuardian
+        );
+        require!(
+            now < self.expire_at,
+            crate::error::VerifiableDataRegistryError::RecoveryExpired
+        );
+        require!(
+            !self.approvals.contains(&guardian),
+            crate::error::VerifiableDataRegistryError::RecoveryAlreadyApproved
+        );
+        require!(
+            self.approvals.len() < MAX_GUARDIANS,
+            crate::error::VerifiableDataRegistryError::TooManyGuardians
+        );
+        self.approvals.push(guardian);
+        if self.executable_at.is_none() && self.is_approved(did_document) {
+            // Guardians set by a compromised controller must not outrun the operation delay.
+            let delay = did_document.recovery_delay.max(did_document.operation_delay);
+            self.executable_at = Some(now.saturating_add(delay));
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Debug)]
 pub struct VerificationMethod {
+    pub version: u8,
     pub controller: Pubkey,
     pub did: String,
     pub key_id: String,
     pub r#type: String,
     pub public_key_multibase: String,
+    pub valid_from: Option<u64>,  // Unix timestamps in milliseconds
+    pub valid_until: Option<u64>, // Unix timestamps in milliseconds
+}
+
+impl VerificationMethod {
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        is_within_validity_window(self.valid_from, self.valid_until, timestamp)
+    }
 }
 
 #[account]
+#[derive(Debug)]
 pub struct VerificationRelationship {
+    pub version: u8,
     pub did: String,
     pub relationship: Relationship,
     pub key_id: String,
+    pub valid_from: Option<u64>,  // Unix timestamps in milliseconds
+    pub valid_until: Option<u64>, // Unix timestamps in milliseconds
+}
+
+impl VerificationRelationship {
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        is_within_validity_window(self.valid_from, self.valid_until, timestamp)
+    }
+}
+
+pub fn is_within_validity_window(
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+    timestamp: u64,
+) -> bool {
+    !matches!(valid_from, Some(from) if from > timestamp)
+        && !matches!(valid_until, Some(until) if timestamp >= until)
+}
+
+pub fn is_valid_validity_window(valid_from: Option<u64>, valid_until: Option<u64>) -> bool {
+    match (valid_from, valid_until) {
+        (Some(from), Some(until)) => from < until,
+        _ => true,
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -40,11 +219,167 @@ impl Relationship {
 }
 
 #[account]
+#[derive(Debug)]
 pub struct CredentialState {
+    pub version: u8,
     pub issuer_did: String,
     pub credential_id: String,
     pub status: CredentialStatus,
-    pub expire_at: Option<u64>, // Unix timestamps in milliseconds
+    pub expire_at: Option<u64>,     // Unix timestamps in milliseconds
+    pub payer: Pubkey,              // receives the rent back, see `rent_recipient`
+    pub delegation: Option<Pubkey>, // `Delegation` the credential was issued under, if any
+    pub holder: Option<CredentialHolder>, // subject the credential was issued to, if recorded
+    pub acknowledged_at: Option<u64>,     // when the holder acknowledged the credential
+    pub schema: Option<Pubkey>,           // `CredentialSchema` of the claims, if declared
+    pub field_merkle_root: Option<[u8; 32]>, // Poseidon `fieldMerkleRoot` of the claims, big-endian
+}
+
+impl CredentialState {
+    pub fn is_active_at(&self, timestamp: u64) -> bool {
+        self.status == CredentialStatus::Active
+            && !matches!(self.expire_at, Some(expire_at) if expire_at <= timestamp)
+    }
+
+    // Account refunded the rent when the credential is closed. Credentials migrated from the
+    // layout without a payer record the default key and refund the controller of the issuer.
+    pub fn rent_recipient(&self, issuer_controller: &Pubkey) -> Pubkey {
+        if self.payer == Pubkey::default() {
+            *issuer_controller
+        } else {
+            self.payer
+        }
+    }
+
+    pub fn space(issuer_did: &str, credential_id: &str, holder: Option<&CredentialHolder>) -> usize {
+        8 + 1 + (4 + issuer_did.len()) + (4 + credential_id.len()) + 1 + (1 + 8) + 32 + (1 + 32)
+            + (1 + holder.map_or(0, CredentialHolder::space))
+            + (1 + 8)
+            + (1 + 32)
+            + (1 + 32)
+    }
+
+    // Hash signed by the issuer to add `credential_id`. It covers the expiry, holder, schema and
+    // field Merkle root, recorded or not, so whoever submits the signature cannot change what it
+    // attests.
+    pub fn issuance_message_hash(
+        credential_id: &str,
+        expire_at: Option<u64>,
+        holder: Option<&CredentialHolder>,
+        schema: Option<&Pubkey>,
+        field_merkle_root: Option<&[u8; 32]>,
+    ) -> [u8; 32] {
+        let mut message = ISSUANCE_MESSAGE_TAG.to_vec();
+        push_str(&mut message, credential_id);
+        push_option(&mut message, expire_at.map(u64::to_le_bytes).as_ref().map(|at| &at[..]));
+        push_option(&mut message, holder.map(CredentialHolder::message_bytes).as_deref());
+        push_option(&mut message, schema.map(Pubkey::as_ref));
+        push_option(&mut message, field_merkle_root.map(|root| &root[..]));
+        solana_program::keccak::hash(&message).to_bytes()
+    }
+
+    // Hash signed by the issuer `issuer_did` to revoke `credential_id`.
+    pub fn revocation_message_hash(issuer_did: &str, credential_id: &str) -> [u8; 32] {
+        let mut message = REVOCATION_MESSAGE_TAG.to_vec();
+        push_str(&mut message, issuer_did);
+        push_str(&mut message, credential_id);
+        solana_program::keccak::hash(&message).to_bytes()
+    }
+
+    // Hash signed by the issuer to close `credential_id`.
+    pub fn close_message_hash(credential_id: &str) -> [u8; 32] {
+        let mut message = CLOSE_MESSAGE_TAG.to_vec();
+        push_str(&mut message, credential_id);
+        solana_program::keccak::hash(&message).to_bytes()
+    }
+
+    // Hash signed by the holder to acknowledge `credential_id`.
+    pub fn acknowledgement_message_hash(credential_id: &str) -> [u8; 32] {
+        let mut message = ACKNOWLEDGEMENT_MESSAGE_TAG.to_vec();
+        push_str(&mut message, credential_id);
+        solana_program::keccak::hash(&message).to_bytes()
+    }
+}
+
+// Subject a credential is bound to, mirroring `holder` and `holderPublicKey` of the
+// credentials built by the TypeScript library.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum CredentialHolder {
+    Did(String),                  // acknowledges with one of its authentication keys
+    KeccakCommitment([u8; 32]),   // keccak of an uncompressed secp256k1 key, `x || y`
+    PoseidonCommitment([u8; 32]), // Poseidon hash of a BabyJub key, only provable off-chain
+}
+
+impl CredentialHolder {
+    pub fn space(&self) -> usize {
+        match self {
+            CredentialHolder::Did(did) => 4 + did.len(),
+            CredentialHolder::KeccakCommitment(_) | CredentialHolder::PoseidonCommitment(_) => 32,
+        }
+    }
+
+    // The variant index, then the DID or the commitment, as Borsh encodes the holder.
+    fn message_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            CredentialHolder::Did(did) => {
+                bytes.push(0);
+                push_str(&mut bytes, did);
+            }
+            CredentialHolder::KeccakCommitment(commitment) => {
+                bytes.push(1);
+                bytes.extend_from_slice(commitment);
+            }
+            CredentialHolder::PoseidonCommitment(commitment) => {
+                bytes.push(2);
+                bytes.extend_from_slice(commitment);
+            }
+        }
+        bytes
+    }
+}
+
+// Messages signed about a credential start with a tag naming the operation. Strings follow
+// their u32 little-endian length and optional fields a presence byte, as Borsh encodes them,
+// so that no two operations or field lists share an encoding.
+const ISSUANCE_MESSAGE_TAG: &[u8] = b"ZUNI_ISSUE_V1";
+const REVOCATION_MESSAGE_TAG: &[u8] = b"ZUNI_REVOKE_V1";
+const CLOSE_MESSAGE_TAG: &[u8] = b"ZUNI_CLOSE_V1";
+const ACKNOWLEDGEMENT_MESSAGE_TAG: &[u8] = b"ZUNI_ACKNOWLEDGE_V1";
+
+fn push_str(message: &mut Vec<u8>, value: &str) {
+    message.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    message.extend_from_slice(value.as_bytes());
+}
+
+fn push_option(message: &mut Vec<u8>, value: Option<&[u8]>) {
+    match value {
+        Some(value) => {
+            message.push(1);
+            message.extend_from_slice(value);
+        }
+        None => message.push(0),
+    }
+}
+
+// Time after `expire_at` from which anyone may close a credential, in milliseconds.
+pub const CREDENTIAL_CLOSE_GRACE_PERIOD: u64 = 30 * 24 * 60 * 60 * 1000;
+
+pub const CLOSED_CREDENTIAL_SEED: &[u8] = b"closed_credential";
+
+// Left by `close_credential` in place of the credential. Issuing checks it is absent, so the
+// issuer's signature of a closed credential cannot be replayed to bring it back.
+#[account]
+#[derive(Debug)]
+pub struct ClosedCredential {
+    pub version: u8,
+    pub credential_id: String,
+    pub closed_at: u64, // Unix timestamp in milliseconds
+}
+
+impl ClosedCredential {
+    pub fn space(credential_id: &str) -> usize {
+        8 + 1 + (4 + credential_id.len()) + 8
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -58,3 +393,472 @@ pub struct Secp256k1Signature {
     pub recovery_id: u8,
     pub signature: [u8; 64],
 }
+
+// WebAuthn assertion of a passkey answering the message hash as its challenge. The passkey
+// signature over `authenticator_data || sha256(client_data_json)` is checked by a secp256r1
+// instruction placed before the one carrying the assertion.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct WebAuthnAssertion {
+    pub authenticator_data: Vec<u8>,
+    pub client_data_json: Vec<u8>,
+}
+
+pub const CONTROLLER_DID_INDEX_SEED: &[u8] = b"controller_did_index";
+pub const ISSUER_CREDENTIAL_INDEX_SEED: &[u8] = b"issuer_credential_index";
+pub const INDEX_PAGE_CAPACITY: usize = 32;
+pub const INDEX_PAGE_SPACE: usize = 8 + 1 + 32 + 4 + (4 + 32 * INDEX_PAGE_CAPACITY);
+
+// Page of the `DidDocument` addresses controlled by `controller`.
+#[account]
+#[derive(Debug)]
+pub struct ControllerDidIndex {
+    pub version: u8,
+    pub controller: Pubkey,
+    pub page: u32,
+    pub entries: Vec<Pubkey>,
+}
+
+// Page of the `CredentialState` addresses issued by the DID at `issuer`.
+#[account]
+#[derive(Debug)]
+pub struct IssuerCredentialIndex {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub page: u32,
+    pub entries: Vec<Pubkey>,
+}
+
+impl ControllerDidIndex {
+    // `init_if_needed` hands over a zeroed page the first time it is used.
+    pub fn init_or_check(&mut self, controller: Pubkey, page: u32) -> Result<()> {
+        if self.controller == Pubkey::default() {
+            self.version = Self::VERSION;
+            self.controller = controller;
+            self.page = page;
+        }
+        require!(
+            self.version == Self::VERSION,
+            crate::error::VerifiableDataRegistryError::AccountVersionMismatch
+        );
+        Ok(())
+    }
+}
+
+impl IssuerCredentialIndex {
+    // `init_if_needed` hands over a zeroed page the first time it is used.
+    pub fn init_or_check(&mut self, issuer: Pubkey, page: u32) -> Result<()> {
+        if self.issuer == Pubkey::default() {
+            self.version = Self::VERSION;
+            self.issuer = issuer;
+            self.page = page;
+        }
+        require!(
+            self.version == Self::VERSION,
+            crate::error::VerifiableDataRegistryError::AccountVersionMismatch
+        );
+        Ok(())
+    }
+}
+
+pub const PENDING_OPERATION_SEED: &[u8] = b"pending_operation";
+pub const PENDING_OPERATION_SPACE: usize = 8 + 1 + 32 + 8 + DID_OPERATION_SPACE + 8;
+const DID_OPERATION_SPACE: usize = 1
+    + (4 + MAX_KEY_ID_LENGTH)
+    + (4 + MAX_KEY_TYPE_LENGTH)
+    + (4 + MAX_PUBLIC_KEY_MULTIBASE_LENGTH)
+    + 32
+    + (1 + 8)
+    + (1 + 8);
+
+// Controller operation announced ahead of time on a DID with an `operation_delay`, executed
+// by the matching `execute_*` instruction once `executable_at` has passed.
+#[account]
+#[derive(Debug)]
+pub struct PendingOperation {
+    pub version: u8,
+    pub did_document: Pubkey,
+    pub id: u64, // chosen by the controller, several operations may be pending at once
+    pub operation: DidOperation,
+    pub executable_at: u64, // Unix timestamps in milliseconds
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum DidOperation {
+    TransferController {
+        new_controller: Pubkey,
+    },
+    AddVerificationMethod {
+        key_id: String,
+        r#type: String,
+        public_key_multibase: String,
+        controller: Pubkey,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
+    },
+    AddVerificationRelationship {
+        relationship: Relationship,
+        key_id: String,
+        valid_from: Option<u64>,
+        valid_until: Option<u64>,
+    },
+    SetOperationDelay {
+        operation_delay: u64,
+    },
+}
+
+pub const DID_CHANGE_LOG_SEED: &[u8] = b"did_change_log";
+pub const DID_CHANGE_LOG_CAPACITY: usize = 16;
+pub const DID_CHANGE_LOG_SPACE: usize =
+    8 + 1 + 32 + 8 + (4 + DID_CHANGE_SPACE * DID_CHANGE_LOG_CAPACITY);
+const DID_CHANGE_SPACE: usize = 8 + 8 + (1 + 1) + (4 + MAX_KEY_ID_LENGTH) + 32;
+
+// Ring buffer of the latest changes to a DID, from which resolvers rebuild the document as
+// it was at an earlier slot. Change `n` is kept in `entries[(n - 1) % DID_CHANGE_LOG_CAPACITY]`
+// until it is overwritten.
+#[account]
+#[derive(Debug)]
+pub struct DidChangeLog {
+    pub version: u8,
+    pub did_document: Pubkey,
+    pub version_id: u64, // number of changes recorded, the `versionId` of the current document
+    pub entries: Vec<DidChange>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct DidChange {
+    pub version_id: u64,
+    pub slot: u64,
+    pub op: DidChangeOp,
+    pub key_id: String,     // empty for controller changes
+    pub key_hash: [u8; 32], // keccak of the public key or controller the change binds
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum DidChangeOp {
+    Created,
+    ControllerTransferred,
+    VerificationMethodAdded,
+    VerificationRelationshipAdded { relationship: Relationship },
+}
+
+impl DidChangeLog {
+    // `init_if_needed` hands over a zeroed log to DIDs created before change logs existed.
+    pub fn init_or_check(&mut self, did_document: Pubkey) -> Result<()> {
+        if self.did_document == Pubkey::default() {
+            self.version = Self::VERSION;
+            self.did_document = did_document;
+        }
+        require!(
+            self.version == Self::VERSION,
+            crate::error::VerifiableDataRegistryError::AccountVersionMismatch
+        );
+        Ok(())
+    }
+
+    pub fn record(&mut self, op: DidChangeOp, key_id: String, key_hash: [u8; 32], slot: u64) {
+        self.version_id += 1;
+        let change = DidChange {
+            version_id: self.version_id,
+            slot,
+            op,
+            key_id,
+            key_hash,
+        };
+        let position = ((self.version_id - 1) % DID_CHANGE_LOG_CAPACITY as u64) as usize;
+        if position < self.entries.len() {
+            self.entries[position] = change;
+        } else {
+            self.entries.push(change);
+        }
+    }
+
+    /// Retained changes, oldest first.
+    pub fn changes(&self) -> impl Iterator<Item = &DidChange> {
+        let split = (self.version_id % DID_CHANGE_LOG_CAPACITY as u64) as usize;
+        let (newer, older) = self.entries.split_at(split.min(self.entries.len()));
+        older.iter().chain(newer)
+    }
+}
+
+pub const DELEGATION_SEED: &[u8] = b"delegation";
+
+// Right granted by the controller of `did` to the `delegate` signer to issue credentials
+// for `did` without being one of its verification methods. Credentials refer to their
+// delegation by address, so the controller picks a new `id` for every delegation it grants
+// the same delegate: a later grant never inherits the credentials of a revoked one.
+#[account]
+#[derive(Debug)]
+pub struct Delegation {
+    pub version: u8,
+    pub did: String,
+    pub delegate: Pubkey,
+    pub id: u64,
+    pub schema_id_prefix: String, // `CredentialSchema` ids the delegate may issue against
+    pub max_credentials: Option<u32>,
+    pub issued_credentials: u32,
+    pub can_revoke: bool,       // may revoke the credentials issued under this delegation
+    pub expire_at: Option<u64>, // Unix timestamps in milliseconds
+}
+
+impl Delegation {
+    pub fn is_expired_at(&self, timestamp: u64) -> bool {
+        matches!(self.expire_at, Some(expire_at) if expire_at <= timestamp)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        matches!(self.max_credentials, Some(max) if self.issued_credentials >= max)
+    }
+
+    // A credential issued under the delegation may not outlive it.
+    pub fn covers_expiry(&self, credential_expire_at: Option<u64>) -> bool {
+        match (self.expire_at, credential_expire_at) {
+            (Some(expire_at), Some(credential_expire_at)) => credential_expire_at <= expire_at,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+pub const CREDENTIAL_SCHEMA_SEED: &[u8] = b"credential_schema";
+
+// Claims carried by a type of credential, published by the DID at `author`. A schema is
+// immutable: authors publish changes as a new `schema_version` and deprecate the old one,
+// which credentials then can no longer be issued against.
+#[account]
+#[derive(Debug)]
+pub struct CredentialSchema {
+    pub version: u8,
+    pub author: Pubkey, // `DidDocument` whose controller manages the schema
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub name: String,
+    pub fields: Vec<SchemaField>,
+    pub content_hash: [u8; 32], // sha256 of the full JSON Schema document, kept off-chain
+    pub deprecated: bool,
+}
+
+impl CredentialSchema {
+    pub fn space(schema_id: &str, name: &str, fields: &[SchemaField]) -> usize {
+        8 + 1 + 32 + (4 + schema_id.len()) + 4 + (4 + name.len())
+            + (4 + fields.iter().map(|field| 4 + field.name.len() + 1).sum::<usize>())
+            + 32
+            + 1
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub field_type: SchemaFieldType,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SchemaFieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Date, // ISO 8601, as in `issuanceDate`
+    Bytes,
+}
+
+pub const VERIFICATION_SCHEMA_SEED: &[u8] = b"verification_schema";
+
+// Presentation request published by the DID at `verifier`, the on-chain counterpart of the
+// off-chain `Schema`. Holders fetch it from the verifier's PDA instead of trusting whoever
+// sends it, and a `vc_schema_field_check_verifier` proof is accepted only if its
+// `schemaCheckRoot` is one of `check_roots`. Immutable like `CredentialSchema`.
+#[account]
+#[derive(Debug)]
+pub struct VerificationSchema {
+    pub version: u8,
+    pub verifier: Pubkey, // `DidDocument` whose controller manages the schema
+    pub schema_id: String,
+    pub schema_version: u32,
+    pub name: String,
+    pub check_roots: Vec<[u8; 32]>, // `fieldMerkleRoot` of each credential check, big-endian
+    pub requested_fields: Vec<String>, // `<check index>:<field>`, as in `Schema.requestedFields`
+    pub deprecated: bool,
+}
+
+impl VerificationSchema {
+    pub fn space(
+        schema_id: &str,
+        name: &str,
+        check_roots: &[[u8; 32]],
+        requested_fields: &[String],
+    ) -> usize {
+        8 + 1 + 32 + (4 + schema_id.len()) + 4 + (4 + name.len())
+            + (4 + check_roots.len() * 32)
+            + (4 + requested_fields.iter().map(|field| 4 + field.len()).sum::<usize>())
+            + 1
+    }
+
+    /// Whether a proof against `schema_check_root` answers this request.
+    pub fn has_check_root(&self, schema_check_root: &[u8; 32]) -> bool {
+        !self.deprecated && self.check_roots.contains(schema_check_root)
+    }
+}
+
+pub const ACCREDITATION_SEED: &[u8] = b"accreditation";
+// Longest accreditation chain below a root authority, bounding what `check_accreditation`
+// walks.
+pub const MAX_ACCREDITATION_DEPTH: u8 = 3;
+
+// Trust granted by the DID at `authority` to the DID at `issuer` for credentials of `scope`.
+// Any DID may accredit; verifiers pick the root authorities they trust and
+// `check_accreditation` follows accreditations from an issuer up to one of them.
+#[account]
+#[derive(Debug)]
+pub struct Accreditation {
+    pub version: u8,
+    pub authority: Pubkey, // `DidDocument` granting the accreditation
+    pub issuer: Pubkey,    // `DidDocument` accredited
+    pub scope: AccreditationScope,
+    pub max_depth: u8, // levels of accreditations `issuer` may grant below it for `scope`
+    pub expire_at: Option<u64>, // Unix timestamps in milliseconds
+}
+
+impl Accreditation {
+    pub fn space(scope: &AccreditationScope) -> usize {
+        8 + 1 + 32 + 32 + (1 + scope.space()) + 1 + (1 + 8)
+    }
+
+    pub fn is_expired_at(&self, timestamp: u64) -> bool {
+        matches!(self.expire_at, Some(expire_at) if expire_at <= timestamp)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum AccreditationScope {
+    CredentialType(String), // `type` entry of the credential, e.g. `MedicalLicense`
+    Schema(Pubkey),         // `CredentialSchema` the credentials are issued against
+}
+
+impl AccreditationScope {
+    pub fn space(&self) -> usize {
+        match self {
+            AccreditationScope::CredentialType(credential_type) => 4 + credential_type.len(),
+            AccreditationScope::Schema(_) => 32,
+        }
+    }
+
+    // Seed of the `Accreditation` PDA.
+    pub fn seed(&self) -> solana_program::keccak::Hash {
+        match self {
+            AccreditationScope::CredentialType(credential_type) => solana_program::keccak::hash(
+                [b"TYPE".as_ref(), credential_type.as_bytes()].concat().as_slice(),
+            ),
+            AccreditationScope::Schema(schema) => solana_program::keccak::hash(
+                [b"SCHEMA".as_ref(), schema.as_ref()].concat().as_slice(),
+            ),
+        }
+    }
+}
+
+pub const REGISTRY_CONFIG_SEED: &[u8] = b"registry_config";
+pub const MAX_ALLOWED_KEY_TYPES: usize = 8;
+pub const REGISTRY_CONFIG_SPACE: usize =
+    8 + 1 + 32 + (4 + MAX_ALLOWED_KEY_TYPES * (4 + MAX_KEY_TYPE_LENGTH)) + (1 + 8) + 1;
+
+// Program-wide settings, a singleton created by the program upgrade authority.
+// Issuance fees are collected in this account until the admin withdraws them.
+#[account]
+#[derive(Debug)]
+pub struct RegistryConfig {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub allowed_key_types: Vec<String>, // accepted `VerificationMethod` types
+    pub issuance_fee: Option<u64>,      // lamports charged by `add_credential`
+    pub paused: bool,                   // stops every instruction but the admin ones
+}
+
+impl RegistryConfig {
+    pub fn is_key_type_allowed(&self, r#type: &str) -> bool {
+        self.allowed_key_types.iter().any(|allowed| allowed == r#type)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ConfigUpdate {
+    Admin(Pubkey),
+    AllowedKeyTypes(Vec<String>),
+    IssuanceFee(Option<u64>),
+    Paused(bool),
+}
+
+pub fn push_index_entry(entries: &mut Vec<Pubkey>, entry: Pubkey) -> Result<()> {
+    require!(
+        entries.len() < INDEX_PAGE_CAPACITY,
+        crate::error::VerifiableDataRegistryError::IndexPageFull
+    );
+    entries.push(entry);
+    Ok(())
+}
+
+// Accounts from before indexes were kept are in none, removing them is a no-op.
+pub fn remove_index_entry(entries: &mut Vec<Pubkey>, entry: &Pubkey) {
+    if let Some(position) = entries.iter().position(|e| e == entry) {
+        entries.swap_remove(position);
+    }
+}
+
+pub const AUTH_CHALLENGE_SEED: &[u8] = b"auth_challenge";
+pub const AUTH_CHALLENGE_SPACE: usize = 8 + 1 + 32 + 32 + 32 + 32 + 8 + 32;
+// Time a challenge can be answered in, in milliseconds.
+pub const AUTH_CHALLENGE_TTL: u64 = 5 * 60 * 1000;
+
+// Nonce issued by the program to `session_key` for proving control of the DID at
+// `did_document` to `audience`, answered by `create_auth_session`.
+#[account]
+#[derive(Debug)]
+pub struct AuthChallenge {
+    pub version: u8,
+    pub did_document: Pubkey,
+    pub session_key: Pubkey,
+    pub audience: Pubkey, // program or dApp the session is meant for
+    pub nonce: [u8; 32],
+    pub expire_at: u64, // Unix timestamps in milliseconds
+    pub payer: Pubkey,  // receives the rent back when the challenge is answered
+}
+
+impl AuthChallenge {
+    // Hash signed by a secp256k1 `Authentication` key to answer the challenge.
+    pub fn message_hash(&self) -> [u8; 32] {
+        solana_program::keccak::hash(
+            [
+                self.nonce.as_ref(),
+                self.audience.as_ref(),
+                self.session_key.as_ref(),
+                b"AUTH",
+            ]
+            .concat()
+            .as_slice(),
+        )
+        .to_bytes()
+    }
+}
+
+pub const AUTH_SESSION_SEED: &[u8] = b"auth_session";
+pub const AUTH_SESSION_SPACE: usize = 8 + 1 + 32 + 32 + 32 + 8 + 32;
+// Longest session `create_auth_session` opens, in milliseconds.
+pub const MAX_AUTH_SESSION_DURATION: u64 = 24 * 60 * 60 * 1000;
+
+// Proof that `session_key` was handed a login to `audience` by an `Authentication` key of the
+// DID at `did_document`. Other programs read it at its PDA or call `check_auth_session`.
+#[account]
+#[derive(Debug)]
+pub struct AuthSession {
+    pub version: u8,
+    pub did_document: Pubkey,
+    pub session_key: Pubkey,
+    pub audience: Pubkey,
+    pub expire_at: u64, // Unix timestamps in milliseconds
+    pub payer: Pubkey,  // receives the rent back when the session is closed
+}
+
+impl AuthSession {
+    pub fn is_valid_for(&self, audience: &Pubkey, timestamp: u64) -> bool {
+        self.audience == *audience && timestamp < self.expire_at
+    }
+}
@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use solana_program::{keccak, secp256k1_recover};
+
+use crate::error::VerifiableDataRegistryError;
+use crate::state::*;
+use crate::webauthn::{verify_webauthn_assertion, P256_PUBLIC_KEY_LENGTH};
+
+/// Current cluster time in Unix milliseconds, the unit used by every timestamp in `state.rs`.
+pub fn current_timestamp() -> Result<u64> {
+    let unix_timestamp = Clock::get()?.unix_timestamp;
+    Ok((unix_timestamp.max(0) as u64).saturating_mul(1000))
+}
+
+/// Appends a change of `did_document` to its change log, filling in a log created by
+/// `init_if_needed`.
+pub fn record_did_change(
+    change_log: &mut Account<DidChangeLog>,
+    did_document: Pubkey,
+    op: DidChangeOp,
+    key_id: String,
+    key: &[u8],
+) -> Result<()> {
+    change_log.init_or_check(did_document)?;
+    change_log.record(op, key_id, keccak::hash(key).to_bytes(), Clock::get()?.slot);
+    Ok(())
+}
+
+/// Moves the issuance fee set in `config`, if any, from `payer` to the config account.
+pub fn charge_issuance_fee<'info>(
+    config: &Account<'info, RegistryConfig>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if let Some(issuance_fee) = config.issuance_fee {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: config.to_account_info(),
+                },
+            ),
+            issuance_fee,
+        )?;
+    }
+    Ok(())
+}
+
+/// Address of the schema a credential declares, after checking that credentials can still
+/// be issued against it.
+pub fn issuable_schema(
+    credential_schema: Option<&Account<CredentialSchema>>,
+) -> Result<Option<Pubkey>> {
+    credential_schema
+        .map(|credential_schema| {
+            require!(
+                credential_schema.version == CredentialSchema::VERSION,
+                VerifiableDataRegistryError::AccountVersionMismatch
+            );
+            require!(
+                !credential_schema.deprecated,
+                VerifiableDataRegistryError::CredentialSchemaDeprecated
+            );
+            Ok(credential_schema.key())
+        })
+        .transpose()
+}
+
+/// Guardian entry `guardian` signs for: the DID of `guardian_did_document` when one is passed,
+/// which it must control, or else its own key.
+pub fn signing_guardian(
+    guardian: &Signer,
+    guardian_did_document: Option<&Account<DidDocument>>,
+) -> Result<Guardian> {
+    match guardian_did_document {
+        Some(did_document) => {
+            require_keys_eq!(
+                did_document.controller,
+                guardian.key(),
+                VerifiableDataRegistryError::Unauthorized
+            );
+            Ok(Guardian::Did(did_document.key()))
+        }
+        None => Ok(Guardian::Key(guardian.key())),
+    }
+}
+
+const SECP256K1_KEY_TYPE: &str = "EcdsaSecp256k1VerificationKey2019";
+
+/// Verification method types whose key is a Solana public key, used by signing the
+/// transaction instead of a message.
+pub const SOLANA_KEY_TYPES: [&str; 2] = ["SolanaMethod2021", "Ed25519VerificationKey2020"];
+
+/// Verification method type of the X25519 keys used for `KeyAgreement`, the only
+/// relationship they may be bound to.
+pub const X25519_KEY_TYPE: &str = "X25519KeyAgreementKey2020";
+
+/// Verification method type of passkeys, P-256 keys used through WebAuthn assertions.
+pub const P256_KEY_TYPE: &str = "JsonWebKey2020";
+
+/// Multicodec prefix of the ed25519 keys of `Ed25519VerificationKey2020` methods.
+pub const ED25519_MULTICODEC_PREFIX: [u8; 2] = [0xed, 0x01];
+/// Multicodec prefix of the keys of `X25519KeyAgreementKey2020` methods.
+pub const X25519_MULTICODEC_PREFIX: [u8; 2] = [0xec, 0x01];
+/// Multicodec prefix (varint of 0x1200) of the compressed keys of `JsonWebKey2020` methods.
+pub const P256_MULTICODEC_PREFIX: [u8; 2] = [0x80, 0x24];
+
+/// Solana public key encoded in `public_key_multibase`, either bare or with the ed25519
+/// multicodec prefix.
+pub fn solana_public_key(public_key_multibase: &str) -> Option<Pubkey> {
+    curve25519_key(public_key_multibase, ED25519_MULTICODEC_PREFIX).map(Pubkey::new_from_array)
+}
+
+/// X25519 public key encoded in `public_key_multibase`, either bare or with the x25519
+/// multicodec prefix.
+pub fn x25519_public_key(public_key_multibase: &str) -> Option<[u8; 32]> {
+    curve25519_key(public_key_multibase, X25519_MULTICODEC_PREFIX)
+}
+
+/// Compressed P-256 public key encoded in `public_key_multibase`, either bare or with the
+/// p256 multicodec prefix.
+pub fn p256_public_key(public_key_multibase: &str) -> Option<[u8; P256_PUBLIC_KEY_LENGTH]> {
+    let (_, bytes) = multibase::decode(public_key_multibase).ok()?;
+    let key = match bytes.len() {
+        P256_PUBLIC_KEY_LENGTH => &bytes[..],
+        35 => bytes.strip_prefix(P256_MULTICODEC_PREFIX.as_slice())?,
+        _ => return None,
+    };
+    let key: [u8; P256_PUBLIC_KEY_LENGTH] = key.try_into().ok()?;
+    matches!(key[0], 0x02 | 0x03).then_some(key)
+}
+
+/// Ed25519 or X25519 public key encoded in `public_key_multibase`, either bare or with
+/// `multicodec_prefix`.
+pub fn curve25519_key(public_key_multibase: &str, multicodec_prefix: [u8; 2]) -> Option<[u8; 32]> {
+    let (_, bytes) = multibase::decode(public_key_multibase).ok()?;
+    let key = match bytes.len() {
+        32 => &bytes[..],
+        34 => bytes.strip_prefix(multicodec_prefix.as_slice())?,
+        _ => return None,
+    };
+    key.try_into().ok()
+}
+
+/// Checks that the key of `verification_method` authenticated the issuer, that its type is
+/// still allowed by `config`, and that both the key and its `authentication` relationship
+/// are valid at `now`. Secp256k1 keys need a `secp256k1_signature` over `message_hash`;
+/// Solana keys need `signer` to be that key; passkeys need a WebAuthn assertion of
+/// `message_hash` and the instructions sysvar.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_authentication(
+    verification_method: &VerificationMethod,
+    authentication: &VerificationRelationship,
+    config: &RegistryConfig,
+    message_hash: &[u8],
+    secp256k1_signature: Option<&Secp256k1Signature>,
+    signer: Option<&Signer>,
+    webauthn: Option<(&WebAuthnAssertion, &AccountInfo)>,
+    now: u64,
+) -> Result<()> {
+    require!(
+        verification_method.version == VerificationMethod::VERSION
+            && authentication.version == VerificationRelationship::VERSION,
+        VerifiableDataRegistryError::AccountVersionMismatch
+    );
+    // Key types the admin disallowed stop authenticating, not only being added.
+    require!(
+        config.is_key_type_allowed(&verification_method.r#type),
+        VerifiableDataRegistryError::NotSupportKeyType
+    );
+    require!(
+        verification_method.is_valid_at(now),
+        VerifiableDataRegistryError::VerificationMethodNotValid
+    );
+    require!(
+        authentication.is_valid_at(now),
+        VerifiableDataRegistryError::VerificationRelationshipNotValid
+    );
+
+    let r#type = verification_method.r#type.as_str();
+    if r#type == SECP256K1_KEY_TYPE {
+        let secp256k1_signature =
+            secp256k1_signature.ok_or(VerifiableDataRegistryError::Unauthorized)?;
+        verify_secp256k1_signature(verification_method, message_hash, secp256k1_signature)
+    } else if SOLANA_KEY_TYPES.contains(&r#type) {
+        let signer = signer.ok_or(VerifiableDataRegistryError::Unauthorized)?;
+        let key = solana_public_key(&verification_method.public_key_multibase)
+            .ok_or(VerifiableDataRegistryError::InvalidSolanaPublicKey)?;
+        require_keys_eq!(signer.key(), key, VerifiableDataRegistryError::Unauthorized);
+        Ok(())
+    } else if r#type == P256_KEY_TYPE {
+        let (assertion, instructions_sysvar) =
+            webauthn.ok_or(VerifiableDataRegistryError::Unauthorized)?;
+        let key = p256_public_key(&verification_method.public_key_multibase)
+            .ok_or(VerifiableDataRegistryError::InvalidP256PublicKey)?;
+        verify_webauthn_assertion(&key, message_hash, assertion, instructions_sysvar)
+    } else {
+        err!(VerifiableDataRegistryError::NotSupportKeyType)
+    }
+}
+
+fn verify_secp256k1_signature(
+    verification_method: &VerificationMethod,
+    message_hash: &[u8],
+    secp256k1_signature: &Secp256k1Signature,
+) -> Result<()> {
+    let signed_pubkey = secp256k1_recover::secp256k1_recover(
+        message_hash,
+        secp256k1_signature.recovery_id,
+        secp256k1_signature.signature.as_ref(),
+    )
+    .map_err(|_| VerifiableDataRegistryError::Unauthorized)?;
+    let (_, verification_pubkey) = multibase::decode(&verification_method.public_key_multibase)
+        .map_err(|_| VerifiableDataRegistryError::InvalidMultibase)?;
+    require!(
+        signed_pubkey.0.to_ascii_lowercase() == verification_pubkey.to_ascii_lowercase(),
+        VerifiableDataRegistryError::Unauthorized
+    );
+    Ok(())
+}
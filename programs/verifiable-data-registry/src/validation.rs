@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+
+use crate::error::VerifiableDataRegistryError;
+use crate::state::{AccreditationScope, CredentialHolder, SchemaField, MAX_ACCREDITATION_DEPTH};
+
+// Identifiers end up in account space and PDA seeds, so they are bounded and restricted to
+// ASCII: DID Core requires anything else to be percent-encoded anyway.
+pub const MAX_DID_LENGTH: usize = 128;
+pub const MAX_KEY_FRAGMENT_LENGTH: usize = 64;
+pub const MAX_KEY_ID_LENGTH: usize = MAX_DID_LENGTH + 1 + MAX_KEY_FRAGMENT_LENGTH;
+pub const MAX_KEY_TYPE_LENGTH: usize = 64;
+pub const MAX_PUBLIC_KEY_MULTIBASE_LENGTH: usize = 256;
+pub const MAX_CREDENTIAL_ID_LENGTH: usize = 128;
+pub const MAX_ALSO_KNOWN_AS: usize = 4;
+pub const MAX_SCHEMA_ID_LENGTH: usize = 128;
+pub const MAX_SCHEMA_NAME_LENGTH: usize = 64;
+pub const MAX_SCHEMA_FIELDS: usize = 32;
+pub const MAX_SCHEMA_FIELD_NAME_LENGTH: usize = 64;
+pub const MAX_SCHEMA_CHECKS: usize = 8;
+pub const MAX_REQUESTED_FIELDS: usize = 32;
+pub const MAX_CREDENTIAL_TYPE_LENGTH: usize = 64;
+
+/// Checks `did` against the DID Core ABNF:
+/// `did = "did:" method-name ":" method-specific-id`.
+pub fn validate_did(did: &str) -> Result<()> {
+    require!(did.is_ascii(), VerifiableDataRegistryError::NonAsciiIdentifier);
+    require!(
+        did.len() <= MAX_DID_LENGTH,
+        VerifiableDataRegistryError::DidTooLong
+    );
+    require!(is_did(did), VerifiableDataRegistryError::InvalidDid);
+    Ok(())
+}
+
+/// Checks the `alsoKnownAs` entries of `did`: a few distinct DIDs other than `did` itself.
+pub fn validate_also_known_as(did: &str, also_known_as: &[String]) -> Result<()> {
+    require!(
+        also_known_as.len() <= MAX_ALSO_KNOWN_AS,
+        VerifiableDataRegistryError::TooManyAlsoKnownAs
+    );
+    for (i, alias) in also_known_as.iter().enumerate() {
+        validate_did(alias)?;
+        require!(
+            alias != did && !also_known_as[..i].contains(alias),
+            VerifiableDataRegistryError::InvalidAlsoKnownAs
+        );
+    }
+    Ok(())
+}
+
+/// Checks that `key_id` is a DID URL `<did>#<fragment>` of `did`.
+pub fn validate_key_id(did: &str, key_id: &str) -> Result<()> {
+    require!(key_id.is_ascii(), VerifiableDataRegistryError::NonAsciiIdentifier);
+    require!(
+        key_id.len() <= MAX_KEY_ID_LENGTH,
+        VerifiableDataRegistryError::KeyIdTooLong
+    );
+    let fragment = key_id
+        .strip_prefix(did)
+        .and_then(|rest| rest.strip_prefix('#'))
+        .ok_or(VerifiableDataRegistryError::InvalidKeyId)?;
+    require!(
+        fragment.len() <= MAX_KEY_FRAGMENT_LENGTH,
+        VerifiableDataRegistryError::KeyIdTooLong
+    );
+    require!(
+        !fragment.is_empty() && is_fragment(fragment),
+        VerifiableDataRegistryError::InvalidKeyId
+    );
+    Ok(())
+}
+
+/// Checks a verification method type such as `EcdsaSecp256k1VerificationKey2019`.
+pub fn validate_key_type(r#type: &str) -> Result<()> {
+    require!(r#type.is_ascii(), VerifiableDataRegistryError::NonAsciiIdentifier);
+    require!(
+        r#type.len() <= MAX_KEY_TYPE_LENGTH,
+        VerifiableDataRegistryError::KeyTypeTooLong
+    );
+    require!(
+        !r#type.is_empty() && r#type.bytes().all(|b| b.is_ascii_alphanumeric()),
+        VerifiableDataRegistryError::InvalidKeyType
+    );
+    Ok(())
+}
+
+pub fn validate_public_key_multibase(public_key_multibase: &str) -> Result<()> {
+    require!(
+        public_key_multibase.is_ascii(),
+        VerifiableDataRegistryError::NonAsciiIdentifier
+    );
+    require!(
+        public_key_multibase.len() <= MAX_PUBLIC_KEY_MULTIBASE_LENGTH,
+        VerifiableDataRegistryError::PublicKeyMultibaseTooLong
+    );
+    Ok(())
+}
+
+/// Credential ids are opaque (URLs, URNs, UUIDs...): any non-empty run of visible ASCII.
+pub fn validate_credential_id(credential_id: &str) -> Result<()> {
+    require!(
+        credential_id.is_ascii(),
+        VerifiableDataRegistryError::NonAsciiIdentifier
+    );
+    require!(
+        credential_id.len() <= MAX_CREDENTIAL_ID_LENGTH,
+        VerifiableDataRegistryError::CredentialIdTooLong
+    );
+    require!(
+        !credential_id.is_empty() && credential_id.bytes().all(|b| b.is_ascii_graphic()),
+        VerifiableDataRegistryError::InvalidCredentialId
+    );
+    Ok(())
+}
+
+/// A delegation scope is a possibly empty schema id prefix, so the same character set.
+pub fn validate_schema_id_prefix(prefix: &str) -> Result<()> {
+    require!(prefix.is_ascii(), VerifiableDataRegistryError::NonAsciiIdentifier);
+    require!(
+        prefix.len() <= MAX_SCHEMA_ID_LENGTH && prefix.bytes().all(|b| b.is_ascii_graphic()),
+        VerifiableDataRegistryError::InvalidSchemaId
+    );
+    Ok(())
+}
+
+/// Holder commitments are opaque hashes, only a holder DID has a format to check.
+pub fn validate_credential_holder(holder: &CredentialHolder) -> Result<()> {
+    match holder {
+        CredentialHolder::Did(did) => validate_did(did),
+        CredentialHolder::KeccakCommitment(_) | CredentialHolder::PoseidonCommitment(_) => Ok(()),
+    }
+}
+
+/// Checks a credential schema: an opaque id like credential ids, a printable name and a few
+/// distinct field names as they appear in `credentialSubject`.
+pub fn validate_credential_schema(
+    schema_id: &str,
+    name: &str,
+    fields: &[SchemaField],
+) -> Result<()> {
+    validate_schema_id_and_name(schema_id, name)?;
+    require!(
+        !fields.is_empty() && fields.len() <= MAX_SCHEMA_FIELDS,
+        VerifiableDataRegistryError::InvalidSchemaFieldCount
+    );
+    for (i, field) in fields.iter().enumerate() {
+        require!(
+            is_field_name(&field.name)
+                && !fields[..i].iter().any(|other| other.name == field.name),
+            VerifiableDataRegistryError::InvalidSchemaField
+        );
+    }
+    Ok(())
+}
+
+/// Checks a verification schema: named like credential schemas, a few credential checks and
+/// distinct requested fields, each pointing at one of the checks.
+pub fn validate_verification_schema(
+    schema_id: &str,
+    name: &str,
+    check_roots: &[[u8; 32]],
+    requested_fields: &[String],
+) -> Result<()> {
+    validate_schema_id_and_name(schema_id, name)?;
+    require!(
+        !check_roots.is_empty() && check_roots.len() <= MAX_SCHEMA_CHECKS,
+        VerifiableDataRegistryError::InvalidSchemaCheckCount
+    );
+    require!(
+        requested_fields.len() <= MAX_REQUESTED_FIELDS,
+        VerifiableDataRegistryError::InvalidRequestedField
+    );
+    for (i, requested_field) in requested_fields.iter().enumerate() {
+        let valid = match requested_field.split_once(':') {
+            Some((check_index, field)) => {
+                check_index.bytes().all(|b| b.is_ascii_digit())
+                    && matches!(check_index.parse::<usize>(), Ok(index) if index < check_roots.len())
+                    && is_field_name(field)
+            }
+            None => false,
+        };
+        require!(
+            valid && !requested_fields[..i].contains(requested_field),
+            VerifiableDataRegistryError::InvalidRequestedField
+        );
+    }
+    Ok(())
+}
+
+/// Checks what an accreditation covers and how many levels of accreditations it allows below.
+pub fn validate_accreditation(scope: &AccreditationScope, max_depth: u8) -> Result<()> {
+    if let AccreditationScope::CredentialType(credential_type) = scope {
+        require!(
+            credential_type.is_ascii(),
+            VerifiableDataRegistryError::NonAsciiIdentifier
+        );
+        require!(
+            !credential_type.is_empty()
+                && credential_type.len() <= MAX_CREDENTIAL_TYPE_LENGTH
+                && credential_type.bytes().all(|b| b.is_ascii_graphic()),
+            VerifiableDataRegistryError::InvalidCredentialType
+        );
+    }
+    require!(
+        max_depth < MAX_ACCREDITATION_DEPTH,
+        VerifiableDataRegistryError::InvalidAccreditationDepth
+    );
+    Ok(())
+}
+
+fn validate_schema_id_and_name(schema_id: &str, name: &str) -> Result<()> {
+    require!(
+        schema_id.is_ascii() && name.is_ascii(),
+        VerifiableDataRegistryError::NonAsciiIdentifier
+    );
+    require!(
+        !schema_id.is_empty()
+            && schema_id.len() <= MAX_SCHEMA_ID_LENGTH
+            && schema_id.bytes().all(|b| b.is_ascii_graphic()),
+        VerifiableDataRegistryError::InvalidSchemaId
+    );
+    require!(
+        !name.is_empty()
+            && name.len() <= MAX_SCHEMA_NAME_LENGTH
+            && name.bytes().all(|b| b.is_ascii_graphic() || b == b' '),
+        VerifiableDataRegistryError::InvalidSchemaName
+    );
+    Ok(())
+}
+
+fn is_field_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_SCHEMA_FIELD_NAME_LENGTH
+        && name.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn is_did(did: &str) -> bool {
+    let Some(rest) = did.strip_prefix("did:") else {
+        return false;
+    };
+    let Some((method_name, method_specific_id)) = rest.split_once(':') else {
+        return false;
+    };
+    // method-name = 1*method-char, method-char = %x61-7A / DIGIT
+    !method_name.is_empty()
+        && method_name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        // method-specific-id = *( *idchar ":" ) 1*idchar
+        && !method_specific_id.is_empty()
+        && !method_specific_id.ends_with(':')
+        && is_pct_encoded_run(method_specific_id, |b| {
+            b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b':')
+        })
+}
+
+// fragment = *( pchar / "/" / "?" ) from RFC 3986
+fn is_fragment(fragment: &str) -> bool {
+    is_pct_encoded_run(fragment, |b| {
+        b.is_ascii_alphanumeric()
+            || matches!(
+                b,
+                b'-' | b'.'
+                    | b'_'
+                    | b'~'
+                    | b'!'
+                    | b'$'
+                    | b'&'
+                    | b'\''
+                    | b'('
+                    | b')'
+                    | b'*'
+                    | b'+'
+                    | b','
+                    | b';'
+                    | b'='
+                    | b':'
+                    | b'@'
+                    | b'/'
+                    | b'?'
+            )
+    })
+}
+
+// Every byte is allowed by `allowed` or part of a `pct-encoded` triplet.
+fn is_pct_encoded_run(value: &str, allowed: impl Fn(u8) -> bool) -> bool {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            match bytes.get(i + 1..i + 3) {
+                Some([high, low]) if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                    i += 3
+                }
+                _ => return false,
+            }
+        } else if allowed(bytes[i]) {
+            i += 1;
+        } else {
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hash;
+use solana_program::pubkey;
+use solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::error::VerifiableDataRegistryError;
+use crate::state::WebAuthnAssertion;
+
+/// Native program verifying secp256r1 (P-256) signatures, the curve of passkeys.
+pub const SECP256R1_PROGRAM_ID: Pubkey = pubkey!("Secp256r1SigVerify1111111111111111111111111");
+
+pub const P256_PUBLIC_KEY_LENGTH: usize = 33; // compressed
+pub const P256_SIGNATURE_LENGTH: usize = 64; // r || s
+
+// Layout of the secp256r1 instruction data: a signature count, a padding byte, then per
+// signature seven u16 offsets: signature, its instruction, public key, its instruction,
+// message, message size and its instruction.
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LENGTH: usize = 14;
+// Instruction index standing for the secp256r1 instruction itself.
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// rpIdHash (32 bytes), flags (1 byte) and signCount (4 bytes).
+const AUTHENTICATOR_DATA_MIN_LENGTH: usize = 37;
+const USER_PRESENT: u8 = 0x01;
+// Browsers serialize these members first and in this order, so the challenge can be checked
+// without a JSON parser, as in the "limited verification algorithm" of WebAuthn.
+const CLIENT_DATA_PREFIX: &[u8] = br#"{"type":"webauthn.get","challenge":""#;
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Checks that `assertion` answers `message_hash` as its challenge with the user present, and
+/// that a secp256r1 instruction earlier in the transaction verified the signature of
+/// `public_key` over it. The relying party id is not checked: the challenge alone ties the
+/// assertion to the operation.
+pub fn verify_webauthn_assertion(
+    public_key: &[u8; P256_PUBLIC_KEY_LENGTH],
+    message_hash: &[u8],
+    assertion: &WebAuthnAssertion,
+    instructions_sysvar: &AccountInfo,
+) -> Result<()> {
+    let authenticator_data = &assertion.authenticator_data;
+    require!(
+        authenticator_data.len() >= AUTHENTICATOR_DATA_MIN_LENGTH
+            && authenticator_data[32] & USER_PRESENT != 0,
+        VerifiableDataRegistryError::InvalidWebAuthnAssertion
+    );
+    let challenge = assertion
+        .client_data_json
+        .strip_prefix(CLIENT_DATA_PREFIX)
+        .and_then(|rest| rest.strip_prefix(base64url(message_hash).as_slice()));
+    require!(
+        matches!(challenge, Some(rest) if rest.starts_with(b"\"")),
+        VerifiableDataRegistryError::InvalidWebAuthnAssertion
+    );
+
+    let signed_message = [
+        authenticator_data.as_slice(),
+        hash(&assertion.client_data_json).as_ref(),
+    ]
+    .concat();
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index.into(), instructions_sysvar)?;
+        if instruction.program_id == SECP256R1_PROGRAM_ID
+            && verifies(&instruction.data, index, public_key, &signed_message)
+        {
+            return Ok(());
+        }
+    }
+    err!(VerifiableDataRegistryError::Unauthorized)
+}
+
+// Whether one of the signatures a secp256r1 instruction, at `index`, verified is over
+// `message` by `public_key`. They and the signature must be read from that instruction's own
+// data.
+fn verifies(data: &[u8], index: u16, public_key: &[u8], message: &[u8]) -> bool {
+    let count = data.first().copied().unwrap_or(0) as usize;
+    (0..count).any(|i| {
+        let start = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_LENGTH;
+        let offsets = match data.get(start..start + SIGNATURE_OFFSETS_LENGTH) {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        let offset = |n: usize| u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]) as usize;
+        let in_itself = |n: usize| {
+            let instruction_index = offset(n) as u16;
+            instruction_index == CURRENT_INSTRUCTION || instruction_index == index
+        };
+        in_itself(1)
+            && in_itself(3)
+            && in_itself(6)
+            && data.get(offset(0)..offset(0) + P256_SIGNATURE_LENGTH).is_some()
+            && data.get(offset(2)..offset(2) + P256_PUBLIC_KEY_LENGTH) == Some(public_key)
+            && offset(5) == message.len()
+            && data.get(offset(4)..offset(4) + offset(5)) == Some(message)
+    })
+}
+
+// Unpadded base64url, the encoding of the WebAuthn challenge.
+fn base64url(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(bytes.len() * 4 / 3 + 1);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64URL_ALPHABET[(n >> (18 - 6 * i)) as usize & 63]);
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedInstruction,
+    };
+
+    use super::*;
+
+    const PUBLIC_KEY: [u8; P256_PUBLIC_KEY_LENGTH] = [2; P256_PUBLIC_KEY_LENGTH];
+    const MESSAGE_HASH: [u8; 32] = [7; 32];
+    // Data of a secp256r1 instruction with one signature: offsets, public key, signature and
+    // signed message.
+    const DATA_START: usize = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LENGTH;
+    const PUBLIC_KEY_OFFSET: usize = DATA_START;
+    const SIGNATURE_OFFSET: usize = PUBLIC_KEY_OFFSET + P256_PUBLIC_KEY_LENGTH;
+    const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + P256_SIGNATURE_LENGTH;
+
+    fn assertion(flags: u8, client_data_json: &[u8]) -> WebAuthnAssertion {
+        WebAuthnAssertion {
+            authenticator_data: [[9u8; 32].as_slice(), &[flags, 0, 0, 0, 1]].concat(),
+            client_data_json: client_data_json.to_vec(),
+        }
+    }
+
+    fn client_data_json(challenge: &[u8]) -> Vec<u8> {
+        [
+            CLIENT_DATA_PREFIX,
+            base64url(challenge).as_slice(),
+            br#"","origin":"https://zuni.app"}"#,
+        ]
+        .concat()
+    }
+
+    fn signed_message(assertion: &WebAuthnAssertion) -> Vec<u8> {
+        [
+            assertion.authenticator_data.as_slice(),
+            hash(&assertion.client_data_json).as_ref(),
+        ]
+        .concat()
+    }
+
+    // Offsets in the order of the layout, the instruction indexes pointing at itself.
+    fn offsets(message: &[u8]) -> [u16; 7] {
+        [
+            SIGNATURE_OFFSET as u16,
+            CURRENT_INSTRUCTION,
+            PUBLIC_KEY_OFFSET as u16,
+            CURRENT_INSTRUCTION,
+            MESSAGE_OFFSET as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ]
+    }
+
+    fn secp256r1_data(offsets: [u16; 7], message: &[u8]) -> Vec<u8> {
+        let mut data = vec![1, 0];
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(&PUBLIC_KEY);
+        data.extend_from_slice(&[5; P256_SIGNATURE_LENGTH]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    // Checks `assertion` in a transaction made of a secp256r1 instruction with `data`, then
+    // the registry instruction.
+    fn verify(assertion: &WebAuthnAssertion, data: &[u8]) -> Result<()> {
+        let program_id = crate::ID;
+        let mut sysvar_data = construct_instructions_data(&[
+            BorrowedInstruction {
+                program_id: &SECP256R1_PROGRAM_ID,
+                accounts: Vec::new(),
+                data,
+            },
+            BorrowedInstruction {
+                program_id: &program_id,
+                accounts: Vec::new(),
+                data: &[],
+            },
+        ]);
+        store_current_index(&mut sysvar_data, 1);
+        let key = solana_program::sysvar::instructions::ID;
+        let owner = solana_program::sysvar::ID;
+        let mut lamports = 0;
+        let sysvar = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut sysvar_data,
+            &owner,
+            false,
+            0,
+        );
+        verify_webauthn_assertion(&PUBLIC_KEY, &MESSAGE_HASH, assertion, &sysvar)
+    }
+
+    #[test]
+    fn test_verify_webauthn_assertion() {
+        let assertion = assertion(0x05, &client_data_json(&MESSAGE_HASH));
+        let message = signed_message(&assertion);
+        assert_eq!(verify(&assertion, &secp256r1_data(offsets(&message), &message)), Ok(()));
+        assert_eq!(
+            base64url(&MESSAGE_HASH),
+            b"BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_verify_webauthn_assertion_rejects_wrong_offsets() {
+        let assertion = assertion(0x05, &client_data_json(&MESSAGE_HASH));
+        let message = signed_message(&assertion);
+        let data_length = secp256r1_data(offsets(&message), &message).len() as u16;
+        // Signature past the data, public key and message shifted, message cut short.
+        for (n, offset) in [
+            (0, data_length),
+            (2, PUBLIC_KEY_OFFSET as u16 + 1),
+            (4, MESSAGE_OFFSET as u16 + 1),
+            (5, message.len() as u16 - 1),
+        ] {
+            let mut offsets = offsets(&message);
+            offsets[n] = offset;
+            assert_eq!(
+                verify(&assertion, &secp256r1_data(offsets, &message)),
+                Err(VerifiableDataRegistryError::Unauthorized.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_webauthn_assertion_rejects_data_of_other_instructions() {
+        let assertion = assertion(0x05, &client_data_json(&MESSAGE_HASH));
+        let message = signed_message(&assertion);
+        for n in [1, 3, 6] {
+            let mut offsets = offsets(&message);
+            offsets[n] = 1; // the registry instruction
+            assert_eq!(
+                verify(&assertion, &secp256r1_data(offsets, &message)),
+                Err(VerifiableDataRegistryError::Unauthorized.into())
+            );
+        }
+        // Index 0 is the secp256r1 instruction itself.
+        let mut offsets = offsets(&message);
+        offsets[3] = 0;
+        assert_eq!(verify(&assertion, &secp256r1_data(offsets, &message)), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_webauthn_assertion_rejects_invalid_assertions() {
+        let wrong_challenge = client_data_json(&[8; 32]);
+        let complete = client_data_json(&MESSAGE_HASH);
+        // Ends within the challenge, or right after it without its closing quote.
+        let challenge_end = CLIENT_DATA_PREFIX.len() + base64url(&MESSAGE_HASH).len();
+        for assertion in [
+            assertion(0x05, &wrong_challenge),
+            assertion(0x04, &complete), // user verified but not present
+            assertion(0x05, &complete[..challenge_end - 1]),
+            assertion(0x05, &complete[..challenge_end]),
+            assertion(0x05, &complete[..CLIENT_DATA_PREFIX.len() - 1]),
+        ] {
+            // Even with a matching signature.
+            let message = signed_message(&assertion);
+            assert_eq!(
+                verify(&assertion, &secp256r1_data(offsets(&message), &message)),
+                Err(VerifiableDataRegistryError::InvalidWebAuthnAssertion.into())
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import { ensureRegistryConfig, expectAnchorError } from './utils';
+
+const ANCHOR_ERROR_ISSUER_NOT_ACCREDITED = 'IssuerNotAccredited';
+const ANCHOR_ERROR_INVALID_ACCREDITATION_DEPTH = 'InvalidAccreditationDepth';
+const ANCHOR_ERROR_ACCREDITATION_EXPIRED = 'AccreditationExpired';
+const ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED = 'AccountNotInitialized';
+
+describe('Accreditation', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const credentialType = 'MedicalLicense';
+  const scope = { credentialType: [credentialType] };
+  const ministry = 'did:zuni:solana:ministry';
+  const board = 'did:zuni:solana:board';
+  const clinic = 'did:zuni:solana:clinic';
+  const lab = 'did:zuni:solana:lab';
+
+  const findDidPda = (did: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    )[0];
+
+  const findAccreditationPda = (authorityDid: string, issuerDid: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('accreditation'),
+        findDidPda(authorityDid).toBuffer(),
+        findDidPda(issuerDid).toBuffer(),
+        Buffer.from(keccak_256(Buffer.from('TYPE' + credentialType))),
+      ],
+      program.programId,
+    )[0];
+
+  const accredit = (
+    authorityDid: string,
+    issuerDid: string,
+    maxDepth: number,
+    expireAt: number | null,
+  ) =>
+    program.methods
+      .accreditIssuer(
+        authorityDid,
+        issuerDid,
+        scope,
+        maxDepth,
+        expireAt === null ? null : new anchor.BN(expireAt),
+      )
+      .accounts({
+        accreditation: findAccreditationPda(authorityDid, issuerDid),
+        didDocument: findDidPda(authorityDid),
+        issuerDidDocument: findDidPda(issuerDid),
+      })
+      .rpc();
+
+  // `path` runs from the issuer up to the root.
+  const checkAccreditation = (path: string[]) =>
+    program.methods
+      .checkAccreditation(path[path.length - 1], path[0], scope)
+      .accounts({
+        rootDidDocument: findDidPda(path[path.length - 1]),
+        issuerDidDocument: findDidPda(path[0]),
+        credentialState: null,
+      })
+      .remainingAccounts(
+        path.slice(1).map((authorityDid, i) => ({
+          pubkey: findAccreditationPda(authorityDid, path[i]),
+          isSigner: false,
+          isWritable: false,
+        })),
+      )
+      .rpc();
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+    for (const did of [ministry, board, clinic, lab]) {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: findDidPda(did) })
+        .rpc();
+    }
+  });
+
+  it('Should accept issuers accredited through a chain', async () => {
+    await accredit(ministry, board, 1, null);
+    await accredit(board, clinic, 0, null);
+
+    const accreditation = await program.account.accreditation.fetch(
+      findAccreditationPda(board, clinic),
+    );
+    expect(accreditation.authority.toBase58()).to.equal(
+      findDidPda(board).toBase58(),
+    );
+    expect(accreditation.maxDepth).to.equal(0);
+
+    await checkAccreditation([board, ministry]);
+    await checkAccreditation([clinic, board, ministry]);
+  });
+
+  it('Fail to check beyond the depth an authority allows', async () => {
+    await accredit(clinic, lab, 0, null);
+    await expectAnchorError(
+      checkAccreditation([lab, clinic, board, ministry]),
+      ANCHOR_ERROR_ISSUER_NOT_ACCREDITED,
+    );
+  });
+
+  it('Fail to accredit beyond the maximum depth', async () => {
+    await expectAnchorError(
+      accredit(ministry, lab, 3, null),
+      ANCHOR_ERROR_INVALID_ACCREDITATION_DEPTH,
+    );
+  });
+
+  it('Fail with an expired accreditation', async () => {
+    await accredit(ministry, lab, 0, new Date().getTime() - 1);
+    await expectAnchorError(
+      checkAccreditation([lab, ministry]),
+      ANCHOR_ERROR_ACCREDITATION_EXPIRED,
+    );
+  });
+
+  it('Should stop accepting issuers below a revoked accreditation', async () => {
+    await program.methods
+      .revokeAccreditation(ministry, board, scope)
+      .accounts({
+        accreditation: findAccreditationPda(ministry, board),
+        didDocument: findDidPda(ministry),
+        issuerDidDocument: findDidPda(board),
+      })
+      .rpc();
+    // The closed accreditation no longer exists.
+    await expectAnchorError(
+      checkAccreditation([clinic, board, ministry]),
+      ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED,
+    );
+  });
+});
@@ -0,0 +1,232 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { ec as EC } from 'elliptic';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import {
+  KEY_TYPE,
+  MULTIBASE_PREFIX,
+  ensureRegistryConfig,
+  expectAnchorError,
+  findAuthChallengePda,
+  findAuthSessionPda,
+  findVerificationPdasWithKeyIds,
+  genKeyIds,
+} from './utils';
+
+const ANCHOR_ERROR_UNAUTHORIZED = 'Unauthorized';
+const ANCHOR_ERROR_AUTH_SESSION_AUDIENCE_MISMATCH =
+  'AuthSessionAudienceMismatch';
+
+const ONE_HOUR = 60 * 60 * 1000;
+
+describe('Auth', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const did = 'did:zuni:solana:auth';
+  const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [keccak_256(did)],
+    program.programId,
+  );
+  const [authenticationId] = genKeyIds(1, did);
+  const [verificationPda] = findVerificationPdasWithKeyIds(
+    program.programId,
+    did,
+    [authenticationId],
+  );
+  const [authenticationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(keccak_256(did + 'authentication' + authenticationId))],
+    program.programId,
+  );
+  const ec = new EC('secp256k1');
+  const authenticationKeyPair = ec.genKeyPair();
+  const audience = anchor.web3.Keypair.generate().publicKey;
+
+  const requestChallenge = async (sessionKey: anchor.web3.Keypair) => {
+    const challengePda = findAuthChallengePda(
+      program.programId,
+      didPda,
+      sessionKey.publicKey,
+    );
+    await program.methods
+      .requestAuthChallenge(did, audience)
+      .accounts({
+        challenge: challengePda,
+        didDocument: didPda,
+        sessionKey: sessionKey.publicKey,
+      })
+      .signers([sessionKey])
+      .rpc();
+    return program.account.authChallenge.fetch(challengePda);
+  };
+
+  const login = async (
+    sessionKey: anchor.web3.Keypair,
+    signedAudience: anchor.web3.PublicKey,
+  ) => {
+    const challenge = await requestChallenge(sessionKey);
+    const message = keccak_256(
+      Buffer.concat([
+        Buffer.from(challenge.nonce),
+        signedAudience.toBuffer(),
+        sessionKey.publicKey.toBuffer(),
+        Buffer.from('AUTH'),
+      ]),
+    );
+    const signature = authenticationKeyPair.sign(message);
+    await program.methods
+      .createAuthSession(
+        did,
+        authenticationId,
+        new anchor.BN(ONE_HOUR),
+        signature.recoveryParam,
+        [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+        null,
+      )
+      .accounts({
+        authSession: findAuthSessionPda(
+          program.programId,
+          didPda,
+          sessionKey.publicKey,
+        ),
+        challenge: findAuthChallengePda(
+          program.programId,
+          didPda,
+          sessionKey.publicKey,
+        ),
+        challengePayer: provider.wallet.publicKey,
+        didDocument: didPda,
+        verificationMethod: verificationPda,
+        authentication: authenticationPda,
+        authenticationSigner: null,
+        instructionsSysvar: null,
+      })
+      .rpc();
+  };
+
+  const checkSession = (
+    sessionKey: anchor.web3.Keypair,
+    checkedAudience: anchor.web3.PublicKey,
+  ) =>
+    program.methods
+      .checkAuthSession(did, checkedAudience)
+      .accounts({
+        authSession: findAuthSessionPda(
+          program.programId,
+          didPda,
+          sessionKey.publicKey,
+        ),
+        didDocument: didPda,
+        sessionKey: sessionKey.publicKey,
+      })
+      .signers([sessionKey])
+      .rpc();
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    await program.methods
+      .addVerificationMethod(
+        did,
+        authenticationId,
+        KEY_TYPE.secp256k1,
+        MULTIBASE_PREFIX.hex +
+          authenticationKeyPair.getPublic().encode('hex', false).slice(2),
+        provider.wallet.publicKey,
+        null,
+        null,
+      )
+      .accounts({
+        didDocument: didPda,
+        verificationMethod: verificationPda,
+      })
+      .rpc();
+    await program.methods
+      .addVerificationRelationship(
+        did,
+        { authentication: {} },
+        authenticationId,
+        null,
+        null,
+      )
+      .accounts({
+        didDocument: didPda,
+        verificationMethod: verificationPda,
+        verificationRelationship: authenticationPda,
+      })
+      .rpc();
+  });
+
+  it('Should open a session for the challenged session key', async () => {
+    const sessionKey = anchor.web3.Keypair.generate();
+    await login(sessionKey, audience);
+
+    const authSession = await program.account.authSession.fetch(
+      findAuthSessionPda(program.programId, didPda, sessionKey.publicKey),
+    );
+    expect(authSession.didDocument.toBase58()).to.equal(didPda.toBase58());
+    expect(authSession.sessionKey.toBase58()).to.equal(
+      sessionKey.publicKey.toBase58(),
+    );
+    expect(authSession.audience.toBase58()).to.equal(audience.toBase58());
+
+    await checkSession(sessionKey, audience);
+    const challenge = await provider.connection.getAccountInfo(
+      findAuthChallengePda(program.programId, didPda, sessionKey.publicKey),
+    );
+    expect(challenge).to.equal(null);
+  });
+
+  it('Fail to answer a challenge signed for another audience', async () => {
+    await expectAnchorError(
+      login(
+        anchor.web3.Keypair.generate(),
+        anchor.web3.Keypair.generate().publicKey,
+      ),
+      ANCHOR_ERROR_UNAUTHORIZED,
+    );
+  });
+
+  it('Fail to check a session for another audience', async () => {
+    const sessionKey = anchor.web3.Keypair.generate();
+    await login(sessionKey, audience);
+    await expectAnchorError(
+      checkSession(sessionKey, anchor.web3.Keypair.generate().publicKey),
+      ANCHOR_ERROR_AUTH_SESSION_AUDIENCE_MISMATCH,
+    );
+  });
+
+  it('Should close the session on logout', async () => {
+    const sessionKey = anchor.web3.Keypair.generate();
+    await login(sessionKey, audience);
+    const authSessionPda = findAuthSessionPda(
+      program.programId,
+      didPda,
+      sessionKey.publicKey,
+    );
+    await program.methods
+      .closeAuthSession(did)
+      .accounts({
+        authSession: authSessionPda,
+        didDocument: didPda,
+        payer: provider.wallet.publicKey,
+        closer: sessionKey.publicKey,
+      })
+      .signers([sessionKey])
+      .rpc();
+
+    const authSession = await provider.connection.getAccountInfo(
+      authSessionPda,
+    );
+    expect(authSession).to.equal(null);
+  });
+});
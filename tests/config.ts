@@ -0,0 +1,83 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import { ensureRegistryConfig, expectAnchorError, KEY_TYPE } from './utils';
+
+const ANCHOR_ERROR_REGISTRY_PAUSED = 'RegistryPaused';
+const ANCHOR_ERROR_HAS_ONE = 'ConstraintHasOne';
+const ANCHOR_ERROR_INSUFFICIENT_FEES = 'InsufficientFees';
+
+describe('RegistryConfig', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  let registryConfigPda: anchor.web3.PublicKey;
+
+  before(async () => {
+    registryConfigPda = await ensureRegistryConfig(program);
+  });
+
+  it('Should initialize config properly', async () => {
+    const config = await program.account.registryConfig.fetch(
+      registryConfigPda,
+    );
+    expect(config.admin.toBase58()).to.equal(
+      provider.wallet.publicKey.toBase58(),
+    );
+    expect(config.allowedKeyTypes).to.include(KEY_TYPE.secp256k1);
+    expect(config.paused).to.equal(false);
+  });
+
+  it('Should pause and resume the registry', async () => {
+    const did = 'did:zuni:solana:paused';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+
+    await program.methods.updateConfig({ paused: [true] }).rpc();
+    await expectAnchorError(
+      program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_REGISTRY_PAUSED,
+    );
+
+    await program.methods.updateConfig({ paused: [false] }).rpc();
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    const didDocument = await program.account.didDocument.fetch(didPda);
+    expect(didDocument.did).to.equal(did);
+  });
+
+  it('Fail to update config with no auth', async () => {
+    const malicious = anchor.web3.Keypair.generate();
+    await expectAnchorError(
+      program.methods
+        .updateConfig({ paused: [true] })
+        .accounts({ admin: malicious.publicKey })
+        .signers([malicious])
+        .rpc(),
+      ANCHOR_ERROR_HAS_ONE,
+    );
+  });
+
+  it('Fail to withdraw more than the collected fees', async () => {
+    await expectAnchorError(
+      program.methods
+        .withdrawFees(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
+        .accounts({ recipient: provider.wallet.publicKey })
+        .rpc(),
+      ANCHOR_ERROR_INSUFFICIENT_FEES,
+    );
+  });
+});
@@ -5,14 +5,33 @@ import { expect } from 'chai';
 import { ec as EC } from 'elliptic';
 import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
 import {
+  acknowledgementMessage,
+  closeMessage,
+  ensureRegistryConfig,
+  expectAnchorError,
+  findClosedCredentialPda,
+  findIssuerCredentialIndexPda,
+  findRegistryConfigPda,
   findVerificationPdasWithKeyIds,
   genKeyIds,
+  issuanceMessage,
   KEY_TYPE,
   MULTIBASE_PREFIX,
+  revocationMessage,
 } from './utils';
 
 // const ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED = 'AccountNotInitialized';
 const ANCHOR_ERROR_UNAUTHORIZED = 'Unauthorized';
+const ANCHOR_ERROR_VERIFICATION_METHOD_NOT_VALID = 'VerificationMethodNotValid';
+const ANCHOR_ERROR_CREDENTIAL_NOT_CLOSABLE = 'CredentialNotClosable';
+const ANCHOR_ERROR_CREDENTIAL_ID_TOO_LONG = 'CredentialIdTooLong';
+const ANCHOR_ERROR_CREDENTIAL_ALREADY_ACKNOWLEDGED =
+  'CredentialAlreadyAcknowledged';
+const ANCHOR_ERROR_FIELD_MERKLE_ROOT_MISMATCH = 'FieldMerkleRootMismatch';
+const ANCHOR_ERROR_CREDENTIAL_ID_CLOSED = 'CredentialIdClosed';
+const ANCHOR_ERROR_NOT_SUPPORT_KEY_TYPE = 'NotSupportKeyType';
+
+const MAX_CREDENTIAL_ID_LENGTH = 128;
 
 const VERIFICATION_RELATIONSHIP = {
   authentication: {
@@ -41,6 +60,10 @@ describe('Credential', () => {
   const program = anchor.workspace
     .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
 
+  before(async () => {
+    await ensureRegistryConfig(program);
+  });
+
   const CREDENTIAL_STATUS = {
     active: {},
     revoked: {},
@@ -81,7 +104,7 @@ describe('Credential', () => {
 
     before(async () => {
       await program.methods
-        .initializeDid(did)
+        .initializeDid(did, 0)
         .accounts({ didDocument: didPda })
         .rpc();
       await program.methods
@@ -91,6 +114,8 @@ describe('Credential', () => {
           keyType,
           verificationPublicKeyMultibase,
           provider.wallet.publicKey,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -103,6 +128,8 @@ describe('Credential', () => {
           did,
           VERIFICATION_RELATIONSHIP.authentication.input,
           authenticationId,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -120,7 +147,9 @@ describe('Credential', () => {
         program.programId,
       );
       const expiredAt = new Date().getTime();
-      const signature = verificationKeyPair.sign(hashedCredentialId);
+      const signature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, expiredAt)),
+      );
 
       if (signature.recoveryParam === null) {
         throw new Error('recoveryParam is undefined');
@@ -132,14 +161,25 @@ describe('Credential', () => {
           authenticationId,
           credentialId,
           new anchor.BN(expiredAt),
+          null,
+          null,
           signature.recoveryParam,
           [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+          null,
+          0,
         )
         .accounts({
           didDocument: didPda,
           verificationMethod: verificationPda,
           authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
           credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
         })
         .rpc();
 
@@ -147,51 +187,326 @@ describe('Credential', () => {
         credentialPda,
       );
 
-      expect(credentialState.credentialId === credentialId);
-      expect((credentialState.issuerDid = did));
-      expect(credentialState.expireAt?.toString() === expiredAt.toString());
-      expect(
-        credentialState.status.toString() ===
-          CREDENTIAL_STATUS.active.toString(),
+      expect(credentialState.credentialId).to.equal(credentialId);
+      expect(credentialState.issuerDid).to.equal(did);
+      expect(credentialState.expireAt?.toNumber()).to.equal(expiredAt);
+      expect(credentialState.status).to.deep.equal(CREDENTIAL_STATUS.active);
+    });
+
+    it('Should anchor the field Merkle root signed by the issuer', async () => {
+      const credentialId = 'issueWithFieldRoot';
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      // `fieldMerkleRoot` of the credential, big-endian
+      const fieldMerkleRoot = [...Array(32).keys()];
+      const signature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, null, fieldMerkleRoot)),
+      );
+
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+
+      await program.methods
+        .addCredential(
+          did,
+          authenticationId,
+          credentialId,
+          null,
+          null,
+          fieldMerkleRoot,
+          signature.recoveryParam,
+          [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+          null,
+          0,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
+        })
+        .rpc();
+
+      const credentialState = await program.account.credentialState.fetch(
+        credentialPda,
+      );
+      expect(credentialState.fieldMerkleRoot).to.deep.equal(fieldMerkleRoot);
+
+      await program.methods
+        .checkCredential(credentialId, fieldMerkleRoot)
+        .accounts({ credentialState: credentialPda })
+        .rpc();
+      await expectAnchorError(
+        program.methods
+          .checkCredential(credentialId, Array(32).fill(0))
+          .accounts({ credentialState: credentialPda })
+          .rpc(),
+        ANCHOR_ERROR_FIELD_MERKLE_ROOT_MISMATCH,
       );
     });
 
     it('Fail with wrong signature', async () => {
-      try {
-        const credentialId = 'issue2';
-        const hashedCredentialId = keccak_256(credentialId);
-        const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
-          [hashedCredentialId],
-          program.programId,
-        );
-        const expiredAt = new Date().getTime();
-        const malicious = ec.genKeyPair();
-        const signature = malicious.sign(hashedCredentialId);
+      const credentialId = 'issue2';
+      const hashedCredentialId = keccak_256(credentialId);
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [hashedCredentialId],
+        program.programId,
+      );
+      const expiredAt = new Date().getTime();
+      const malicious = ec.genKeyPair();
+      const signature = malicious.sign(hashedCredentialId);
 
-        if (signature.recoveryParam === null) {
-          throw new Error('recoveryParam is undefined');
-        }
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
 
-        await program.methods
+      await expectAnchorError(
+        program.methods
           .addCredential(
             did,
             authenticationId,
             credentialId,
             new anchor.BN(expiredAt),
+            null,
+            null,
             signature.recoveryParam,
             [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+            null,
+            0,
           )
           .accounts({
             didDocument: didPda,
             verificationMethod: verificationPda,
             authentication: authenticationPda,
+            authenticationSigner: null,
+            instructionsSysvar: null,
+            credentialSchema: null,
             credentialState: credentialPda,
+            closedCredential: findClosedCredentialPda(
+              program.programId,
+              credentialId,
+            ),
           })
+          .rpc(),
+        ANCHOR_ERROR_UNAUTHORIZED,
+      );
+    });
+
+    it('Fail with a key type the registry no longer allows', async () => {
+      const credentialId = 'issueWithDisallowedKeyType';
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      const signature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, null)),
+      );
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      const config = await program.account.registryConfig.fetch(
+        findRegistryConfigPda(program.programId),
+      );
+
+      await program.methods
+        .updateConfig({
+          allowedKeyTypes: [
+            config.allowedKeyTypes.filter((type) => type !== keyType),
+          ],
+        })
+        .rpc();
+      try {
+        await expectAnchorError(
+          program.methods
+            .addCredential(
+              did,
+              authenticationId,
+              credentialId,
+              null,
+              null,
+              null,
+              signature.recoveryParam,
+              [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+              null,
+              0,
+            )
+            .accounts({
+              didDocument: didPda,
+              verificationMethod: verificationPda,
+              authentication: authenticationPda,
+              authenticationSigner: null,
+              instructionsSysvar: null,
+              credentialSchema: null,
+              credentialState: credentialPda,
+              closedCredential: findClosedCredentialPda(
+                program.programId,
+                credentialId,
+              ),
+            })
+            .rpc(),
+          ANCHOR_ERROR_NOT_SUPPORT_KEY_TYPE,
+        );
+      } finally {
+        await program.methods
+          .updateConfig({ allowedKeyTypes: [config.allowedKeyTypes] })
           .rpc();
-      } catch (error) {
-        expect(error.error.errorCode.code === ANCHOR_ERROR_UNAUTHORIZED);
       }
     });
+
+    it('Fail with expired verification method', async () => {
+      const expiredKeyId = `${did}#expired-key`;
+      const [expiredVerificationPda] = findVerificationPdasWithKeyIds(
+        program.programId,
+        did,
+        [expiredKeyId],
+      );
+      const [expiredAuthenticationPda] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(
+              keccak_256(
+                did +
+                  VERIFICATION_RELATIONSHIP.authentication.discriminator +
+                  expiredKeyId,
+              ),
+            ),
+          ],
+          program.programId,
+        );
+      const now = new Date().getTime();
+
+      await program.methods
+        .addVerificationMethod(
+          did,
+          expiredKeyId,
+          keyType,
+          verificationPublicKeyMultibase,
+          provider.wallet.publicKey,
+          new anchor.BN(now - 2 * 60 * 60 * 1000),
+          new anchor.BN(now - 60 * 60 * 1000),
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: expiredVerificationPda,
+        })
+        .rpc();
+      await program.methods
+        .addVerificationRelationship(
+          did,
+          VERIFICATION_RELATIONSHIP.authentication.input,
+          expiredKeyId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: expiredVerificationPda,
+          verificationRelationship: expiredAuthenticationPda,
+        })
+        .rpc();
+
+      const credentialId = 'issue3';
+      const hashedCredentialId = keccak_256(credentialId);
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [hashedCredentialId],
+        program.programId,
+      );
+      const signature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, null)),
+      );
+
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+
+      await expectAnchorError(
+        program.methods
+          .addCredential(
+            did,
+            expiredKeyId,
+            credentialId,
+            null,
+            null,
+            null,
+            signature.recoveryParam,
+            [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+            null,
+            0,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: expiredVerificationPda,
+            authentication: expiredAuthenticationPda,
+            authenticationSigner: null,
+            instructionsSysvar: null,
+            credentialSchema: null,
+            credentialState: credentialPda,
+            closedCredential: findClosedCredentialPda(
+              program.programId,
+              credentialId,
+            ),
+          })
+          .rpc(),
+        ANCHOR_ERROR_VERIFICATION_METHOD_NOT_VALID,
+      );
+    });
+
+    it('Fail with a too long credential id', async () => {
+      const credentialId = 'a'.repeat(MAX_CREDENTIAL_ID_LENGTH + 1);
+      const hashedCredentialId = keccak_256(credentialId);
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [hashedCredentialId],
+        program.programId,
+      );
+      const signature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, null)),
+      );
+
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+
+      await expectAnchorError(
+        program.methods
+          .addCredential(
+            did,
+            authenticationId,
+            credentialId,
+            null,
+            null,
+            null,
+            signature.recoveryParam,
+            [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+            null,
+            0,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: verificationPda,
+            authentication: authenticationPda,
+            authenticationSigner: null,
+            instructionsSysvar: null,
+            credentialSchema: null,
+            credentialState: credentialPda,
+            closedCredential: findClosedCredentialPda(
+              program.programId,
+              credentialId,
+            ),
+          })
+          .rpc(),
+        ANCHOR_ERROR_CREDENTIAL_ID_TOO_LONG,
+      );
+    });
   });
 
   describe('revokeCredential()', () => {
@@ -229,7 +544,7 @@ describe('Credential', () => {
 
     before(async () => {
       await program.methods
-        .initializeDid(did)
+        .initializeDid(did, 0)
         .accounts({ didDocument: didPda })
         .rpc();
       await program.methods
@@ -239,6 +554,8 @@ describe('Credential', () => {
           keyType,
           verificationPublicKeyMultibase,
           provider.wallet.publicKey,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -251,6 +568,8 @@ describe('Credential', () => {
           did,
           VERIFICATION_RELATIONSHIP.authentication.input,
           authenticationId,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -266,7 +585,7 @@ describe('Credential', () => {
         [keccak_256(credentialId)],
         program.programId,
       );
-      const issueMsg = keccak_256(credentialId);
+      const issueMsg = keccak_256(issuanceMessage(credentialId, null));
       const issueSignature = verificationKeyPair.sign(issueMsg);
       if (issueSignature.recoveryParam === null) {
         throw new Error('recoveryParam is undefined');
@@ -277,18 +596,29 @@ describe('Credential', () => {
           authenticationId,
           credentialId,
           null,
+          null,
+          null,
           issueSignature.recoveryParam,
           [...issueSignature.r.toBuffer(), ...issueSignature.s.toBuffer()],
+          null,
+          0,
         )
         .accounts({
           didDocument: didPda,
           verificationMethod: verificationPda,
           authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
           credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
         })
         .rpc();
 
-      const revokeMsg = keccak_256(credentialId + 'REVOKE');
+      const revokeMsg = keccak_256(revocationMessage(did, credentialId));
       const revokeSignature = verificationKeyPair.sign(revokeMsg);
       if (revokeSignature.recoveryParam === null) {
         throw new Error('recoveryParam is undefined');
@@ -301,11 +631,14 @@ describe('Credential', () => {
           credentialId,
           revokeSignature.recoveryParam,
           [...revokeSignature.r.toBuffer(), ...revokeSignature.s.toBuffer()],
+          null,
         )
         .accounts({
           didDocument: didPda,
           verificationMethod: verificationPda,
           authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
           credentialState: credentialPda,
         })
         .rpc();
@@ -313,68 +646,685 @@ describe('Credential', () => {
       const credentialState = await program.account.credentialState.fetch(
         credentialPda,
       );
-      expect(credentialState.credentialId === credentialId);
-      expect((credentialState.issuerDid = did));
-      expect(
-        credentialState.status.toString() ===
-          CREDENTIAL_STATUS.revoked.toString(),
-      );
+      expect(credentialState.credentialId).to.equal(credentialId);
+      expect(credentialState.issuerDid).to.equal(did);
+      expect(credentialState.status).to.deep.equal(CREDENTIAL_STATUS.revoked);
     });
 
     it('Fail with wrong signature', async () => {
-      try {
-        const credentialId = 'revoke2';
-        const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
-          [keccak_256(credentialId)],
-          program.programId,
-        );
-        const issueMsg = keccak_256(credentialId);
-        const issueSignature = verificationKeyPair.sign(issueMsg);
-        if (issueSignature.recoveryParam === null) {
-          throw new Error('recoveryParam is undefined');
-        }
-        await program.methods
-          .addCredential(
-            did,
-            authenticationId,
+      const credentialId = 'revoke2';
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      const issueMsg = keccak_256(issuanceMessage(credentialId, null));
+      const issueSignature = verificationKeyPair.sign(issueMsg);
+      if (issueSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      await program.methods
+        .addCredential(
+          did,
+          authenticationId,
+          credentialId,
+          null,
+          null,
+          null,
+          issueSignature.recoveryParam,
+          [...issueSignature.r.toBuffer(), ...issueSignature.s.toBuffer()],
+          null,
+          0,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
             credentialId,
-            null,
-            issueSignature.recoveryParam,
-            [...issueSignature.r.toBuffer(), ...issueSignature.s.toBuffer()],
-          )
-          .accounts({
-            didDocument: didPda,
-            verificationMethod: verificationPda,
-            authentication: authenticationPda,
-            credentialState: credentialPda,
-          })
-          .rpc();
+          ),
+        })
+        .rpc();
 
-        const revokeMsg = keccak_256(credentialId + 'REVOKE');
-        const malicious = ec.genKeyPair();
-        const revokeSignature = malicious.sign(revokeMsg);
-        if (revokeSignature.recoveryParam === null) {
-          throw new Error('recoveryParam is undefined');
-        }
+      const revokeMsg = keccak_256(revocationMessage(did, credentialId));
+      const malicious = ec.genKeyPair();
+      const revokeSignature = malicious.sign(revokeMsg);
+      if (revokeSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
 
-        await program.methods
+      await expectAnchorError(
+        program.methods
           .revokeCredential(
             did,
             authenticationId,
             credentialId,
             revokeSignature.recoveryParam,
             [...revokeSignature.r.toBuffer(), ...revokeSignature.s.toBuffer()],
+            null,
           )
           .accounts({
             didDocument: didPda,
             verificationMethod: verificationPda,
             authentication: authenticationPda,
+            authenticationSigner: null,
+            instructionsSysvar: null,
             credentialState: credentialPda,
           })
-          .rpc();
-      } catch (error) {
-        expect(error.error.errorCode.code === ANCHOR_ERROR_UNAUTHORIZED);
+          .rpc(),
+        ANCHOR_ERROR_UNAUTHORIZED,
+      );
+    });
+
+    it('Fail to revoke a credential of another issuer', async () => {
+      const credentialId = 'revoke3';
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      const issueSignature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, null)),
+      );
+      if (issueSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
       }
+      await program.methods
+        .addCredential(
+          did,
+          authenticationId,
+          credentialId,
+          null,
+          null,
+          null,
+          issueSignature.recoveryParam,
+          [...issueSignature.r.toBuffer(), ...issueSignature.s.toBuffer()],
+          null,
+          0,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
+        })
+        .rpc();
+
+      // Another DID revokes it with a valid signature of its own key.
+      const otherDid = 'did:zuni:solana:revokeCredentialOther';
+      const [otherDidPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(otherDid)],
+        program.programId,
+      );
+      const [otherAuthenticationId] = genKeyIds(1, otherDid);
+      const [otherVerificationPda] = findVerificationPdasWithKeyIds(
+        program.programId,
+        otherDid,
+        [otherAuthenticationId],
+      );
+      const [otherAuthenticationPda] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(
+              keccak_256(
+                otherDid +
+                  VERIFICATION_RELATIONSHIP.authentication.discriminator +
+                  otherAuthenticationId,
+              ),
+            ),
+          ],
+          program.programId,
+        );
+      const otherKeyPair = ec.genKeyPair();
+      await program.methods
+        .initializeDid(otherDid, 0)
+        .accounts({ didDocument: otherDidPda })
+        .rpc();
+      await program.methods
+        .addVerificationMethod(
+          otherDid,
+          otherAuthenticationId,
+          keyType,
+          MULTIBASE_PREFIX.hex +
+            otherKeyPair.getPublic().encode('hex', false).slice(2),
+          provider.wallet.publicKey,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: otherDidPda,
+          verificationMethod: otherVerificationPda,
+        })
+        .rpc();
+      await program.methods
+        .addVerificationRelationship(
+          otherDid,
+          VERIFICATION_RELATIONSHIP.authentication.input,
+          otherAuthenticationId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: otherDidPda,
+          verificationMethod: otherVerificationPda,
+          verificationRelationship: otherAuthenticationPda,
+        })
+        .rpc();
+
+      const revokeSignature = otherKeyPair.sign(
+        keccak_256(revocationMessage(otherDid, credentialId)),
+      );
+      if (revokeSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      await expectAnchorError(
+        program.methods
+          .revokeCredential(
+            otherDid,
+            otherAuthenticationId,
+            credentialId,
+            revokeSignature.recoveryParam,
+            [...revokeSignature.r.toBuffer(), ...revokeSignature.s.toBuffer()],
+            null,
+          )
+          .accounts({
+            didDocument: otherDidPda,
+            verificationMethod: otherVerificationPda,
+            authentication: otherAuthenticationPda,
+            authenticationSigner: null,
+            instructionsSysvar: null,
+            credentialState: credentialPda,
+          })
+          .rpc(),
+        ANCHOR_ERROR_UNAUTHORIZED,
+      );
+    });
+  });
+
+  describe('closeCredential()', () => {
+    const did = 'did:zuni:solana:closeCredential';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const keyIds = genKeyIds(1, did);
+    const keyType = KEY_TYPE.secp256k1;
+    const ec = new EC('secp256k1');
+    const verificationKeyPair = ec.genKeyPair();
+    const verificationPdas = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      keyIds,
+    );
+    const verificationPublicKeyMultibase =
+      MULTIBASE_PREFIX.hex +
+      verificationKeyPair.getPublic().encode('hex', false).slice(2);
+    const authenticationId = keyIds[0];
+    const verificationPda = verificationPdas[0];
+    const [authenticationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(
+          keccak_256(
+            did +
+              VERIFICATION_RELATIONSHIP.authentication.discriminator +
+              authenticationId,
+          ),
+        ),
+      ],
+      program.programId,
+    );
+    const issuerIndexPda = findIssuerCredentialIndexPda(
+      program.programId,
+      didPda,
+      0,
+    );
+
+    const issue = async (credentialId: string, expireAt: number | null) => {
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      const issueSignature = verificationKeyPair.sign(
+        keccak_256(issuanceMessage(credentialId, expireAt)),
+      );
+      if (issueSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      await program.methods
+        .addCredential(
+          did,
+          authenticationId,
+          credentialId,
+          expireAt === null ? null : new anchor.BN(expireAt),
+          null,
+          null,
+          issueSignature.recoveryParam,
+          [...issueSignature.r.toBuffer(), ...issueSignature.s.toBuffer()],
+          null,
+          0,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+          credentialSchema: null,
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
+        })
+        .rpc();
+      return credentialPda;
+    };
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+      await program.methods
+        .addVerificationMethod(
+          did,
+          authenticationId,
+          keyType,
+          verificationPublicKeyMultibase,
+          provider.wallet.publicKey,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+        })
+        .rpc();
+      await program.methods
+        .addVerificationRelationship(
+          did,
+          VERIFICATION_RELATIONSHIP.authentication.input,
+          authenticationId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          verificationRelationship: authenticationPda,
+        })
+        .rpc();
+    });
+
+    it('Should close credential by issuer properly', async () => {
+      const credentialId = 'close1';
+      const credentialPda = await issue(credentialId, null);
+
+      const closeSignature = verificationKeyPair.sign(
+        keccak_256(closeMessage(credentialId)),
+      );
+      if (closeSignature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      await program.methods
+        .closeCredential(
+          credentialId,
+          0,
+          closeSignature.recoveryParam,
+          [...closeSignature.r.toBuffer(), ...closeSignature.s.toBuffer()],
+          null,
+        )
+        .accounts({
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
+          didDocument: didPda,
+          issuerIndex: issuerIndexPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          instructionsSysvar: null,
+          payer: provider.wallet.publicKey,
+          closer: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const credentialAccount = await provider.connection.getAccountInfo(
+        credentialPda,
+      );
+      expect(credentialAccount).to.equal(null);
+      const issuerIndex = await program.account.issuerCredentialIndex.fetch(
+        issuerIndexPda,
+      );
+      expect(
+        issuerIndex.entries.map((entry) => entry.toBase58()),
+      ).to.not.include(credentialPda.toBase58());
+      const closedCredential = await program.account.closedCredential.fetch(
+        findClosedCredentialPda(program.programId, credentialId),
+      );
+      expect(closedCredential.credentialId).to.equal(credentialId);
+    });
+
+    it('Fail to issue a closed credential again', async () => {
+      // Replays the issuer's signature of the credential closed above.
+      await expectAnchorError(
+        issue('close1', null),
+        ANCHOR_ERROR_CREDENTIAL_ID_CLOSED,
+      );
+    });
+
+    it('Fail to close unexpired credential without issuer signature', async () => {
+      const credentialId = 'close2';
+      const credentialPda = await issue(credentialId, new Date().getTime());
+      await expectAnchorError(
+        program.methods
+          .closeCredential(credentialId, 0, null, null, null)
+          .accounts({
+            credentialState: credentialPda,
+            closedCredential: findClosedCredentialPda(
+              program.programId,
+              credentialId,
+            ),
+            didDocument: didPda,
+            issuerIndex: issuerIndexPda,
+            verificationMethod: null,
+            authentication: null,
+            instructionsSysvar: null,
+            payer: provider.wallet.publicKey,
+            closer: provider.wallet.publicKey,
+          })
+          .rpc(),
+        ANCHOR_ERROR_CREDENTIAL_NOT_CLOSABLE,
+      );
+    });
+  });
+
+  describe('addCredential() with a Solana key', () => {
+    const did = 'did:zuni:solana:solanaIssuer';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const issuer = anchor.web3.Keypair.generate();
+    const [authenticationId] = genKeyIds(1, did);
+    const [verificationPda] = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      [authenticationId],
+    );
+    const [authenticationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(
+          keccak_256(
+            did +
+              VERIFICATION_RELATIONSHIP.authentication.discriminator +
+              authenticationId,
+          ),
+        ),
+      ],
+      program.programId,
+    );
+
+    const addCredential = (
+      credentialId: string,
+      authenticationSigner: anchor.web3.Keypair,
+    ) => {
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      );
+      return program.methods
+        .addCredential(
+          did,
+          authenticationId,
+          credentialId,
+          null,
+          null,
+          null,
+          null,
+          null,
+          null,
+          0,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          authentication: authenticationPda,
+          authenticationSigner: authenticationSigner.publicKey,
+          instructionsSysvar: null,
+          credentialSchema: null,
+          credentialState: credentialPda,
+          closedCredential: findClosedCredentialPda(
+            program.programId,
+            credentialId,
+          ),
+        })
+        .signers([authenticationSigner])
+        .rpc();
+    };
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+      await program.methods
+        .addVerificationMethod(
+          did,
+          authenticationId,
+          KEY_TYPE.solana,
+          MULTIBASE_PREFIX.base58btc + issuer.publicKey.toBase58(),
+          provider.wallet.publicKey,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+        })
+        .rpc();
+      await program.methods
+        .addVerificationRelationship(
+          did,
+          VERIFICATION_RELATIONSHIP.authentication.input,
+          authenticationId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          verificationRelationship: authenticationPda,
+        })
+        .rpc();
+    });
+
+    it('Should issue credential signed by the Solana key', async () => {
+      await addCredential('solanaIssue1', issuer);
+      const [credentialPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256('solanaIssue1')],
+        program.programId,
+      );
+      const credentialState = await program.account.credentialState.fetch(
+        credentialPda,
+      );
+      expect(credentialState.issuerDid).to.equal(did);
+    });
+
+    it('Fail to issue credential signed by another key', async () => {
+      await expectAnchorError(
+        addCredential('solanaIssue2', anchor.web3.Keypair.generate()),
+        ANCHOR_ERROR_UNAUTHORIZED,
+      );
+    });
+  });
+
+  describe('acknowledgeCredential()', () => {
+    const did = 'did:zuni:solana:acknowledgeCredential';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const issuer = anchor.web3.Keypair.generate();
+    const [authenticationId] = genKeyIds(1, did);
+    const [verificationPda] = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      [authenticationId],
+    );
+    const [authenticationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(
+          keccak_256(
+            did +
+              VERIFICATION_RELATIONSHIP.authentication.discriminator +
+              authenticationId,
+          ),
+        ),
+      ],
+      program.programId,
+    );
+    const ec = new EC('secp256k1');
+    const holderKeyPair = ec.genKeyPair();
+    // keccak of the uncompressed holder key without its 04 prefix
+    const holderCommitment = [
+      ...keccak_256(
+        Buffer.from(
+          holderKeyPair.getPublic().encode('hex', false).slice(2),
+          'hex',
+        ),
+      ),
+    ];
+
+    const findCredentialPda = (credentialId: string) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(credentialId)],
+        program.programId,
+      )[0];
+
+    const acknowledge = (credentialId: string, holder: EC.KeyPair) => {
+      const signature = holder.sign(
+        keccak_256(acknowledgementMessage(credentialId)),
+      );
+      if (signature.recoveryParam === null) {
+        throw new Error('recoveryParam is undefined');
+      }
+      return program.methods
+        .acknowledgeCredential(
+          credentialId,
+          signature.recoveryParam,
+          [...signature.r.toBuffer(), ...signature.s.toBuffer()],
+          null,
+        )
+        .accounts({
+          credentialState: findCredentialPda(credentialId),
+          verificationMethod: null,
+          authentication: null,
+          authenticationSigner: null,
+          instructionsSysvar: null,
+        })
+        .rpc();
+    };
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+      await program.methods
+        .addVerificationMethod(
+          did,
+          authenticationId,
+          KEY_TYPE.solana,
+          MULTIBASE_PREFIX.base58btc + issuer.publicKey.toBase58(),
+          provider.wallet.publicKey,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+        })
+        .rpc();
+      await program.methods
+        .addVerificationRelationship(
+          did,
+          VERIFICATION_RELATIONSHIP.authentication.input,
+          authenticationId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          verificationRelationship: authenticationPda,
+        })
+        .rpc();
+      for (const credentialId of ['acknowledge1', 'acknowledge2']) {
+        await program.methods
+          .addCredential(
+            did,
+            authenticationId,
+            credentialId,
+            null,
+            { keccakCommitment: [holderCommitment] },
+            null,
+            null,
+            null,
+            null,
+            0,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: verificationPda,
+            authentication: authenticationPda,
+            authenticationSigner: issuer.publicKey,
+            instructionsSysvar: null,
+            credentialSchema: null,
+            credentialState: findCredentialPda(credentialId),
+            closedCredential: findClosedCredentialPda(
+              program.programId,
+              credentialId,
+            ),
+          })
+          .signers([issuer])
+          .rpc();
+      }
+    });
+
+    it('Should record the acknowledgement of the holder key', async () => {
+      await acknowledge('acknowledge1', holderKeyPair);
+
+      const credentialState = await program.account.credentialState.fetch(
+        findCredentialPda('acknowledge1'),
+      );
+      expect(credentialState.acknowledgedAt).to.not.equal(null);
+      expect(credentialState.holder).to.not.equal(null);
+    });
+
+    it('Fail to acknowledge twice', async () => {
+      await expectAnchorError(
+        acknowledge('acknowledge1', holderKeyPair),
+        ANCHOR_ERROR_CREDENTIAL_ALREADY_ACKNOWLEDGED,
+      );
+    });
+
+    it('Fail to acknowledge with another key', async () => {
+      await expectAnchorError(
+        acknowledge('acknowledge2', ec.genKeyPair()),
+        ANCHOR_ERROR_UNAUTHORIZED,
+      );
     });
   });
 });
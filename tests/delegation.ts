@@ -0,0 +1,309 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import {
+  ensureRegistryConfig,
+  expectAnchorError,
+  findClosedCredentialPda,
+  findCredentialSchemaPda,
+  findDelegationPda,
+} from './utils';
+
+const ANCHOR_ERROR_CREDENTIAL_NOT_IN_DELEGATION_SCOPE =
+  'CredentialNotInDelegationScope';
+const ANCHOR_ERROR_CREDENTIAL_OUTLIVES_DELEGATION =
+  'CredentialOutlivesDelegation';
+const ANCHOR_ERROR_DELEGATION_LIMIT_REACHED = 'DelegationLimitReached';
+const ANCHOR_ERROR_DELEGATION_EXPIRED = 'DelegationExpired';
+const ANCHOR_ERROR_DELEGATION_CANNOT_REVOKE = 'DelegationCannotRevoke';
+const ANCHOR_ERROR_UNAUTHORIZED = 'Unauthorized';
+
+describe('Delegation', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const did = 'did:zuni:solana:delegation';
+  const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [keccak_256(did)],
+    program.programId,
+  );
+  const diplomaSchemaPda = findCredentialSchemaPda(
+    program.programId,
+    didPda,
+    'diploma:bachelor',
+    1,
+  );
+  const transcriptSchemaPda = findCredentialSchemaPda(
+    program.programId,
+    didPda,
+    'transcript',
+    1,
+  );
+
+  const findCredentialPda = (credentialId: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(credentialId)],
+      program.programId,
+    )[0];
+
+  const createDelegation = async (
+    delegate: anchor.web3.Keypair,
+    id: number,
+    schemaIdPrefix: string,
+    maxCredentials: number | null,
+    canRevoke: boolean,
+    expireAt: number | null,
+  ) => {
+    const delegationPda = findDelegationPda(
+      program.programId,
+      didPda,
+      delegate.publicKey,
+      id,
+    );
+    await program.methods
+      .createDelegation(
+        did,
+        delegate.publicKey,
+        new anchor.BN(id),
+        schemaIdPrefix,
+        maxCredentials,
+        canRevoke,
+        expireAt === null ? null : new anchor.BN(expireAt),
+      )
+      .accounts({ delegation: delegationPda, didDocument: didPda })
+      .rpc();
+    return delegationPda;
+  };
+
+  const addDelegatedCredential = (
+    delegate: anchor.web3.Keypair,
+    credentialId: string,
+    credentialSchema = diplomaSchemaPda,
+    expireAt: number | null = null,
+  ) =>
+    program.methods
+      .addDelegatedCredential(
+        did,
+        credentialId,
+        expireAt === null ? null : new anchor.BN(expireAt),
+        null,
+        null,
+        0,
+      )
+      .accounts({
+        credentialState: findCredentialPda(credentialId),
+        closedCredential: findClosedCredentialPda(
+          program.programId,
+          credentialId,
+        ),
+        didDocument: didPda,
+        delegation: findDelegationPda(
+          program.programId,
+          didPda,
+          delegate.publicKey,
+          0,
+        ),
+        credentialSchema,
+        delegate: delegate.publicKey,
+      })
+      .signers([delegate])
+      .rpc();
+
+  const revokeDelegatedCredential = (
+    delegate: anchor.web3.Keypair,
+    delegationPda: anchor.web3.PublicKey,
+    credentialId: string,
+  ) =>
+    program.methods
+      .revokeDelegatedCredential(did, credentialId)
+      .accounts({
+        credentialState: findCredentialPda(credentialId),
+        didDocument: didPda,
+        delegation: delegationPda,
+        delegate: delegate.publicKey,
+      })
+      .signers([delegate])
+      .rpc();
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    for (const [schemaId, schemaPda] of [
+      ['diploma:bachelor', diplomaSchemaPda],
+      ['transcript', transcriptSchemaPda],
+    ] as const) {
+      await program.methods
+        .createCredentialSchema(
+          did,
+          schemaId,
+          1,
+          'Degree',
+          [{ name: 'degree', fieldType: { string: {} } }],
+          new Array(32).fill(0),
+        )
+        .accounts({ credentialSchema: schemaPda, didDocument: didPda })
+        .rpc();
+    }
+  });
+
+  it('Should issue and revoke credentials as a delegate', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const delegationPda = await createDelegation(
+      delegate,
+      0,
+      'diploma:',
+      2,
+      true,
+      null,
+    );
+
+    await addDelegatedCredential(delegate, 'delegated-1');
+
+    const credentialPda = findCredentialPda('delegated-1');
+    const credentialState = await program.account.credentialState.fetch(
+      credentialPda,
+    );
+    expect(credentialState.delegation?.toBase58()).to.equal(
+      delegationPda.toBase58(),
+    );
+    expect(credentialState.schema?.toBase58()).to.equal(
+      diplomaSchemaPda.toBase58(),
+    );
+    const delegation = await program.account.delegation.fetch(delegationPda);
+    expect(delegation.issuedCredentials).to.equal(1);
+
+    await revokeDelegatedCredential(delegate, delegationPda, 'delegated-1');
+    const revoked = await program.account.credentialState.fetch(credentialPda);
+    expect(revoked.status).to.deep.equal({ revoked: {} });
+  });
+
+  it('Fail to issue against a schema outside the delegated prefix', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    await createDelegation(delegate, 0, 'diploma:', null, false, null);
+    await expectAnchorError(
+      addDelegatedCredential(delegate, 'transcript-1', transcriptSchemaPda),
+      ANCHOR_ERROR_CREDENTIAL_NOT_IN_DELEGATION_SCOPE,
+    );
+  });
+
+  it('Fail to issue more than the delegated maximum', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    await createDelegation(delegate, 0, '', 1, false, null);
+    await addDelegatedCredential(delegate, 'limited-1');
+    await expectAnchorError(
+      addDelegatedCredential(delegate, 'limited-2'),
+      ANCHOR_ERROR_DELEGATION_LIMIT_REACHED,
+    );
+  });
+
+  it('Fail to issue with an expired delegation', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    await createDelegation(delegate, 0, '', null, false, 1);
+    await expectAnchorError(
+      addDelegatedCredential(delegate, 'expired-delegation-1'),
+      ANCHOR_ERROR_DELEGATION_EXPIRED,
+    );
+  });
+
+  it('Fail to issue a credential outliving its delegation', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const expireAt = new Date().getTime() + 24 * 60 * 60 * 1000;
+    await createDelegation(delegate, 0, '', null, false, expireAt);
+    await expectAnchorError(
+      addDelegatedCredential(delegate, 'outliving-1'),
+      ANCHOR_ERROR_CREDENTIAL_OUTLIVES_DELEGATION,
+    );
+    await expectAnchorError(
+      addDelegatedCredential(
+        delegate,
+        'outliving-1',
+        diplomaSchemaPda,
+        expireAt + 1,
+      ),
+      ANCHOR_ERROR_CREDENTIAL_OUTLIVES_DELEGATION,
+    );
+    await addDelegatedCredential(
+      delegate,
+      'outliving-1',
+      diplomaSchemaPda,
+      expireAt,
+    );
+  });
+
+  it('Fail to revoke without the revoke right', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const delegationPda = await createDelegation(
+      delegate,
+      0,
+      '',
+      null,
+      false,
+      null,
+    );
+    await addDelegatedCredential(delegate, 'no-revoke-1');
+    await expectAnchorError(
+      revokeDelegatedCredential(delegate, delegationPda, 'no-revoke-1'),
+      ANCHOR_ERROR_DELEGATION_CANNOT_REVOKE,
+    );
+  });
+
+  it('Should stop issuance once the delegation is revoked', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const delegationPda = await createDelegation(
+      delegate,
+      0,
+      '',
+      null,
+      false,
+      null,
+    );
+    await program.methods
+      .revokeDelegation(did, delegate.publicKey, new anchor.BN(0))
+      .accounts({ delegation: delegationPda, didDocument: didPda })
+      .rpc();
+    const account = await provider.connection.getAccountInfo(delegationPda);
+    expect(account).to.equal(null);
+  });
+
+  it('Fail to revoke the credentials of a revoked delegation under a new one', async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const firstDelegationPda = await createDelegation(
+      delegate,
+      0,
+      '',
+      null,
+      false,
+      null,
+    );
+    await addDelegatedCredential(delegate, 'first-grant-1');
+    await program.methods
+      .revokeDelegation(did, delegate.publicKey, new anchor.BN(0))
+      .accounts({ delegation: firstDelegationPda, didDocument: didPda })
+      .rpc();
+
+    const secondDelegationPda = await createDelegation(
+      delegate,
+      1,
+      '',
+      null,
+      true,
+      null,
+    );
+    expect(secondDelegationPda.toBase58()).to.not.equal(
+      firstDelegationPda.toBase58(),
+    );
+    await expectAnchorError(
+      revokeDelegatedCredential(delegate, secondDelegationPda, 'first-grant-1'),
+      ANCHOR_ERROR_UNAUTHORIZED,
+    );
+  });
+});
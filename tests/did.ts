@@ -2,8 +2,13 @@ import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { keccak_256 } from '@noble/hashes/sha3';
 import { expect } from 'chai';
+import { createECDH } from 'crypto';
 import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
 import {
+  ensureRegistryConfig,
+  expectAnchorError,
+  findControllerDidIndexPda,
+  findDidChangeLogPda,
   findVerificationPdasWithKeyIds,
   genKeyIds,
   KEY_TYPE,
@@ -11,7 +16,14 @@ import {
 } from './utils';
 
 const ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED = 'AccountNotInitialized';
-const ANCHOR_ERROR_UNAUTHORIZED = 'Unauthorized';
+const ANCHOR_ERROR_CONSTRAINT_RAW = 'ConstraintRaw';
+const ANCHOR_ERROR_ACCOUNT_ALREADY_MIGRATED = 'AccountAlreadyMigrated';
+const ANCHOR_ERROR_INVALID_DID = 'InvalidDid';
+const ANCHOR_ERROR_INVALID_KEY_ID = 'InvalidKeyId';
+const ANCHOR_ERROR_RELATIONSHIP_NOT_SUPPORTED_BY_KEY_TYPE =
+  'RelationshipNotSupportedByKeyType';
+const ANCHOR_ERROR_INVALID_ALSO_KNOWN_AS = 'InvalidAlsoKnownAs';
+const ANCHOR_ERROR_INVALID_P256_PUBLIC_KEY = 'InvalidP256PublicKey';
 
 const VERIFICATION_RELATIONSHIP = {
   authentication: {
@@ -40,6 +52,10 @@ describe('DID', () => {
   const program = anchor.workspace
     .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
 
+  before(async () => {
+    await ensureRegistryConfig(program);
+  });
+
   describe('initializeDid()', () => {
     const did = 'did:zuni:solana:initializeDid';
     const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
@@ -48,32 +64,61 @@ describe('DID', () => {
     );
 
     it('Should initialize DID properly', async () => {
-      try {
-        await program.methods
-          .initializeDid(did)
-          .accounts({ didDocument: didPda })
-          .rpc();
-        const didDocument = await program.account.didDocument.fetch(didPda);
-        expect(didDocument.did === did);
-        expect(didDocument.controller === provider.wallet.publicKey);
-      } catch (error) {
-        console.log(error);
-      }
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+      const didDocument = await program.account.didDocument.fetch(didPda);
+      expect(didDocument.version).to.equal(4);
+      expect(didDocument.did).to.equal(did);
+      expect(didDocument.controller.toBase58()).to.equal(
+        provider.wallet.publicKey.toBase58(),
+      );
+
+      const controllerIndex = await program.account.controllerDidIndex.fetch(
+        findControllerDidIndexPda(
+          program.programId,
+          provider.wallet.publicKey,
+          0,
+        ),
+      );
+      expect(
+        controllerIndex.entries.map((entry) => entry.toBase58()),
+      ).to.include(didPda.toBase58());
+
+      const changeLog = await program.account.didChangeLog.fetch(
+        findDidChangeLogPda(program.programId, didPda),
+      );
+      expect(changeLog.versionId.toNumber()).to.equal(1);
+      expect(changeLog.entries[0].op).to.have.property('created');
     });
 
     it('Fail to initialize duplicate DID', async () => {
+      let logs: string[] = [];
       try {
         await program.methods
-          .initializeDid(did)
+          .initializeDid(did, 0)
           .accounts({ didDocument: didPda })
           .rpc();
       } catch (err) {
-        expect(
-          err.logs.find(
-            (log: string) => log === duplicate_err(didPda.toBase58()),
-          ),
-        );
+        logs = err.logs;
       }
+      expect(logs).to.include(duplicate_err(didPda.toBase58()));
+    });
+
+    it('Fail to initialize DID with invalid syntax', async () => {
+      const invalidDid = 'did:Zuni:solana:initializeDid';
+      const [invalidDidPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(invalidDid)],
+        program.programId,
+      );
+      await expectAnchorError(
+        program.methods
+          .initializeDid(invalidDid, 0)
+          .accounts({ didDocument: invalidDidPda })
+          .rpc(),
+        ANCHOR_ERROR_INVALID_DID,
+      );
     });
   });
 
@@ -96,7 +141,7 @@ describe('DID', () => {
 
     before(async () => {
       await program.methods
-        .initializeDid(did)
+        .initializeDid(did, 0)
         .accounts({ didDocument: didPda })
         .rpc();
     });
@@ -111,6 +156,8 @@ describe('DID', () => {
           keyType,
           publicKeyMultibase,
           controller,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -122,61 +169,74 @@ describe('DID', () => {
         verificationPda,
       );
 
-      expect(verificationMethod.did === did);
-      expect(verificationMethod.keyId === keyId);
-      expect(verificationMethod.rType === keyType);
-      expect(verificationMethod.publicKeyMultibase === publicKeyMultibase);
-      expect(verificationMethod.controller === controller);
+      expect(verificationMethod.did).to.equal(did);
+      expect(verificationMethod.keyId).to.equal(keyId);
+      expect(verificationMethod.rType).to.equal(keyType);
+      expect(verificationMethod.publicKeyMultibase).to.equal(
+        publicKeyMultibase,
+      );
+      expect(verificationMethod.controller.toBase58()).to.equal(
+        controller.toBase58(),
+      );
     });
 
     it('Fail to add verification without DID', async () => {
-      try {
-        const notExistDid = 'not exist';
-        const keyId = keyIds[0];
-        const verificationPda = verificationPdas[0];
+      const notExistDid = 'did:zuni:solana:notExist';
+      const [notExistDidPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256(notExistDid)],
+        program.programId,
+      );
+      const [keyId] = genKeyIds(1, notExistDid);
+      const [verificationPda] = findVerificationPdasWithKeyIds(
+        program.programId,
+        notExistDid,
+        [keyId],
+      );
 
-        await program.methods
+      await expectAnchorError(
+        program.methods
           .addVerificationMethod(
             notExistDid,
             keyId,
             keyType,
             publicKeyMultibase,
             controller,
+            null,
+            null,
           )
           .accounts({
-            didDocument: didPda,
+            didDocument: notExistDidPda,
             verificationMethod: verificationPda,
           })
-          .rpc();
-      } catch (err) {
-        expect(
-          err.error.errorCode.code === ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED,
-        );
-      }
+          .rpc(),
+        ANCHOR_ERROR_ACCOUNT_NOT_INITIALIZED,
+      );
     });
 
     it('Fail to add verification with no auth', async () => {
-      try {
-        const malicious = anchor.web3.Keypair.generate();
-        const transaction = new anchor.web3.Transaction().add(
-          anchor.web3.SystemProgram.transfer({
-            fromPubkey: provider.publicKey,
-            toPubkey: malicious.publicKey,
-            lamports: anchor.web3.LAMPORTS_PER_SOL,
-          }),
-        );
-        await provider.sendAndConfirm(transaction);
+      const malicious = anchor.web3.Keypair.generate();
+      const transaction = new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: malicious.publicKey,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        }),
+      );
+      await provider.sendAndConfirm(transaction);
 
-        const keyId = keyIds[2];
-        const verificationPda = verificationPdas[2];
+      const keyId = keyIds[2];
+      const verificationPda = verificationPdas[2];
 
-        await program.methods
+      await expectAnchorError(
+        program.methods
           .addVerificationMethod(
             did,
             keyId,
             keyType,
             publicKeyMultibase,
             controller,
+            null,
+            null,
           )
           .accounts({
             didDocument: didPda,
@@ -184,10 +244,86 @@ describe('DID', () => {
             controller: malicious.publicKey,
           })
           .signers([malicious])
-          .rpc();
-      } catch (err) {
-        expect(err.error.errorCode.code === ANCHOR_ERROR_UNAUTHORIZED);
-      }
+          .rpc(),
+        ANCHOR_ERROR_CONSTRAINT_RAW,
+      );
+    });
+
+    it('Fail to add verification method with a key id of another DID', async () => {
+      const keyId = 'did:zuni:solana:other#key-0';
+      const [verificationPda] = findVerificationPdasWithKeyIds(
+        program.programId,
+        did,
+        [keyId],
+      );
+      await expectAnchorError(
+        program.methods
+          .addVerificationMethod(
+            did,
+            keyId,
+            keyType,
+            publicKeyMultibase,
+            controller,
+            null,
+            null,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: verificationPda,
+          })
+          .rpc(),
+        ANCHOR_ERROR_INVALID_KEY_ID,
+      );
+    });
+
+    it('Should add a passkey verification method', async () => {
+      const passkey = createECDH('prime256v1');
+      passkey.generateKeys();
+      const p256PublicKeyMultibase =
+        MULTIBASE_PREFIX.hex + passkey.getPublicKey('hex', 'compressed');
+      await program.methods
+        .addVerificationMethod(
+          did,
+          keyIds[3],
+          KEY_TYPE.p256,
+          p256PublicKeyMultibase,
+          controller,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPdas[3],
+        })
+        .rpc();
+
+      const verificationMethod = await program.account.verificationMethod.fetch(
+        verificationPdas[3],
+      );
+      expect(verificationMethod.rType).to.equal(KEY_TYPE.p256);
+    });
+
+    it('Fail to add a passkey verification method with an uncompressed key', async () => {
+      const passkey = createECDH('prime256v1');
+      passkey.generateKeys();
+      await expectAnchorError(
+        program.methods
+          .addVerificationMethod(
+            did,
+            keyIds[4],
+            KEY_TYPE.p256,
+            MULTIBASE_PREFIX.hex + passkey.getPublicKey('hex', 'uncompressed'),
+            controller,
+            null,
+            null,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: verificationPdas[4],
+          })
+          .rpc(),
+        ANCHOR_ERROR_INVALID_P256_PUBLIC_KEY,
+      );
     });
   });
 
@@ -207,13 +343,15 @@ describe('DID', () => {
       did,
       keyIds,
     );
-    const verificationRelationshipTypes = Object.values(
-      VERIFICATION_RELATIONSHIP,
-    );
+    // Only X25519 keys may be bound to keyAgreement, see below.
+    const verificationRelationshipTypes = [
+      VERIFICATION_RELATIONSHIP.authentication,
+      VERIFICATION_RELATIONSHIP.assertion,
+    ];
 
     before(async () => {
       await program.methods
-        .initializeDid(did)
+        .initializeDid(did, 0)
         .accounts({ didDocument: didPda })
         .rpc();
     });
@@ -228,6 +366,8 @@ describe('DID', () => {
           keyType,
           publicKeyMultibase,
           provider.wallet.publicKey,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -245,7 +385,13 @@ describe('DID', () => {
           program.programId,
         );
         await program.methods
-          .addVerificationRelationship(did, relationshipType.input, keyId)
+          .addVerificationRelationship(
+            did,
+            relationshipType.input,
+            keyId,
+            null,
+            null,
+          )
           .accounts({
             didDocument: didPda,
             verificationMethod: verificationPda,
@@ -256,11 +402,10 @@ describe('DID', () => {
         const verificationRelationship =
           await program.account.verificationRelationship.fetch(relationshipPda);
 
-        expect(verificationRelationship.did === did);
-        expect(verificationRelationship.keyId === keyId);
-        expect(
-          JSON.stringify(verificationRelationship.relationship) ===
-            JSON.stringify(relationshipType.input),
+        expect(verificationRelationship.did).to.equal(did);
+        expect(verificationRelationship.keyId).to.equal(keyId);
+        expect(verificationRelationship.relationship).to.deep.equal(
+          relationshipType.input,
         );
       }
     });
@@ -275,6 +420,8 @@ describe('DID', () => {
           keyType,
           publicKeyMultibase,
           provider.wallet.publicKey,
+          null,
+          null,
         )
         .accounts({
           didDocument: didPda,
@@ -283,29 +430,35 @@ describe('DID', () => {
         .rpc();
 
       for (const relationshipType of verificationRelationshipTypes) {
-        try {
-          const malicious = anchor.web3.Keypair.generate();
-          const transaction = new anchor.web3.Transaction().add(
-            anchor.web3.SystemProgram.transfer({
-              fromPubkey: provider.publicKey,
-              toPubkey: malicious.publicKey,
-              lamports: anchor.web3.LAMPORTS_PER_SOL,
-            }),
+        const malicious = anchor.web3.Keypair.generate();
+        const transaction = new anchor.web3.Transaction().add(
+          anchor.web3.SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: malicious.publicKey,
+            lamports: anchor.web3.LAMPORTS_PER_SOL,
+          }),
+        );
+        await provider.sendAndConfirm(transaction);
+
+        const [relationshipPda] =
+          anchor.web3.PublicKey.findProgramAddressSync(
+            [
+              Buffer.from(
+                keccak_256(did + relationshipType.discriminator + keyId),
+              ),
+            ],
+            program.programId,
           );
-          await provider.sendAndConfirm(transaction);
-
-          const [relationshipPda] =
-            anchor.web3.PublicKey.findProgramAddressSync(
-              [
-                Buffer.from(
-                  keccak_256(did + relationshipType.discriminator + keyId),
-                ),
-              ],
-              program.programId,
-            );
-
-          await program.methods
-            .addVerificationRelationship(did, relationshipType.input, keyId)
+
+        await expectAnchorError(
+          program.methods
+            .addVerificationRelationship(
+              did,
+              relationshipType.input,
+              keyId,
+              null,
+              null,
+            )
             .accounts({
               didDocument: didPda,
               verificationMethod: verificationPda,
@@ -313,11 +466,233 @@ describe('DID', () => {
               controller: malicious.publicKey,
             })
             .signers([malicious])
-            .rpc();
-        } catch (err) {
-          expect(err.error.errorCode.code === ANCHOR_ERROR_UNAUTHORIZED);
-        }
+            .rpc(),
+          ANCHOR_ERROR_CONSTRAINT_RAW,
+        );
       }
     });
+
+    it('Should add an X25519 key agreement key', async () => {
+      const keyId = keyIds[2];
+      const verificationPda = verificationPdas[2];
+      // Any 32 bytes stand in for an X25519 public key.
+      const x25519PublicKeyMultibase =
+        MULTIBASE_PREFIX.base58btc +
+        anchor.web3.Keypair.generate().publicKey.toBase58();
+      await program.methods
+        .addVerificationMethod(
+          did,
+          keyId,
+          KEY_TYPE.x25519,
+          x25519PublicKeyMultibase,
+          provider.wallet.publicKey,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+        })
+        .rpc();
+
+      const [relationshipPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(
+            keccak_256(
+              did +
+                VERIFICATION_RELATIONSHIP.keyAgreement.discriminator +
+                keyId,
+            ),
+          ),
+        ],
+        program.programId,
+      );
+      await program.methods
+        .addVerificationRelationship(
+          did,
+          VERIFICATION_RELATIONSHIP.keyAgreement.input,
+          keyId,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+          verificationRelationship: relationshipPda,
+        })
+        .rpc();
+
+      const verificationRelationship =
+        await program.account.verificationRelationship.fetch(relationshipPda);
+      expect(verificationRelationship.keyId).to.equal(keyId);
+    });
+
+    it('Fail to use a signing key for key agreement', async () => {
+      const keyId = keyIds[0];
+      const [relationshipPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(
+            keccak_256(
+              did +
+                VERIFICATION_RELATIONSHIP.keyAgreement.discriminator +
+                keyId,
+            ),
+          ),
+        ],
+        program.programId,
+      );
+      await expectAnchorError(
+        program.methods
+          .addVerificationRelationship(
+            did,
+            VERIFICATION_RELATIONSHIP.keyAgreement.input,
+            keyId,
+            null,
+            null,
+          )
+          .accounts({
+            didDocument: didPda,
+            verificationMethod: verificationPdas[0],
+            verificationRelationship: relationshipPda,
+          })
+          .rpc(),
+        ANCHOR_ERROR_RELATIONSHIP_NOT_SUPPORTED_BY_KEY_TYPE,
+      );
+    });
+  });
+
+  describe('transferDidController()', () => {
+    const did = 'did:zuni:solana:transferDidController';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const newController = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+    });
+
+    it('Should move DID to the new controller index', async () => {
+      const fromControllerIndex = findControllerDidIndexPda(
+        program.programId,
+        provider.wallet.publicKey,
+        0,
+      );
+      const toControllerIndex = findControllerDidIndexPda(
+        program.programId,
+        newController.publicKey,
+        0,
+      );
+      await program.methods
+        .transferDidController(did, newController.publicKey, 0, 0)
+        .accounts({
+          didDocument: didPda,
+          fromControllerIndex,
+          toControllerIndex,
+        })
+        .rpc();
+
+      const didDocument = await program.account.didDocument.fetch(didPda);
+      expect(didDocument.controller.toBase58()).to.equal(
+        newController.publicKey.toBase58(),
+      );
+      const fromIndex = await program.account.controllerDidIndex.fetch(
+        fromControllerIndex,
+      );
+      expect(
+        fromIndex.entries.map((entry) => entry.toBase58()),
+      ).to.not.include(didPda.toBase58());
+      const toIndex = await program.account.controllerDidIndex.fetch(
+        toControllerIndex,
+      );
+      expect(toIndex.entries.map((entry) => entry.toBase58())).to.include(
+        didPda.toBase58(),
+      );
+    });
+
+    it('Fail to transfer DID with no auth', async () => {
+      await expectAnchorError(
+        program.methods
+          .transferDidController(did, provider.wallet.publicKey, 0, 0)
+          .accounts({ didDocument: didPda })
+          .rpc(),
+        ANCHOR_ERROR_CONSTRAINT_RAW,
+      );
+    });
+  });
+
+  describe('setAlsoKnownAs()', () => {
+    const did = 'did:zuni:solana:setAlsoKnownAs';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const aliases = [
+      'did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK',
+      `did:pkh:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:${provider.wallet.publicKey.toBase58()}`,
+    ];
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+    });
+
+    it('Should link the DID to other identifiers', async () => {
+      await program.methods
+        .setAlsoKnownAs(did, aliases)
+        .accounts({ didDocument: didPda })
+        .rpc();
+
+      let didDocument = await program.account.didDocument.fetch(didPda);
+      expect(didDocument.alsoKnownAs).to.deep.equal(aliases);
+
+      await program.methods
+        .setAlsoKnownAs(did, [])
+        .accounts({ didDocument: didPda })
+        .rpc();
+      didDocument = await program.account.didDocument.fetch(didPda);
+      expect(didDocument.alsoKnownAs).to.deep.equal([]);
+    });
+
+    it('Fail to list the DID itself as an alias', async () => {
+      await expectAnchorError(
+        program.methods
+          .setAlsoKnownAs(did, [aliases[0], did])
+          .accounts({ didDocument: didPda })
+          .rpc(),
+        ANCHOR_ERROR_INVALID_ALSO_KNOWN_AS,
+      );
+    });
+  });
+
+  describe('migrateAccount()', () => {
+    const did = 'did:zuni:solana:migrateAccount';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+
+    before(async () => {
+      await program.methods
+        .initializeDid(did, 0)
+        .accounts({ didDocument: didPda })
+        .rpc();
+    });
+
+    it('Fail to migrate an account already on the current layout', async () => {
+      await expectAnchorError(
+        program.methods
+          .migrateAccount()
+          .accounts({ account: didPda })
+          .rpc(),
+        ANCHOR_ERROR_ACCOUNT_ALREADY_MIGRATED,
+      );
+    });
   });
 });
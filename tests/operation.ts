@@ -0,0 +1,274 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import {
+  KEY_TYPE,
+  MULTIBASE_PREFIX,
+  ensureRegistryConfig,
+  expectAnchorError,
+  findDelegationPda,
+  findPendingOperationPda,
+  findVerificationPdasWithKeyIds,
+} from './utils';
+
+const ANCHOR_ERROR_OPERATION_TIME_LOCKED = 'OperationTimeLocked';
+const ANCHOR_ERROR_OPERATION_NOT_EXECUTABLE = 'OperationNotExecutable';
+const ANCHOR_ERROR_OPERATION_MISMATCH = 'OperationMismatch';
+
+const ONE_DAY = 24 * 60 * 60 * 1000;
+
+describe('Operation', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const controller = provider.wallet.publicKey;
+  const keyType = KEY_TYPE.ed25519;
+  const publicKeyMultibase =
+    MULTIBASE_PREFIX.base58btc + controller.toBase58();
+
+  const createDid = async (did: string, operationDelay: number) => {
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    if (operationDelay > 0) {
+      await program.methods
+        .setOperationDelay(did, new anchor.BN(operationDelay))
+        .accounts({ didDocument: didPda })
+        .rpc();
+    }
+    return didPda;
+  };
+
+  const addVerificationMethodOperation = (keyId: string) => ({
+    addVerificationMethod: {
+      keyId,
+      type: keyType,
+      publicKeyMultibase,
+      controller,
+      validFrom: null,
+      validUntil: null,
+    },
+  });
+
+  const proposeOperation = (
+    did: string,
+    didPda: anchor.web3.PublicKey,
+    id: number,
+    operation,
+  ) =>
+    program.methods
+      .proposeOperation(did, new anchor.BN(id), operation)
+      .accounts({
+        pendingOperation: findPendingOperationPda(
+          program.programId,
+          didPda,
+          id,
+        ),
+        didDocument: didPda,
+      })
+      .rpc();
+
+  const executeAddVerificationMethod = (
+    did: string,
+    didPda: anchor.web3.PublicKey,
+    keyId: string,
+    id: number,
+  ) => {
+    const [verificationPda] = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      [keyId],
+    );
+    return program.methods
+      .executeAddVerificationMethod(
+        did,
+        keyId,
+        keyType,
+        publicKeyMultibase,
+        controller,
+        null,
+        null,
+        new anchor.BN(id),
+      )
+      .accounts({
+        operation: {
+          verificationMethod: verificationPda,
+          didDocument: didPda,
+        },
+        pendingOperation: findPendingOperationPda(
+          program.programId,
+          didPda,
+          id,
+        ),
+      })
+      .rpc();
+  };
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+  });
+
+  it('Should refuse direct changes once a delay is set', async () => {
+    const did = 'did:zuni:solana:timeLocked';
+    const didPda = await createDid(did, ONE_DAY);
+    const keyId = did + '#key-0';
+    const [verificationPda] = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      [keyId],
+    );
+
+    await expectAnchorError(
+      program.methods
+        .addVerificationMethod(
+          did,
+          keyId,
+          keyType,
+          publicKeyMultibase,
+          controller,
+          null,
+          null,
+        )
+        .accounts({
+          didDocument: didPda,
+          verificationMethod: verificationPda,
+        })
+        .rpc(),
+      ANCHOR_ERROR_OPERATION_TIME_LOCKED,
+    );
+
+    // Lowering the delay is itself time-locked.
+    await expectAnchorError(
+      program.methods
+        .setOperationDelay(did, new anchor.BN(0))
+        .accounts({ didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_OPERATION_TIME_LOCKED,
+    );
+  });
+
+  it('Should refuse guardian, alias and delegation changes once a delay is set', async () => {
+    const did = 'did:zuni:solana:timeLockedSettings';
+    const didPda = await createDid(did, ONE_DAY);
+    const delegate = anchor.web3.Keypair.generate().publicKey;
+
+    await expectAnchorError(
+      program.methods
+        .setGuardians(
+          did,
+          [{ key: [anchor.web3.Keypair.generate().publicKey] }],
+          1,
+          new anchor.BN(0),
+        )
+        .accounts({ didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_OPERATION_TIME_LOCKED,
+    );
+    await expectAnchorError(
+      program.methods
+        .setAlsoKnownAs(did, [
+          `did:pkh:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:${controller.toBase58()}`,
+        ])
+        .accounts({ didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_OPERATION_TIME_LOCKED,
+    );
+    await expectAnchorError(
+      program.methods
+        .createDelegation(
+          did,
+          delegate,
+          new anchor.BN(0),
+          '',
+          null,
+          false,
+          null,
+        )
+        .accounts({
+          delegation: findDelegationPda(program.programId, didPda, delegate, 0),
+          didDocument: didPda,
+        })
+        .rpc(),
+      ANCHOR_ERROR_OPERATION_TIME_LOCKED,
+    );
+  });
+
+  it('Should execute a proposed operation once its delay is over', async () => {
+    const did = 'did:zuni:solana:operationExecuted';
+    const didPda = await createDid(did, 0);
+    const keyId = did + '#key-0';
+
+    await proposeOperation(
+      did,
+      didPda,
+      0,
+      addVerificationMethodOperation(keyId),
+    );
+
+    // The arguments must match the proposal.
+    await expectAnchorError(
+      executeAddVerificationMethod(did, didPda, did + '#key-1', 0),
+      ANCHOR_ERROR_OPERATION_MISMATCH,
+    );
+
+    await executeAddVerificationMethod(did, didPda, keyId, 0);
+    const [verificationPda] = findVerificationPdasWithKeyIds(
+      program.programId,
+      did,
+      [keyId],
+    );
+    const method = await program.account.verificationMethod.fetch(
+      verificationPda,
+    );
+    expect(method.keyId).to.equal(keyId);
+    const pendingOperation = await provider.connection.getAccountInfo(
+      findPendingOperationPda(program.programId, didPda, 0),
+    );
+    expect(pendingOperation).to.equal(null);
+  });
+
+  it('Should let the controller cancel a pending operation', async () => {
+    const did = 'did:zuni:solana:operationCancelled';
+    const didPda = await createDid(did, ONE_DAY);
+    const keyId = did + '#key-0';
+
+    await proposeOperation(
+      did,
+      didPda,
+      7,
+      addVerificationMethodOperation(keyId),
+    );
+    const pending = await program.account.pendingOperation.fetch(
+      findPendingOperationPda(program.programId, didPda, 7),
+    );
+    expect(pending.executableAt.toNumber()).to.be.greaterThan(Date.now());
+
+    await expectAnchorError(
+      executeAddVerificationMethod(did, didPda, keyId, 7),
+      ANCHOR_ERROR_OPERATION_NOT_EXECUTABLE,
+    );
+
+    await program.methods
+      .cancelOperation(did, new anchor.BN(7))
+      .accounts({
+        pendingOperation: findPendingOperationPda(program.programId, didPda, 7),
+        didDocument: didPda,
+      })
+      .rpc();
+    const account = await provider.connection.getAccountInfo(
+      findPendingOperationPda(program.programId, didPda, 7),
+    );
+    expect(account).to.equal(null);
+  });
+});
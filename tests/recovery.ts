@@ -0,0 +1,285 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import {
+  ensureRegistryConfig,
+  expectAnchorError,
+  findControllerDidIndexPda,
+  findRecoveryPda,
+} from './utils';
+
+const ANCHOR_ERROR_NOT_GUARDIAN = 'NotGuardian';
+const ANCHOR_ERROR_RECOVERY_NOT_EXECUTABLE = 'RecoveryNotExecutable';
+const ANCHOR_ERROR_INVALID_GUARDIAN_THRESHOLD = 'InvalidGuardianThreshold';
+const ANCHOR_ERROR_UNAUTHORIZED = 'Unauthorized';
+const ANCHOR_ERROR_RECOVERY_NOT_EXPIRED = 'RecoveryNotExpired';
+
+describe('Recovery', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const guardians = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  // Creates a DID controlled by the provider wallet, guarded 2-of-3 by `guardians`.
+  const createGuardedDid = async (did: string, recoveryDelay: number) => {
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    await program.methods
+      .setGuardians(
+        did,
+        guardians.map((guardian) => ({ key: [guardian.publicKey] })),
+        2,
+        new anchor.BN(recoveryDelay),
+      )
+      .accounts({ didDocument: didPda })
+      .rpc();
+    return didPda;
+  };
+
+  const initiateAndApprove = async (
+    did: string,
+    didPda: anchor.web3.PublicKey,
+    newController: anchor.web3.PublicKey,
+  ) => {
+    const recovery = findRecoveryPda(program.programId, didPda);
+    await program.methods
+      .initiateRecovery(did, newController)
+      .accounts({
+        recovery,
+        didDocument: didPda,
+        guardianDidDocument: null,
+        guardian: guardians[0].publicKey,
+      })
+      .signers([guardians[0]])
+      .rpc();
+    await program.methods
+      .approveRecovery(did)
+      .accounts({
+        recovery,
+        didDocument: didPda,
+        guardianDidDocument: null,
+        guardian: guardians[1].publicKey,
+      })
+      .signers([guardians[1]])
+      .rpc();
+    return recovery;
+  };
+
+  const executeRecovery = (
+    did: string,
+    didPda: anchor.web3.PublicKey,
+    newController: anchor.web3.PublicKey,
+  ) =>
+    program.methods
+      .executeRecovery(did, 0, 0)
+      .accounts({
+        recovery: findRecoveryPda(program.programId, didPda),
+        didDocument: didPda,
+        fromControllerIndex: findControllerDidIndexPda(
+          program.programId,
+          provider.wallet.publicKey,
+          0,
+        ),
+        toControllerIndex: findControllerDidIndexPda(
+          program.programId,
+          newController,
+          0,
+        ),
+        payer: provider.wallet.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+  });
+
+  it('Should hand the DID over once guardians approved', async () => {
+    const did = 'did:zuni:solana:recovered';
+    const didPda = await createGuardedDid(did, 0);
+    const newController = anchor.web3.Keypair.generate();
+
+    const recovery = await initiateAndApprove(
+      did,
+      didPda,
+      newController.publicKey,
+    );
+    const pending = await program.account.recovery.fetch(recovery);
+    expect(pending.approvals).to.have.lengthOf(2);
+    expect(pending.executableAt).to.not.equal(null);
+
+    await executeRecovery(did, didPda, newController.publicKey);
+    const didDocument = await program.account.didDocument.fetch(didPda);
+    expect(didDocument.controller.toBase58()).to.equal(
+      newController.publicKey.toBase58(),
+    );
+    const account = await provider.connection.getAccountInfo(recovery);
+    expect(account).to.equal(null);
+  });
+
+  it('Should let the controller cancel during the waiting period', async () => {
+    const did = 'did:zuni:solana:recoveryCancelled';
+    const didPda = await createGuardedDid(did, 24 * 60 * 60 * 1000);
+    const newController = anchor.web3.Keypair.generate();
+
+    const recovery = await initiateAndApprove(
+      did,
+      didPda,
+      newController.publicKey,
+    );
+    await expectAnchorError(
+      executeRecovery(did, didPda, newController.publicKey),
+      ANCHOR_ERROR_RECOVERY_NOT_EXECUTABLE,
+    );
+
+    await program.methods
+      .cancelRecovery(did)
+      .accounts({
+        recovery,
+        didDocument: didPda,
+        payer: provider.wallet.publicKey,
+        controller: provider.wallet.publicKey,
+      })
+      .rpc();
+    const didDocument = await program.account.didDocument.fetch(didPda);
+    expect(didDocument.controller.toBase58()).to.equal(
+      provider.wallet.publicKey.toBase58(),
+    );
+  });
+
+  it('Fail to execute a recovery once all guardians are removed', async () => {
+    const did = 'did:zuni:solana:recoveryGuardiansRemoved';
+    const didPda = await createGuardedDid(did, 0);
+    const newController = anchor.web3.Keypair.generate();
+    await initiateAndApprove(did, didPda, newController.publicKey);
+
+    await program.methods
+      .setGuardians(did, [], 0, new anchor.BN(0))
+      .accounts({ didDocument: didPda })
+      .rpc();
+    await expectAnchorError(
+      executeRecovery(did, didPda, newController.publicKey),
+      ANCHOR_ERROR_RECOVERY_NOT_EXECUTABLE,
+    );
+  });
+
+  it('Fail to cancel a recovery as someone other than the controller', async () => {
+    const did = 'did:zuni:solana:recoveryCancelNoAuth';
+    const didPda = await createGuardedDid(did, 24 * 60 * 60 * 1000);
+    const malicious = anchor.web3.Keypair.generate();
+    const recovery = await initiateAndApprove(
+      did,
+      didPda,
+      malicious.publicKey,
+    );
+
+    await expectAnchorError(
+      program.methods
+        .cancelRecovery(did)
+        .accounts({
+          recovery,
+          didDocument: didPda,
+          payer: provider.wallet.publicKey,
+          controller: malicious.publicKey,
+        })
+        .signers([malicious])
+        .rpc(),
+      ANCHOR_ERROR_UNAUTHORIZED,
+    );
+  });
+
+  it('Fail to close a recovery guardians may still approve', async () => {
+    const did = 'did:zuni:solana:recoveryNotExpired';
+    const didPda = await createGuardedDid(did, 0);
+    const recovery = findRecoveryPda(program.programId, didPda);
+    // A lone guardian starts a recovery the others do not approve.
+    await program.methods
+      .initiateRecovery(did, guardians[0].publicKey)
+      .accounts({
+        recovery,
+        didDocument: didPda,
+        guardianDidDocument: null,
+        guardian: guardians[0].publicKey,
+      })
+      .signers([guardians[0]])
+      .rpc();
+    const pending = await program.account.recovery.fetch(recovery);
+    expect(pending.executableAt).to.equal(null);
+    expect(pending.expireAt.toNumber()).to.be.greaterThan(Date.now());
+
+    await expectAnchorError(
+      program.methods
+        .cancelRecovery(did)
+        .accounts({
+          recovery,
+          didDocument: didPda,
+          payer: provider.wallet.publicKey,
+          controller: null,
+        })
+        .rpc(),
+      ANCHOR_ERROR_RECOVERY_NOT_EXPIRED,
+    );
+  });
+
+  it('Fail to approve a recovery as a non guardian', async () => {
+    const did = 'did:zuni:solana:recoveryNoGuardian';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    const malicious = anchor.web3.Keypair.generate();
+    await createGuardedDid(did, 0);
+    await expectAnchorError(
+      program.methods
+        .initiateRecovery(did, malicious.publicKey)
+        .accounts({
+          recovery: findRecoveryPda(program.programId, didPda),
+          didDocument: didPda,
+          guardianDidDocument: null,
+          guardian: malicious.publicKey,
+        })
+        .signers([malicious])
+        .rpc(),
+      ANCHOR_ERROR_NOT_GUARDIAN,
+    );
+  });
+
+  it('Fail to set a threshold above the number of guardians', async () => {
+    const did = 'did:zuni:solana:recoveryThreshold';
+    const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak_256(did)],
+      program.programId,
+    );
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    await expectAnchorError(
+      program.methods
+        .setGuardians(
+          did,
+          [{ key: [guardians[0].publicKey] }],
+          2,
+          new anchor.BN(0),
+        )
+        .accounts({ didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_INVALID_GUARDIAN_THRESHOLD,
+    );
+  });
+});
@@ -0,0 +1,269 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { sha256 } from '@noble/hashes/sha256';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../target/types/verifiable_data_registry';
+import {
+  ensureRegistryConfig,
+  expectAnchorError,
+  findClosedCredentialPda,
+  findCredentialSchemaPda,
+  findDelegationPda,
+  findVerificationSchemaPda,
+} from './utils';
+
+const ANCHOR_ERROR_CREDENTIAL_SCHEMA_DEPRECATED = 'CredentialSchemaDeprecated';
+const ANCHOR_ERROR_CREDENTIAL_SCHEMA_ALREADY_DEPRECATED =
+  'CredentialSchemaAlreadyDeprecated';
+const ANCHOR_ERROR_INVALID_SCHEMA_FIELD = 'InvalidSchemaField';
+const ANCHOR_ERROR_INVALID_REQUESTED_FIELD = 'InvalidRequestedField';
+const ANCHOR_ERROR_VERIFICATION_SCHEMA_ALREADY_DEPRECATED =
+  'VerificationSchemaAlreadyDeprecated';
+
+describe('Credential schema', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const did = 'did:zuni:solana:schema';
+  const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [keccak_256(did)],
+    program.programId,
+  );
+  const schemaId = 'https://zuni.example/schemas/degree';
+  const schemaPda = findCredentialSchemaPda(
+    program.programId,
+    didPda,
+    schemaId,
+    1,
+  );
+  const delegate = anchor.web3.Keypair.generate();
+  const delegationPda = findDelegationPda(
+    program.programId,
+    didPda,
+    delegate.publicKey,
+    0,
+  );
+
+  const addDelegatedCredential = (credentialId: string) =>
+    program.methods
+      .addDelegatedCredential(did, credentialId, null, null, null, 0)
+      .accounts({
+        credentialState: anchor.web3.PublicKey.findProgramAddressSync(
+          [keccak_256(credentialId)],
+          program.programId,
+        )[0],
+        closedCredential: findClosedCredentialPda(
+          program.programId,
+          credentialId,
+        ),
+        didDocument: didPda,
+        delegation: delegationPda,
+        credentialSchema: schemaPda,
+        delegate: delegate.publicKey,
+      })
+      .signers([delegate])
+      .rpc();
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+    await program.methods
+      .createDelegation(
+        did,
+        delegate.publicKey,
+        new anchor.BN(0),
+        schemaId,
+        null,
+        false,
+        null,
+      )
+      .accounts({ delegation: delegationPda, didDocument: didPda })
+      .rpc();
+  });
+
+  it('Should create a schema and issue credentials against it', async () => {
+    const jsonSchema = JSON.stringify({
+      type: 'object',
+      properties: { degree: { type: 'string' } },
+    });
+    await program.methods
+      .createCredentialSchema(
+        did,
+        schemaId,
+        1,
+        'Degree',
+        [
+          { name: 'degree', fieldType: { string: {} } },
+          { name: 'graduatedAt', fieldType: { date: {} } },
+        ],
+        [...sha256(jsonSchema)],
+      )
+      .accounts({ credentialSchema: schemaPda, didDocument: didPda })
+      .rpc();
+
+    const schema = await program.account.credentialSchema.fetch(schemaPda);
+    expect(schema.author.toBase58()).to.equal(didPda.toBase58());
+    expect(schema.fields).to.have.lengthOf(2);
+    expect(schema.deprecated).to.equal(false);
+
+    await addDelegatedCredential('degree:1');
+    const credentialState = await program.account.credentialState.fetch(
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [keccak_256('degree:1')],
+        program.programId,
+      )[0],
+    );
+    expect(credentialState.schema?.toBase58()).to.equal(schemaPda.toBase58());
+  });
+
+  it('Fail to create a schema with duplicate fields', async () => {
+    const otherSchemaId = `${schemaId}-duplicate`;
+    await expectAnchorError(
+      program.methods
+        .createCredentialSchema(
+          did,
+          otherSchemaId,
+          1,
+          'Duplicate',
+          [
+            { name: 'degree', fieldType: { string: {} } },
+            { name: 'degree', fieldType: { integer: {} } },
+          ],
+          new Array(32).fill(0),
+        )
+        .accounts({
+          credentialSchema: findCredentialSchemaPda(
+            program.programId,
+            didPda,
+            otherSchemaId,
+            1,
+          ),
+          didDocument: didPda,
+        })
+        .rpc(),
+      ANCHOR_ERROR_INVALID_SCHEMA_FIELD,
+    );
+  });
+
+  it('Fail to issue against a deprecated schema', async () => {
+    await program.methods
+      .deprecateCredentialSchema(did, schemaId, 1)
+      .accounts({ credentialSchema: schemaPda, didDocument: didPda })
+      .rpc();
+    const schema = await program.account.credentialSchema.fetch(schemaPda);
+    expect(schema.deprecated).to.equal(true);
+
+    await expectAnchorError(
+      addDelegatedCredential('degree:2'),
+      ANCHOR_ERROR_CREDENTIAL_SCHEMA_DEPRECATED,
+    );
+
+    await expectAnchorError(
+      program.methods
+        .deprecateCredentialSchema(did, schemaId, 1)
+        .accounts({ credentialSchema: schemaPda, didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_CREDENTIAL_SCHEMA_ALREADY_DEPRECATED,
+    );
+  });
+});
+
+describe('Verification schema', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .VerifiableDataRegistry as Program<VerifiableDataRegistry>;
+
+  const did = 'did:zuni:solana:verifier';
+  const [didPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [keccak_256(did)],
+    program.programId,
+  );
+  // `fieldMerkleRoot` of a `SchemaCredentialCheck`, as 32 big-endian bytes.
+  const checkRoot = [
+    ...new anchor.BN(
+      '7353183431244536536573612469101768404624432487262452437981389880213428164815',
+    ).toArrayLike(Buffer, 'be', 32),
+  ];
+
+  before(async () => {
+    await ensureRegistryConfig(program);
+    await program.methods
+      .initializeDid(did, 0)
+      .accounts({ didDocument: didPda })
+      .rpc();
+  });
+
+  it('Should publish and deprecate a presentation request', async () => {
+    const schemaId = 'alumni-discount';
+    const schemaPda = findVerificationSchemaPda(
+      program.programId,
+      didPda,
+      schemaId,
+      1,
+    );
+    await program.methods
+      .createVerificationSchema(
+        did,
+        schemaId,
+        1,
+        'Alumni discount',
+        [checkRoot],
+        ['0:degree'],
+      )
+      .accounts({ verificationSchema: schemaPda, didDocument: didPda })
+      .rpc();
+
+    const schema = await program.account.verificationSchema.fetch(schemaPda);
+    expect(schema.verifier.toBase58()).to.equal(didPda.toBase58());
+    expect(schema.checkRoots[0]).to.deep.equal(checkRoot);
+    expect(schema.requestedFields).to.deep.equal(['0:degree']);
+
+    await program.methods
+      .deprecateVerificationSchema(did, schemaId, 1)
+      .accounts({ verificationSchema: schemaPda, didDocument: didPda })
+      .rpc();
+    await expectAnchorError(
+      program.methods
+        .deprecateVerificationSchema(did, schemaId, 1)
+        .accounts({ verificationSchema: schemaPda, didDocument: didPda })
+        .rpc(),
+      ANCHOR_ERROR_VERIFICATION_SCHEMA_ALREADY_DEPRECATED,
+    );
+  });
+
+  it('Fail with a requested field outside the credential checks', async () => {
+    const schemaId = 'out-of-range';
+    await expectAnchorError(
+      program.methods
+        .createVerificationSchema(
+          did,
+          schemaId,
+          1,
+          'Out of range',
+          [checkRoot],
+          ['1:degree'],
+        )
+        .accounts({
+          verificationSchema: findVerificationSchemaPda(
+            program.programId,
+            didPda,
+            schemaId,
+            1,
+          ),
+          didDocument: didPda,
+        })
+        .rpc(),
+      ANCHOR_ERROR_INVALID_REQUESTED_FIELD,
+    );
+  });
+});
@@ -1,5 +1,7 @@
-import { web3 } from '@coral-xyz/anchor';
+import { BN, Program, web3 } from '@coral-xyz/anchor';
 import { keccak_256 } from '@noble/hashes/sha3';
+import { expect } from 'chai';
+import { VerifiableDataRegistry } from '../../target/types/verifiable_data_registry';
 
 export const MULTIBASE_PREFIX = {
   base64: 'm',
@@ -10,6 +12,9 @@ export const MULTIBASE_PREFIX = {
 export const KEY_TYPE = {
   secp256k1: 'EcdsaSecp256k1VerificationKey2019',
   ed25519: 'Ed25519VerificationKey2018',
+  solana: 'SolanaMethod2021',
+  x25519: 'X25519KeyAgreementKey2020',
+  p256: 'JsonWebKey2020',
 };
 
 export const genKeyIds = (numberOfKeys: number, did: string) => {
@@ -35,3 +40,274 @@ export const findVerificationPdasWithKeyIds = (
   }
   return verificationPdas;
 };
+
+const pageSeed = (page: number) => {
+  const seed = Buffer.alloc(4);
+  seed.writeUInt32LE(page);
+  return seed;
+};
+
+export const findControllerDidIndexPda = (
+  programId: web3.PublicKey,
+  controller: web3.PublicKey,
+  page: number,
+) => {
+  const [controllerIndexPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('controller_did_index'),
+      controller.toBuffer(),
+      pageSeed(page),
+    ],
+    programId,
+  );
+  return controllerIndexPda;
+};
+
+export const findIssuerCredentialIndexPda = (
+  programId: web3.PublicKey,
+  issuerDidPda: web3.PublicKey,
+  page: number,
+) => {
+  const [issuerIndexPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('issuer_credential_index'),
+      issuerDidPda.toBuffer(),
+      pageSeed(page),
+    ],
+    programId,
+  );
+  return issuerIndexPda;
+};
+
+export const findClosedCredentialPda = (
+  programId: web3.PublicKey,
+  credentialId: string,
+) => {
+  const [closedCredentialPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('closed_credential'), Buffer.from(keccak_256(credentialId))],
+    programId,
+  );
+  return closedCredentialPda;
+};
+
+// u32 little-endian length, then the bytes, as Borsh encodes strings.
+const lengthPrefixed = (value: string) => {
+  const bytes = Buffer.from(value);
+  const length = Buffer.alloc(4);
+  length.writeUInt32LE(bytes.length);
+  return Buffer.concat([length, bytes]);
+};
+
+// Presence byte, then the value, as Borsh encodes options.
+const optional = (value: Buffer | null) =>
+  value === null ? Buffer.from([0]) : Buffer.concat([Buffer.from([1]), value]);
+
+// Message the issuer signs to add `credentialId`, without holder or schema.
+export const issuanceMessage = (
+  credentialId: string,
+  expireAt: number | null,
+  fieldMerkleRoot: number[] | null = null,
+) =>
+  Buffer.concat([
+    Buffer.from('ZUNI_ISSUE_V1'),
+    lengthPrefixed(credentialId),
+    optional(
+      expireAt === null ? null : new BN(expireAt).toArrayLike(Buffer, 'le', 8),
+    ),
+    optional(null), // holder
+    optional(null), // schema
+    optional(fieldMerkleRoot === null ? null : Buffer.from(fieldMerkleRoot)),
+  ]);
+
+// Message the issuer `did` signs to revoke `credentialId`.
+export const revocationMessage = (did: string, credentialId: string) =>
+  Buffer.concat([
+    Buffer.from('ZUNI_REVOKE_V1'),
+    lengthPrefixed(did),
+    lengthPrefixed(credentialId),
+  ]);
+
+// Message the issuer signs to close `credentialId`.
+export const closeMessage = (credentialId: string) =>
+  Buffer.concat([Buffer.from('ZUNI_CLOSE_V1'), lengthPrefixed(credentialId)]);
+
+// Message the holder signs to acknowledge `credentialId`.
+export const acknowledgementMessage = (credentialId: string) =>
+  Buffer.concat([
+    Buffer.from('ZUNI_ACKNOWLEDGE_V1'),
+    lengthPrefixed(credentialId),
+  ]);
+
+export const findDelegationPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  delegate: web3.PublicKey,
+  id: number,
+) => {
+  const [delegationPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('delegation'),
+      didPda.toBuffer(),
+      delegate.toBuffer(),
+      new BN(id).toArrayLike(Buffer, 'le', 8),
+    ],
+    programId,
+  );
+  return delegationPda;
+};
+
+export const findCredentialSchemaPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  schemaId: string,
+  schemaVersion: number,
+) => {
+  const [credentialSchemaPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('credential_schema'),
+      didPda.toBuffer(),
+      Buffer.from(keccak_256(schemaId)),
+      pageSeed(schemaVersion),
+    ],
+    programId,
+  );
+  return credentialSchemaPda;
+};
+
+export const findVerificationSchemaPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  schemaId: string,
+  schemaVersion: number,
+) => {
+  const [verificationSchemaPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('verification_schema'),
+      didPda.toBuffer(),
+      Buffer.from(keccak_256(schemaId)),
+      pageSeed(schemaVersion),
+    ],
+    programId,
+  );
+  return verificationSchemaPda;
+};
+
+export const findRecoveryPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+) => {
+  const [recoveryPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('recovery'), didPda.toBuffer()],
+    programId,
+  );
+  return recoveryPda;
+};
+
+export const findDidChangeLogPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+) => {
+  const [didChangeLogPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('did_change_log'), didPda.toBuffer()],
+    programId,
+  );
+  return didChangeLogPda;
+};
+
+export const findPendingOperationPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  id: number,
+) => {
+  const [pendingOperationPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('pending_operation'),
+      didPda.toBuffer(),
+      new BN(id).toArrayLike(Buffer, 'le', 8),
+    ],
+    programId,
+  );
+  return pendingOperationPda;
+};
+
+export const findAuthChallengePda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  sessionKey: web3.PublicKey,
+) => {
+  const [authChallengePda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('auth_challenge'), didPda.toBuffer(), sessionKey.toBuffer()],
+    programId,
+  );
+  return authChallengePda;
+};
+
+export const findAuthSessionPda = (
+  programId: web3.PublicKey,
+  didPda: web3.PublicKey,
+  sessionKey: web3.PublicKey,
+) => {
+  const [authSessionPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('auth_session'), didPda.toBuffer(), sessionKey.toBuffer()],
+    programId,
+  );
+  return authSessionPda;
+};
+
+export const findRegistryConfigPda = (programId: web3.PublicKey) => {
+  const [registryConfigPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('registry_config')],
+    programId,
+  );
+  return registryConfigPda;
+};
+
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new web3.PublicKey(
+  'BPFLoaderUpgradeab1e11111111111111111111111',
+);
+
+// The registry config is a singleton shared by every test file, created once by the
+// provider wallet, which is the program upgrade authority in tests.
+export const ensureRegistryConfig = async (
+  program: Program<VerifiableDataRegistry>,
+) => {
+  const registryConfigPda = findRegistryConfigPda(program.programId);
+  const account = await program.provider.connection.getAccountInfo(
+    registryConfigPda,
+  );
+  if (account === null) {
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+    );
+    await program.methods
+      .initializeConfig(
+        program.provider.publicKey,
+        [
+          KEY_TYPE.secp256k1,
+          KEY_TYPE.ed25519,
+          KEY_TYPE.solana,
+          KEY_TYPE.x25519,
+          KEY_TYPE.p256,
+        ],
+        null,
+      )
+      .accounts({ program: program.programId, programData })
+      .rpc();
+  }
+  return registryConfigPda;
+};
+
+// Fails the test unless `promise` is rejected with the Anchor error `code`.
+export const expectAnchorError = async (
+  promise: Promise<unknown>,
+  code: string,
+) => {
+  try {
+    await promise;
+  } catch (err) {
+    expect(err.error?.errorCode?.code, String(err)).to.equal(code);
+    return;
+  }
+  expect.fail(`Expected ${code}`);
+};